@@ -1,7 +1,11 @@
 use clap::Parser;
+use consensus_core::params::SIMNET_PARAMS;
 use hashes::Hash;
+use kaspa_core::{debug, error, info};
 use rpc_core::api::rpc::RpcApi;
-use rpc_core::{GetBlockRequest, GetInfoRequest, RpcHash};
+use rpc_core::{
+    GetBlockRequest, GetCurrentNetworkRequest, GetInfoRequest, GetMetricsRequest, GetSelectedTipHashRequest, PingRequest, RpcHash,
+};
 use rpc_grpc::client::RpcApiGrpc;
 use std::str::FromStr;
 use tokio::time::{sleep, Duration};
@@ -19,6 +23,7 @@ struct Args {
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     let args = Args::parse();
+    kaspa_core::log::init_logger(&std::env::var("KASPA_LOG").unwrap_or_default())?;
 
     // -------------------------------------------------------------------------------------------
     println!("************************");
@@ -26,12 +31,12 @@ async fn main() -> Result<(), Error> {
     println!("************************");
     // -------------------------------------------------------------------------------------------
 
-    let mut c = RpcApiGrpc::connect("http://[::1]:10000".to_string()).await?;
+    let mut c = RpcApiGrpc::connect(format!("http://[::1]:{}", SIMNET_PARAMS.default_rpc_port)).await?;
     c.start().await;
-    println!("connection to rust prototype established");
+    info!("connection to rust prototype established");
 
     let c_listener = c.register_new_listener(None);
-    let c_listener_recv = c_listener.recv_channel.clone();
+    let c_listener_recv = c_listener.clone();
 
     // Launch a reporting task
     tokio::spawn(async move {
@@ -41,12 +46,12 @@ async fn main() -> Result<(), Error> {
             }
             match c_listener_recv.recv().await {
                 Ok(notification) => {
-                    println!("RUST PROTOTYPE Notification received: {}", &*notification)
+                    info!("RUST PROTOTYPE Notification received: {}", &*notification)
                 }
-                Err(err) => println!("Error in notification reporting loop: {:?}", err),
+                Err(err) => error!("Error in notification reporting loop: {:?}", err),
             }
         }
-        println!("Exiting notification reporting loop");
+        debug!("Exiting notification reporting loop");
     });
 
     // Register for notifications
@@ -70,11 +75,31 @@ async fn main() -> Result<(), Error> {
     let response = c.get_block(request).await;
     println!("RESPONSE RP = {:#?}", response);
 
+    println!("REQUEST RP current network");
+    let request = GetCurrentNetworkRequest {};
+    let response = c.get_current_network(request).await;
+    println!("RESPONSE RP = {:#?}", response);
+
+    println!("REQUEST RP ping");
+    let request = PingRequest {};
+    let response = c.ping(request).await;
+    println!("RESPONSE RP = {:#?}", response);
+
+    println!("REQUEST RP selected tip hash");
+    let request = GetSelectedTipHashRequest {};
+    let response = c.get_selected_tip_hash(request).await;
+    println!("RESPONSE RP = {:#?}", response);
+
     println!("REQUEST RP info");
     let request = GetInfoRequest {};
     let response = c.get_info(request).await;
     println!("RESPONSE RP = {:#?}", response);
 
+    println!("REQUEST RP metrics");
+    let request = GetMetricsRequest {};
+    let response = c.get_metrics(request).await;
+    println!("RESPONSE RP = {:#?}", response);
+
     // -------------------------------------------------------------------------------------------
     println!("***********************");
     println!("***  GO KASPA NODE  ***");
@@ -83,10 +108,10 @@ async fn main() -> Result<(), Error> {
 
     let mut c_public = RpcApiGrpc::connect(args.address).await?;
     c_public.start().await;
-    println!("connection to go kaspad established");
+    info!("connection to go kaspad established");
 
     let c_public_listener = c_public.register_new_listener(None);
-    let c_public_listener_recv = c_public_listener.recv_channel.clone();
+    let c_public_listener_recv = c_public_listener.clone();
 
     // Launch a reporting task
     tokio::spawn(async move {
@@ -95,11 +120,11 @@ async fn main() -> Result<(), Error> {
                 break;
             }
             match c_public_listener_recv.recv().await {
-                Ok(notification) => println!("KASPAD Notification received: {}", &*notification),
-                Err(err) => println!("Error in notification reporting loop: {:?}", err),
+                Ok(notification) => info!("KASPAD Notification received: {}", &*notification),
+                Err(err) => error!("Error in notification reporting loop: {:?}", err),
             }
         }
-        println!("Exiting notification reporting loop");
+        debug!("Exiting notification reporting loop");
     });
 
     // Register for notifications
@@ -143,26 +168,26 @@ async fn main() -> Result<(), Error> {
     // println!("RESPONSE = {:#?}", response);
 
     sleep(Duration::from_millis(2500)).await;
-    println!("Stop getting notifications from RUST PROTOTYPE");
+    info!("Stop getting notifications from RUST PROTOTYPE");
     c.stop_notify(c_listener.id, rpc_core::NotificationType::BlockAdded).await?;
 
     sleep(Duration::from_millis(3000)).await;
 
     // Closing connections
-    println!("Shutting down RUST PROTOTYPE connected client");
+    info!("Shutting down RUST PROTOTYPE connected client");
     c.unregister_listener(c_listener.id).await?;
     c.stop().await?;
-    c.shutdown().await?;
+    c.disconnect().await?;
 
-    println!("Shutting down GO KASPA NODE connected client");
+    info!("Shutting down GO KASPA NODE connected client");
     c_public.unregister_listener(c_public_listener.id).await?;
-    c_public_listener.recv_channel.close();
+    c_public_listener.close();
     c_public.stop().await?;
-    c_public.shutdown().await?;
+    c_public.disconnect().await?;
 
     //sleep(Duration::from_millis(2000)).await;
 
-    println!("Terminating client app");
+    info!("Terminating client app");
 
     Ok(())
 }
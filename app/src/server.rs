@@ -1,15 +1,61 @@
+use consensus::simulator::{DagSimulator, SimulatorConfig};
+use consensus_core::{networktype::NetworkType, params::Params};
+use kaspa_core::{info, warn};
+use kaspa_utils::triggers::SingleTrigger;
 use rpc_core::server::service::RpcApi;
 use rpc_grpc::server;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Time given to the requests in flight to complete on shutdown
+const SHUTDOWN_DEADLINE: Duration = Duration::from_secs(10);
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let consensus_block_producer = Arc::new(consensus::notifiy::RandomBlockProducer::new());
-    let consensus_recv = consensus_block_producer.start().await;
-    let core_service = RpcApi::new(consensus_recv);
+    kaspa_core::log::init_logger(&std::env::var("KASPA_LOG").unwrap_or_default())?;
+
+    // The network defaults to simnet and can be set by KASPA_NETWORK
+    let network_type = match std::env::var("KASPA_NETWORK") {
+        Ok(network) => network.parse::<NetworkType>()?,
+        Err(_) => NetworkType::Simnet,
+    };
+    let params: &Params = network_type.into();
+    info!("Running against {}", params.name);
+
+    let genesis_timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+    let simulator = Arc::new(DagSimulator::new(SimulatorConfig { genesis_timestamp, network_type, ..Default::default() })?);
+    // The shutdown is requested either by CTRL+C or by an authorized shutdown RPC call
+    let shutdown = SingleTrigger::new();
+
+    let consensus_recv = simulator.start().await;
+    let core_service = RpcApi::new(simulator.clone(), consensus_recv, shutdown.clone());
     core_service.start();
 
-    let addr = "[::1]:10000".parse().unwrap();
-    let server_handle = server::run_server(addr, core_service);
-    server_handle.await?.map_err(|x| x.into())
+    let metrics_addr = "127.0.0.1:10001".parse().unwrap();
+    let metrics_handle = server::metrics::run_metrics_server(metrics_addr, core_service.metrics(), shutdown.listener.clone());
+
+    let addr = format!("[::1]:{}", params.default_rpc_port).parse().unwrap();
+    let server_handle = server::run_server(addr, core_service.clone(), Default::default())?;
+
+    let ctrl_c_shutdown = shutdown.clone();
+    tokio::spawn(async move {
+        match tokio::signal::ctrl_c().await {
+            Ok(()) => ctrl_c_shutdown.trigger.trigger(),
+            Err(err) => warn!("Unable to listen for the CTRL+C signal: {}", err),
+        }
+    });
+
+    shutdown.listener.clone().await;
+    info!("Shutdown requested");
+
+    // The services stop in the reverse order of their dependencies:
+    // - the gRPC server first, letting the requests in flight complete within the deadline,
+    // - then the core service, which no request can reach anymore,
+    // - then the simulator feeding the core service with consensus notifications,
+    // - the metrics server last, having stopped serving as soon as the shutdown was requested.
+    server_handle.shutdown(SHUTDOWN_DEADLINE).await?;
+    core_service.stop().await?;
+    simulator.stop();
+    metrics_handle.await??;
+    Ok(())
 }
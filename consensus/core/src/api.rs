@@ -0,0 +1,108 @@
+use crate::{
+    block::Block,
+    difficulty,
+    errors::ConsensusResult,
+    ghostdag::GhostdagData,
+    header::Header,
+    networktype::NetworkType,
+    params::Params,
+    tx::{ScriptPublicKey, TransactionOutpoint, UtxoEntry},
+    BlueWorkType,
+};
+use hashes::Hash;
+use std::{fmt::Debug, sync::Arc};
+
+/// State of the virtual block, ie. the block having all current tips as parents
+#[derive(Clone, Debug, Default)]
+pub struct VirtualState {
+    pub parents: Vec<Hash>,
+    pub selected_parent: Hash,
+    pub blue_score: u64,
+    pub blue_work: BlueWorkType,
+    pub daa_score: u64,
+    pub bits: u32,
+
+    /// Timestamp of the selected parent in milliseconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+/// Relations of a block in the DAG, completing the block itself when describing it to clients
+#[derive(Clone, Debug, Default)]
+pub struct BlockDagContext {
+    pub ghostdag_data: GhostdagData,
+    pub children: Vec<Hash>,
+    pub is_chain_block: bool,
+
+    /// Difficulty of the block bits relative to the genesis ones
+    pub difficulty: f64,
+}
+
+/// The queries a consensus answers, making it the single source of truth of the node
+pub trait ConsensusApi: Send + Sync + Debug {
+    /// The network this consensus validates blocks of
+    fn network_type(&self) -> NetworkType;
+
+    /// The parameters of the network of this consensus
+    fn params(&self) -> &'static Params {
+        self.network_type().into()
+    }
+
+    /// Validates a block built outside of consensus and adds it to the DAG.
+    fn validate_and_insert_block(&self, block: Block) -> ConsensusResult<()>;
+
+    fn get_genesis_hash(&self) -> Hash;
+
+    fn get_block(&self, hash: Hash) -> ConsensusResult<Block>;
+
+    fn get_header(&self, hash: Hash) -> ConsensusResult<Header>;
+
+    fn get_ghostdag_data(&self, hash: Hash) -> ConsensusResult<GhostdagData>;
+
+    /// Returns the blocks having `hash` as a direct parent.
+    fn get_block_children(&self, hash: Hash) -> ConsensusResult<Vec<Hash>>;
+
+    /// Returns true if the block is in the selected parent chain of the virtual block.
+    fn is_chain_block(&self, hash: Hash) -> ConsensusResult<bool>;
+
+    fn get_block_dag_context(&self, hash: Hash) -> ConsensusResult<BlockDagContext> {
+        Ok(BlockDagContext {
+            ghostdag_data: self.get_ghostdag_data(hash)?,
+            children: self.get_block_children(hash)?,
+            is_chain_block: self.is_chain_block(hash)?,
+            difficulty: difficulty::difficulty(self.get_header(hash)?.bits, self.get_header(self.get_genesis_hash())?.bits),
+        })
+    }
+
+    /// Estimates the network hashrate over the `window_size` selected chain blocks ending at `start_hash`,
+    /// or at the virtual selected parent if `None`.
+    fn estimate_network_hashes_per_second(&self, start_hash: Option<Hash>, window_size: usize) -> ConsensusResult<u64> {
+        let mut current = start_hash.unwrap_or_else(|| self.get_virtual_state().selected_parent);
+        let mut window = Vec::with_capacity(window_size);
+        while window.len() < window_size {
+            window.push(self.get_header(current)?);
+            if current == self.get_genesis_hash() {
+                break;
+            }
+            current = self.get_ghostdag_data(current)?.selected_parent;
+        }
+        Ok(difficulty::estimate_network_hashes_per_second(&window))
+    }
+
+    /// Returns the current tips of the DAG.
+    fn get_tips(&self) -> Vec<Hash>;
+
+    fn get_virtual_state(&self) -> VirtualState;
+
+    /// Returns the entry of an unspent output of the virtual UTXO set.
+    fn get_utxo(&self, outpoint: &TransactionOutpoint) -> Option<UtxoEntry>;
+
+    /// Returns all unspent outputs of the virtual UTXO set paying to any of `script_public_keys`.
+    fn get_utxos_by_script_public_keys(&self, script_public_keys: &[ScriptPublicKey]) -> Vec<(TransactionOutpoint, UtxoEntry)>;
+
+    /// Returns the total amount of the virtual UTXO set, in sompi.
+    fn get_circulating_supply(&self) -> u64;
+
+    fn is_synced(&self) -> bool;
+}
+
+pub type DynConsensus = Arc<dyn ConsensusApi>;
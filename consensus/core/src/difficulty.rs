@@ -0,0 +1,84 @@
+//! Difficulty math over compact targets: block work, difficulty and network hashrate
+//!
+//! Compact targets (the `bits` of a header) are decoded and encoded by [`Uint256`]. See kaspad `util/difficulty`.
+
+use crate::{header::Header, BlueWorkType};
+use math::Uint256;
+
+/// Expected number of hashes needed to mine a block of the given bits, ie. `2^256 / (target + 1)`.
+///
+/// A zero target can't be reached and has no work.
+pub fn calc_work(bits: u32) -> BlueWorkType {
+    let target = Uint256::from_compact_target_bits(bits);
+    if target.is_zero() {
+        return 0;
+    }
+    // 2^256 doesn't fit, so compute (2^256 - target - 1) / (target + 1) + 1 instead
+    let work = !target / (target + 1u64.into()) + 1u64.into();
+    work.as_u128().unwrap_or(BlueWorkType::MAX)
+}
+
+/// Difficulty of the given bits relative to the easiest target `max_bits`, usually the one of genesis.
+///
+/// Returns zero for an unreachable zero target.
+pub fn difficulty(bits: u32, max_bits: u32) -> f64 {
+    let target = Uint256::from_compact_target_bits(bits);
+    if target.is_zero() {
+        return 0.0;
+    }
+    Uint256::from_compact_target_bits(max_bits).as_f64() / target.as_f64()
+}
+
+/// Estimates the hashes per second of the network from a window of headers: the blue work accumulated
+/// over the window divided by the time it spans.
+///
+/// Returns zero if the window spans no time, which is the case of windows of less than two headers.
+pub fn estimate_network_hashes_per_second<'a>(window: impl IntoIterator<Item = &'a Header>) -> u64 {
+    let mut min_work = BlueWorkType::MAX;
+    let mut max_work = BlueWorkType::MIN;
+    let mut min_timestamp = u64::MAX;
+    let mut max_timestamp = u64::MIN;
+    for header in window {
+        min_work = min_work.min(header.blue_work);
+        max_work = max_work.max(header.blue_work);
+        min_timestamp = min_timestamp.min(header.timestamp);
+        max_timestamp = max_timestamp.max(header.timestamp);
+    }
+    if max_timestamp <= min_timestamp {
+        return 0;
+    }
+    // Timestamps are in milliseconds
+    let hashes_per_second = (max_work - min_work).saturating_mul(1000) / (max_timestamp - min_timestamp) as BlueWorkType;
+    hashes_per_second.try_into().unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calc_work() {
+        // The easiest target has half of the hashes below it
+        assert_eq!(calc_work(0x207fffff), 2);
+        assert_eq!(calc_work(0x1d00ffff), 0x100010001);
+        assert_eq!(calc_work(0x04923456), 0);
+    }
+
+    #[test]
+    fn test_difficulty() {
+        assert_eq!(difficulty(0x207fffff, 0x207fffff), 1.0);
+        assert_eq!(difficulty(0x1f7fffff, 0x207fffff), 256.0);
+        assert_eq!(difficulty(0x04923456, 0x207fffff), 0.0);
+    }
+
+    #[test]
+    fn test_estimate_network_hashes_per_second() {
+        let header = |timestamp, blue_work| Header::new(1, vec![], Default::default(), timestamp, 0x207fffff, 0, 0, blue_work, 0);
+        assert_eq!(estimate_network_hashes_per_second(&[]), 0);
+        assert_eq!(estimate_network_hashes_per_second(&[header(1000, 10)]), 0);
+
+        // 3000 hashes over 1.5 seconds, in any order
+        let window = [header(3500, 3100), header(2000, 100), header(3000, 2000)];
+        assert_eq!(estimate_network_hashes_per_second(&window), 2000);
+    }
+}
@@ -0,0 +1,47 @@
+use crate::BlueWorkType;
+use hashes::Hash;
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error)]
+pub enum ConsensusError {
+    #[error("block {0} not found")]
+    BlockNotFound(Hash),
+
+    #[error("header {0} not found")]
+    HeaderNotFound(Hash),
+
+    #[error("block {0} already exists")]
+    DuplicateBlock(Hash),
+
+    #[error("block {0} has no parents")]
+    NoParents(Hash),
+
+    #[error("block {0} has unknown parents {1:?}")]
+    MissingParents(Hash, Vec<Hash>),
+
+    #[error("block {0} has difficulty bits {1:#010x} but {2:#010x} are expected")]
+    UnexpectedDifficulty(Hash, u32, u32),
+
+    #[error("block {0} has invalid proof of work")]
+    InvalidPoW(Hash),
+
+    #[error("block {0} hash merkle root is {1} but its transactions have root {2}")]
+    BadMerkleRoot(Hash, Hash, Hash),
+
+    #[error("block {0} accepted id merkle root is {1} but the transactions it accepts have root {2}")]
+    BadAcceptedIdMerkleRoot(Hash, Hash, Hash),
+
+    #[error("block {0} commits to UTXO set {1} but the UTXO set it builds on has commitment {2}")]
+    BadUtxoCommitment(Hash, Hash, Hash),
+
+    #[error("block {0} header has DAA score {1} but its parents give {2}")]
+    UnexpectedHeaderDaaScore(Hash, u64, u64),
+
+    #[error("block {0} header has blue score {1} but its parents give {2}")]
+    UnexpectedHeaderBlueScore(Hash, u64, u64),
+
+    #[error("block {0} header has blue work {1} but its parents give {2}")]
+    UnexpectedHeaderBlueWork(Hash, BlueWorkType, BlueWorkType),
+}
+
+pub type ConsensusResult<T> = std::result::Result<T, ConsensusError>;
@@ -0,0 +1,30 @@
+use crate::{blockhash, BlueWorkType};
+use hashes::Hash;
+
+/// GHOSTDAG data of a block: its selected parent and the coloring of its merge set
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GhostdagData {
+    pub blue_score: u64,
+    pub blue_work: BlueWorkType,
+    pub selected_parent: Hash,
+
+    /// Blue blocks of the merge set, starting with the selected parent
+    pub mergeset_blues: Vec<Hash>,
+    pub mergeset_reds: Vec<Hash>,
+}
+
+impl GhostdagData {
+    /// Data of the genesis block, which has no parent and an empty merge set
+    pub fn genesis() -> Self {
+        Self { selected_parent: blockhash::ORIGIN, ..Default::default() }
+    }
+
+    /// Returns all the blocks of the merge set, blues first.
+    pub fn mergeset(&self) -> impl Iterator<Item = &Hash> {
+        self.mergeset_blues.iter().chain(self.mergeset_reds.iter())
+    }
+
+    pub fn mergeset_size(&self) -> usize {
+        self.mergeset_blues.len() + self.mergeset_reds.len()
+    }
+}
@@ -3,6 +3,7 @@ use hashes::Hasher;
 use crate::BlueWorkType;
 
 pub mod header;
+pub mod sighash;
 pub mod tx;
 
 pub(crate) trait HasherExtensions {
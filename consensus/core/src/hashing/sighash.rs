@@ -0,0 +1,563 @@
+//! Signature hashes: the per-input digests signed by transaction inputs
+//!
+//! The hash type tells which other inputs and outputs an input commits to. The hashes of the previous
+//! outputs, sequences, sig op counts and outputs are shared by all inputs of a transaction and are cached
+//! in a [`SigHashReusedValues`] so that signing all inputs isn't quadratic. See kaspad `sighash.go`.
+
+use super::HasherExtensions;
+use crate::{
+    subnets::SUBNETWORK_ID_NATIVE,
+    tx::{ScriptPublicKey, Transaction, TransactionOutput},
+};
+use hashes::{Hash, Hasher, TransactionSigningHash, TransactionSigningHashECDSA, ZERO_HASH};
+use thiserror::Error;
+
+/// Mask of the bits of a hash type selecting the signed outputs
+const SIG_HASH_MASK: u8 = 0b0000_0111;
+
+/// Which parts of a transaction a signature commits to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SigHashType(u8);
+
+/// Commits to all inputs and outputs
+pub const SIG_HASH_ALL: SigHashType = SigHashType(0b0000_0001);
+
+/// Commits to all inputs but no output
+pub const SIG_HASH_NONE: SigHashType = SigHashType(0b0000_0010);
+
+/// Commits to all inputs and to the output of the same index as the signed input
+pub const SIG_HASH_SINGLE: SigHashType = SigHashType(0b0000_0100);
+
+/// Flag committing to the signed input only, to be combined with one of the above
+pub const SIG_HASH_ANY_ONE_CAN_PAY: SigHashType = SigHashType(0b1000_0000);
+
+pub const SIG_HASH_ALL_ANYONE_CAN_PAY: SigHashType = SigHashType(SIG_HASH_ALL.0 | SIG_HASH_ANY_ONE_CAN_PAY.0);
+pub const SIG_HASH_NONE_ANYONE_CAN_PAY: SigHashType = SigHashType(SIG_HASH_NONE.0 | SIG_HASH_ANY_ONE_CAN_PAY.0);
+pub const SIG_HASH_SINGLE_ANYONE_CAN_PAY: SigHashType = SigHashType(SIG_HASH_SINGLE.0 | SIG_HASH_ANY_ONE_CAN_PAY.0);
+
+const ALLOWED_SIG_HASH_TYPES: [SigHashType; 6] = [
+    SIG_HASH_ALL,
+    SIG_HASH_NONE,
+    SIG_HASH_SINGLE,
+    SIG_HASH_ALL_ANYONE_CAN_PAY,
+    SIG_HASH_NONE_ANYONE_CAN_PAY,
+    SIG_HASH_SINGLE_ANYONE_CAN_PAY,
+];
+
+impl SigHashType {
+    pub fn is_sighash_all(self) -> bool {
+        self.0 & SIG_HASH_MASK == SIG_HASH_ALL.0
+    }
+
+    pub fn is_sighash_none(self) -> bool {
+        self.0 & SIG_HASH_MASK == SIG_HASH_NONE.0
+    }
+
+    pub fn is_sighash_single(self) -> bool {
+        self.0 & SIG_HASH_MASK == SIG_HASH_SINGLE.0
+    }
+
+    pub fn is_sighash_anyone_can_pay(self) -> bool {
+        self.0 & SIG_HASH_ANY_ONE_CAN_PAY.0 == SIG_HASH_ANY_ONE_CAN_PAY.0
+    }
+
+    pub fn to_u8(self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for SigHashType {
+    type Error = SigHashError;
+
+    fn try_from(value: u8) -> SigHashResult<Self> {
+        match ALLOWED_SIG_HASH_TYPES.iter().find(|hash_type| hash_type.0 == value) {
+            Some(hash_type) => Ok(*hash_type),
+            None => Err(SigHashError::InvalidSigHashType(value)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum SigHashError {
+    #[error("invalid sighash type {0:#04x}")]
+    InvalidSigHashType(u8),
+
+    #[error("transaction has no input {0}")]
+    InputOutOfRange(usize),
+
+    #[error("input {0} has no UTXO entry")]
+    MissingUtxoEntry(usize),
+}
+
+pub type SigHashResult<T> = std::result::Result<T, SigHashError>;
+
+/// Hashes shared by all the inputs of a transaction, computed on first use.
+///
+/// A given instance must only be used with a single transaction.
+#[derive(Clone, Debug, Default)]
+pub struct SigHashReusedValues {
+    previous_outputs_hash: Option<Hash>,
+    sequences_hash: Option<Hash>,
+    sig_op_counts_hash: Option<Hash>,
+    outputs_hash: Option<Hash>,
+}
+
+impl SigHashReusedValues {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn previous_outputs_hash(tx: &Transaction, hash_type: SigHashType, reused_values: &mut SigHashReusedValues) -> Hash {
+    if hash_type.is_sighash_anyone_can_pay() {
+        return ZERO_HASH;
+    }
+    *reused_values.previous_outputs_hash.get_or_insert_with(|| {
+        let mut hasher = TransactionSigningHash::new();
+        for input in tx.inputs.iter() {
+            hasher.update(input.previous_outpoint.transaction_id).update(input.previous_outpoint.index.to_le_bytes());
+        }
+        hasher.finalize()
+    })
+}
+
+fn sequences_hash(tx: &Transaction, hash_type: SigHashType, reused_values: &mut SigHashReusedValues) -> Hash {
+    if hash_type.is_sighash_single() || hash_type.is_sighash_anyone_can_pay() || hash_type.is_sighash_none() {
+        return ZERO_HASH;
+    }
+    *reused_values.sequences_hash.get_or_insert_with(|| {
+        let mut hasher = TransactionSigningHash::new();
+        for input in tx.inputs.iter() {
+            hasher.update(input.sequence.to_le_bytes());
+        }
+        hasher.finalize()
+    })
+}
+
+fn sig_op_counts_hash(tx: &Transaction, hash_type: SigHashType, reused_values: &mut SigHashReusedValues) -> Hash {
+    if hash_type.is_sighash_anyone_can_pay() {
+        return ZERO_HASH;
+    }
+    *reused_values.sig_op_counts_hash.get_or_insert_with(|| {
+        let mut hasher = TransactionSigningHash::new();
+        for input in tx.inputs.iter() {
+            hasher.update([input.sig_op_count]);
+        }
+        hasher.finalize()
+    })
+}
+
+fn payload_hash(tx: &Transaction) -> Hash {
+    if tx.subnetwork_id == SUBNETWORK_ID_NATIVE {
+        return ZERO_HASH;
+    }
+    let mut hasher = TransactionSigningHash::new();
+    hasher.write_var_bytes(&tx.payload);
+    hasher.finalize()
+}
+
+fn outputs_hash(tx: &Transaction, hash_type: SigHashType, reused_values: &mut SigHashReusedValues, input_index: usize) -> Hash {
+    if hash_type.is_sighash_none() {
+        return ZERO_HASH;
+    }
+    if hash_type.is_sighash_single() {
+        // Only the output of the same index is signed, if any
+        return match tx.outputs.get(input_index) {
+            Some(output) => {
+                let mut hasher = TransactionSigningHash::new();
+                write_output(&mut hasher, output);
+                hasher.finalize()
+            }
+            None => ZERO_HASH,
+        };
+    }
+    *reused_values.outputs_hash.get_or_insert_with(|| {
+        let mut hasher = TransactionSigningHash::new();
+        for output in tx.outputs.iter() {
+            write_output(&mut hasher, output);
+        }
+        hasher.finalize()
+    })
+}
+
+fn write_output<T: Hasher>(hasher: &mut T, output: &TransactionOutput) {
+    hasher.update(output.value.to_le_bytes());
+    write_script_public_key(hasher, &output.script_public_key);
+}
+
+fn write_script_public_key<T: Hasher>(hasher: &mut T, script_public_key: &ScriptPublicKey) {
+    hasher.update(script_public_key.version.to_le_bytes()).write_var_bytes(&script_public_key.script);
+}
+
+/// Computes the hash signed by the Schnorr signature of input `input_index`, whose UTXO entry must be populated.
+pub fn calc_schnorr_signature_hash(
+    tx: &Transaction,
+    input_index: usize,
+    hash_type: SigHashType,
+    reused_values: &mut SigHashReusedValues,
+) -> SigHashResult<Hash> {
+    let input = tx.inputs.get(input_index).ok_or(SigHashError::InputOutOfRange(input_index))?;
+    let entry = input.utxo_entry.as_ref().ok_or(SigHashError::MissingUtxoEntry(input_index))?;
+
+    let mut hasher = TransactionSigningHash::new();
+    hasher
+        .update(tx.version.to_le_bytes())
+        .update(previous_outputs_hash(tx, hash_type, reused_values))
+        .update(sequences_hash(tx, hash_type, reused_values))
+        .update(sig_op_counts_hash(tx, hash_type, reused_values))
+        .update(input.previous_outpoint.transaction_id)
+        .update(input.previous_outpoint.index.to_le_bytes());
+    write_script_public_key(&mut hasher, &entry.script_public_key);
+    hasher
+        .update(entry.amount.to_le_bytes())
+        .update(input.sequence.to_le_bytes())
+        .update([input.sig_op_count])
+        .update(outputs_hash(tx, hash_type, reused_values, input_index))
+        .update(tx.lock_time.to_le_bytes())
+        .update(&tx.subnetwork_id)
+        .update(tx.gas.to_le_bytes())
+        .update(payload_hash(tx))
+        .update([hash_type.to_u8()]);
+    Ok(hasher.finalize())
+}
+
+/// Computes the hash signed by the ECDSA signature of input `input_index`: the Schnorr one hashed again.
+pub fn calc_ecdsa_signature_hash(
+    tx: &Transaction,
+    input_index: usize,
+    hash_type: SigHashType,
+    reused_values: &mut SigHashReusedValues,
+) -> SigHashResult<Hash> {
+    let hash = calc_schnorr_signature_hash(tx, input_index, hash_type, reused_values)?;
+    let mut hasher = TransactionSigningHashECDSA::new();
+    hasher.update(hash);
+    Ok(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        subnets::SubnetworkId,
+        tx::{TransactionInput, TransactionOutpoint, UtxoEntry},
+    };
+    use std::{str::FromStr, sync::Arc};
+
+    enum Modify {
+        NoAction,
+        Output(usize),
+        Input(usize),
+        AmountSpent(usize),
+        PrevScriptPublicKey(usize),
+        Sequence(usize),
+        Payload,
+        Gas,
+        SubnetworkId,
+    }
+
+    fn script(hex: &str) -> Vec<u8> {
+        let mut bytes = vec![0u8; hex.len() / 2];
+        faster_hex::hex_decode(hex.as_bytes(), &mut bytes).unwrap();
+        bytes
+    }
+
+    /// The kaspad test transaction, spending 3 outputs to 2, with the given modification
+    fn transaction(subnetwork: bool, modify: &Modify) -> Transaction {
+        let prev_tx_id = Hash::from_str("880eb9819a31821d9d2399e2f35e2433b72637e393d71ecc9b8d0250f49153c3").unwrap();
+        let script_1 =
+            Arc::new(ScriptPublicKey::new(script("208325613d2eeaf7176ac6c670b13c0043156c427438ed72d74b7800862ad884e8ac"), 0));
+        let script_2 =
+            Arc::new(ScriptPublicKey::new(script("20fcef4c106cf11135bbd70f02a726a92162d2fb8b22f0469126f800862ad884e8ac"), 0));
+
+        let mut entries: Vec<UtxoEntry> = [(100, &script_1), (200, &script_2), (300, &script_2)]
+            .map(|(amount, spk)| UtxoEntry::new(amount, spk.clone(), 0, false))
+            .into();
+        let mut inputs: Vec<TransactionInput> =
+            (0..3u32).map(|i| TransactionInput::new(TransactionOutpoint::new(prev_tx_id, i), vec![], i as u64, 0, None)).collect();
+        let mut outputs = vec![TransactionOutput::new(300, script_2), TransactionOutput::new(300, script_1)];
+        let (mut subnetwork_id, mut gas, mut payload) = match subnetwork {
+            true => (SubnetworkId::from_str("0102030405060708090a00000000000000000000").unwrap(), 250, (10..=20).collect()),
+            false => (SUBNETWORK_ID_NATIVE, 0, vec![]),
+        };
+
+        match *modify {
+            Modify::NoAction => {}
+            Modify::Output(i) => outputs[i].value = 100,
+            Modify::Input(i) => inputs[i].previous_outpoint.index = 2,
+            Modify::AmountSpent(i) => entries[i].amount = 666,
+            Modify::PrevScriptPublicKey(i) => {
+                let spk = &entries[i].script_public_key;
+                entries[i].script_public_key = Arc::new(ScriptPublicKey::new([&spk.script[..], &[1, 2, 3]].concat(), spk.version));
+            }
+            Modify::Sequence(i) => inputs[i].sequence = 12345,
+            Modify::Payload => payload = vec![6, 6, 6, 4, 2, 0, 1, 3, 3, 7],
+            Modify::Gas => gas = 1234,
+            Modify::SubnetworkId => subnetwork_id = SubnetworkId::from_str("0606060402000103030700000000000000000000").unwrap(),
+        }
+
+        let inputs = inputs
+            .into_iter()
+            .zip(entries)
+            .map(|(input, entry)| Arc::new(TransactionInput { utxo_entry: Some(entry), ..input }))
+            .collect();
+        let outputs = outputs.into_iter().map(Arc::new).collect();
+        Transaction::new(0, inputs, outputs, 1615462089000, subnetwork_id, gas, payload, 0)
+    }
+
+    #[test]
+    fn test_signature_hash() {
+        // Vectors from kaspad: name, subnetwork transaction, hash type, input index, modification, expected hash
+        let tests = [
+            (
+                "native-all-0",
+                false,
+                SIG_HASH_ALL,
+                0,
+                Modify::NoAction,
+                "03b7ac6927b2b67100734c3cc313ff8c2e8b3ce3e746d46dd660b706a916b1f5",
+            ),
+            (
+                "native-all-0-modify-input-1",
+                false,
+                SIG_HASH_ALL,
+                0,
+                Modify::Input(1),
+                "a9f563d86c0ef19ec2e4f483901d202e90150580b6123c3d492e26e7965f488c",
+            ),
+            (
+                "native-all-0-modify-output-1",
+                false,
+                SIG_HASH_ALL,
+                0,
+                Modify::Output(1),
+                "aad2b61bd2405dfcf7294fc2be85f325694f02dda22d0af30381cb50d8295e0a",
+            ),
+            (
+                "native-all-0-modify-sequence-1",
+                false,
+                SIG_HASH_ALL,
+                0,
+                Modify::Sequence(1),
+                "0818bd0a3703638d4f01014c92cf866a8903cab36df2fa2506dc0d06b94295e8",
+            ),
+            (
+                "native-all-anyonecanpay-0",
+                false,
+                SIG_HASH_ALL_ANYONE_CAN_PAY,
+                0,
+                Modify::NoAction,
+                "24821e466e53ff8e5fa93257cb17bb06131a48be4ef282e87f59d2bdc9afebc2",
+            ),
+            (
+                "native-all-anyonecanpay-0-modify-input-0",
+                false,
+                SIG_HASH_ALL_ANYONE_CAN_PAY,
+                0,
+                Modify::Input(0),
+                "d09cb639f335ee69ac71f2ad43fd9e59052d38a7d0638de4cf989346588a7c38",
+            ),
+            (
+                "native-all-anyonecanpay-0-modify-input-1",
+                false,
+                SIG_HASH_ALL_ANYONE_CAN_PAY,
+                0,
+                Modify::Input(1),
+                "24821e466e53ff8e5fa93257cb17bb06131a48be4ef282e87f59d2bdc9afebc2",
+            ),
+            (
+                "native-all-anyonecanpay-0-modify-sequence",
+                false,
+                SIG_HASH_ALL_ANYONE_CAN_PAY,
+                0,
+                Modify::Sequence(1),
+                "24821e466e53ff8e5fa93257cb17bb06131a48be4ef282e87f59d2bdc9afebc2",
+            ),
+            (
+                "native-none-0",
+                false,
+                SIG_HASH_NONE,
+                0,
+                Modify::NoAction,
+                "38ce4bc93cf9116d2e377b33ff8449c665b7b5e2f2e65303c543b9afdaa4bbba",
+            ),
+            (
+                "native-none-0-modify-output-1",
+                false,
+                SIG_HASH_NONE,
+                0,
+                Modify::Output(1),
+                "38ce4bc93cf9116d2e377b33ff8449c665b7b5e2f2e65303c543b9afdaa4bbba",
+            ),
+            (
+                "native-none-0-modify-output-1",
+                false,
+                SIG_HASH_NONE,
+                0,
+                Modify::Output(1),
+                "38ce4bc93cf9116d2e377b33ff8449c665b7b5e2f2e65303c543b9afdaa4bbba",
+            ),
+            (
+                "native-none-0-modify-sequence-0",
+                false,
+                SIG_HASH_NONE,
+                0,
+                Modify::Sequence(0),
+                "d9efdd5edaa0d3fd0133ee3ab731d8c20e0a1b9f3c0581601ae2075db1109268",
+            ),
+            (
+                "native-none-0-modify-sequence-1",
+                false,
+                SIG_HASH_NONE,
+                0,
+                Modify::Sequence(1),
+                "38ce4bc93cf9116d2e377b33ff8449c665b7b5e2f2e65303c543b9afdaa4bbba",
+            ),
+            (
+                "native-none-anyonecanpay-0",
+                false,
+                SIG_HASH_NONE_ANYONE_CAN_PAY,
+                0,
+                Modify::NoAction,
+                "06aa9f4239491e07bb2b6bda6b0657b921aeae51e193d2c5bf9e81439cfeafa0",
+            ),
+            (
+                "native-none-anyonecanpay-0-modify-amount-spent",
+                false,
+                SIG_HASH_NONE_ANYONE_CAN_PAY,
+                0,
+                Modify::AmountSpent(0),
+                "f07f45f3634d3ea8c0f2cb676f56e20993edf9be07a83bf0dfdb3debcf1441bf",
+            ),
+            (
+                "native-none-anyonecanpay-0-modify-script-public-key",
+                false,
+                SIG_HASH_NONE_ANYONE_CAN_PAY,
+                0,
+                Modify::PrevScriptPublicKey(0),
+                "20a525c54dc33b2a61201f05233c086dbe8e06e9515775181ed96550b4f2d714",
+            ),
+            (
+                "native-single-0",
+                false,
+                SIG_HASH_SINGLE,
+                0,
+                Modify::NoAction,
+                "44a0b407ff7b239d447743dd503f7ad23db5b2ee4d25279bd3dffaf6b474e005",
+            ),
+            (
+                "native-single-0-modify-output-1",
+                false,
+                SIG_HASH_SINGLE,
+                0,
+                Modify::Output(1),
+                "44a0b407ff7b239d447743dd503f7ad23db5b2ee4d25279bd3dffaf6b474e005",
+            ),
+            (
+                "native-single-0-modify-sequence-0",
+                false,
+                SIG_HASH_SINGLE,
+                0,
+                Modify::Sequence(0),
+                "83796d22879718eee1165d4aace667bb6778075dab579c32c57be945f466a451",
+            ),
+            (
+                "native-single-0-modify-sequence-1",
+                false,
+                SIG_HASH_SINGLE,
+                0,
+                Modify::Sequence(1),
+                "44a0b407ff7b239d447743dd503f7ad23db5b2ee4d25279bd3dffaf6b474e005",
+            ),
+            (
+                "native-single-2-no-corresponding-output",
+                false,
+                SIG_HASH_SINGLE,
+                2,
+                Modify::NoAction,
+                "022ad967192f39d8d5895d243e025ec14cc7a79708c5e364894d4eff3cecb1b0",
+            ),
+            (
+                "native-single-2-no-corresponding-output-modify-output-1",
+                false,
+                SIG_HASH_SINGLE,
+                2,
+                Modify::Output(1),
+                "022ad967192f39d8d5895d243e025ec14cc7a79708c5e364894d4eff3cecb1b0",
+            ),
+            (
+                "native-single-anyonecanpay-0",
+                false,
+                SIG_HASH_SINGLE_ANYONE_CAN_PAY,
+                0,
+                Modify::NoAction,
+                "43b20aba775050cf9ba8d5e48fc7ed2dc6c071d23f30382aea58b7c59cfb8ed7",
+            ),
+            (
+                "native-single-anyonecanpay-2-no-corresponding-output",
+                false,
+                SIG_HASH_SINGLE_ANYONE_CAN_PAY,
+                2,
+                Modify::NoAction,
+                "846689131fb08b77f83af1d3901076732ef09d3f8fdff945be89aa4300562e5f",
+            ),
+            (
+                "subnetwork-all-0",
+                true,
+                SIG_HASH_ALL,
+                0,
+                Modify::NoAction,
+                "b2f421c933eb7e1a91f1d9e1efa3f120fe419326c0dbac487752189522550e0c",
+            ),
+            (
+                "subnetwork-all-modify-payload",
+                true,
+                SIG_HASH_ALL,
+                0,
+                Modify::Payload,
+                "12ab63b9aea3d58db339245a9b6e9cb6075b2253615ce0fb18104d28de4435a1",
+            ),
+            (
+                "subnetwork-all-modify-gas",
+                true,
+                SIG_HASH_ALL,
+                0,
+                Modify::Gas,
+                "2501edfc0068d591160c4bd98646c6e6892cdc051182a8be3ccd6d67f104fd17",
+            ),
+            (
+                "subnetwork-all-subnetwork-id",
+                true,
+                SIG_HASH_ALL,
+                0,
+                Modify::SubnetworkId,
+                "a5d1230ede0dfcfd522e04123a7bcd721462fed1d3a87352031a4f6e3c4389b6",
+            ),
+        ];
+
+        for (name, subnetwork, hash_type, input_index, modify, expected) in tests {
+            let tx = transaction(subnetwork, &modify);
+            let hash = calc_schnorr_signature_hash(&tx, input_index, hash_type, &mut SigHashReusedValues::new()).unwrap();
+            assert_eq!(hash.to_string(), expected, "test {} failed", name);
+        }
+    }
+
+    #[test]
+    fn test_reused_values() {
+        // Signing all inputs with the same cache gives the same hashes as signing each alone
+        let tx = transaction(false, &Modify::NoAction);
+        let mut reused_values = SigHashReusedValues::new();
+        for hash_type in ALLOWED_SIG_HASH_TYPES {
+            for input_index in 0..tx.inputs.len() {
+                let hash = calc_ecdsa_signature_hash(&tx, input_index, hash_type, &mut reused_values).unwrap();
+                assert_eq!(hash, calc_ecdsa_signature_hash(&tx, input_index, hash_type, &mut SigHashReusedValues::new()).unwrap());
+            }
+        }
+
+        assert_eq!(SigHashType::try_from(0x81), Ok(SIG_HASH_ALL_ANYONE_CAN_PAY));
+        assert_eq!(SigHashType::try_from(0x03), Err(SigHashError::InvalidSigHashType(0x03)));
+        let mut tx = transaction(false, &Modify::NoAction);
+        assert_eq!(calc_schnorr_signature_hash(&tx, 3, SIG_HASH_ALL, &mut reused_values), Err(SigHashError::InputOutOfRange(3)));
+        tx.inputs[1] = Arc::new(TransactionInput { utxo_entry: None, ..tx.inputs[1].as_ref().clone() });
+        assert_eq!(calc_schnorr_signature_hash(&tx, 1, SIG_HASH_ALL, &mut reused_values), Err(SigHashError::MissingUtxoEntry(1)));
+    }
+}
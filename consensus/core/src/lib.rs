@@ -1,9 +1,20 @@
+pub mod api;
 pub mod block;
 pub mod blockhash;
+pub mod difficulty;
+pub mod errors;
+pub mod ghostdag;
 pub mod hashing;
 pub mod header;
+pub mod mass;
+pub mod merkle;
+pub mod muhash;
+pub mod networktype;
+pub mod params;
+pub mod script;
 pub mod stubs;
 pub mod subnets;
+pub mod subsidy;
 pub mod tx;
 
 pub type BlueWorkType = u128;
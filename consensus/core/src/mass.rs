@@ -0,0 +1,116 @@
+//! Transaction mass: the weight of a transaction against block capacity and fee rates
+//!
+//! The mass is the sum of a size mass, a script public key mass and a sig op mass, each weighted by a
+//! network parameter. See kaspad `transaction_mass.go`.
+
+use crate::{
+    subnets::SUBNETWORK_ID_SIZE,
+    tx::{Transaction, TransactionInput, TransactionOutput},
+};
+use hashes::HASH_SIZE;
+
+/// Default mass of a serialized transaction byte
+pub const MASS_PER_TX_BYTE: u64 = 1;
+
+/// Default mass of a script public key byte
+pub const MASS_PER_SCRIPT_PUB_KEY_BYTE: u64 = 10;
+
+/// Default mass of a signature operation
+pub const MASS_PER_SIG_OP: u64 = 1000;
+
+/// Computes transaction masses according to network parameters
+#[derive(Clone, Debug)]
+pub struct MassCalculator {
+    mass_per_tx_byte: u64,
+    mass_per_script_pub_key_byte: u64,
+    mass_per_sig_op: u64,
+}
+
+impl MassCalculator {
+    pub fn new(mass_per_tx_byte: u64, mass_per_script_pub_key_byte: u64, mass_per_sig_op: u64) -> Self {
+        Self { mass_per_tx_byte, mass_per_script_pub_key_byte, mass_per_sig_op }
+    }
+
+    /// Computes the mass of a transaction. Coinbase transactions have no mass.
+    pub fn calc_tx_mass(&self, tx: &Transaction) -> u64 {
+        if tx.is_coinbase() {
+            return 0;
+        }
+
+        let size_mass = transaction_estimated_serialized_size(tx) * self.mass_per_tx_byte;
+        let script_public_key_size: u64 =
+            tx.outputs.iter().map(|output| 2 /* version (u16) */ + output.script_public_key.script.len() as u64).sum();
+        let sig_op_count: u64 = tx.inputs.iter().map(|input| input.sig_op_count as u64).sum();
+
+        size_mass + script_public_key_size * self.mass_per_script_pub_key_byte + sig_op_count * self.mass_per_sig_op
+    }
+}
+
+impl Default for MassCalculator {
+    fn default() -> Self {
+        Self::new(MASS_PER_TX_BYTE, MASS_PER_SCRIPT_PUB_KEY_BYTE, MASS_PER_SIG_OP)
+    }
+}
+
+/// Estimated size of the serialized transaction. It has to be deterministic but not accurate since it's
+/// only weighted into the mass.
+pub fn transaction_estimated_serialized_size(tx: &Transaction) -> u64 {
+    let mut size: u64 = 0;
+    size += 2; // version (u16)
+    size += 8; // number of inputs (u64)
+    size += tx.inputs.iter().map(|input| transaction_input_estimated_serialized_size(input)).sum::<u64>();
+    size += 8; // number of outputs (u64)
+    size += tx.outputs.iter().map(|output| transaction_output_estimated_serialized_size(output)).sum::<u64>();
+    size += 8; // lock time (u64)
+    size += SUBNETWORK_ID_SIZE as u64;
+    size += 8; // gas (u64)
+    size += HASH_SIZE as u64; // payload hash
+    size += 8; // payload length (u64)
+    size += tx.payload.len() as u64;
+    size
+}
+
+fn transaction_input_estimated_serialized_size(input: &TransactionInput) -> u64 {
+    let mut size: u64 = 0;
+    size += HASH_SIZE as u64; // previous transaction id
+    size += 4; // previous output index (u32)
+    size += 8; // signature script length (u64)
+    size += input.signature_script.len() as u64;
+    size += 8; // sequence (u64)
+    size
+}
+
+fn transaction_output_estimated_serialized_size(output: &TransactionOutput) -> u64 {
+    let mut size: u64 = 0;
+    size += 8; // value (u64)
+    size += 2; // script public key version (u16)
+    size += 8; // script public key length (u64)
+    size += output.script_public_key.script.len() as u64;
+    size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        subnets::{SUBNETWORK_ID_COINBASE, SUBNETWORK_ID_NATIVE},
+        tx::{ScriptPublicKey, TransactionOutpoint},
+    };
+    use hashes::Hash;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_calc_tx_mass() {
+        let input = Arc::new(TransactionInput::new(TransactionOutpoint::new(Hash::from_u64_word(1), 0), vec![0; 66], 0, 1, None));
+        let output = Arc::new(TransactionOutput::new(1000, Arc::new(ScriptPublicKey::new(vec![0; 34], 0))));
+        let tx = Transaction::new(0, vec![input.clone(); 2], vec![output.clone()], 0, SUBNETWORK_ID_NATIVE, 0, vec![1, 2, 3], 0);
+
+        // 94 base bytes and a 3 bytes payload, 2 inputs of 118 bytes and an output of 52 bytes, with a 36 bytes script and 2 sig ops
+        assert_eq!(transaction_estimated_serialized_size(&tx), 94 + 3 + 2 * 118 + 52);
+        assert_eq!(MassCalculator::default().calc_tx_mass(&tx), 385 + 36 * 10 + 2 * 1000);
+        assert_eq!(MassCalculator::new(2, 1, 0).calc_tx_mass(&tx), 2 * 385 + 36);
+
+        let coinbase = Transaction::new(0, vec![], vec![output], 0, SUBNETWORK_ID_COINBASE, 0, vec![], 0);
+        assert_eq!(MassCalculator::default().calc_tx_mass(&coinbase), 0);
+    }
+}
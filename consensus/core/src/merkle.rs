@@ -0,0 +1,162 @@
+//! Merkle trees over hashes, as committed to by the merkle roots of block headers
+//!
+//! The leaves are padded with empty subtrees up to the next power of two. A node hashes its children
+//! with [`MerkleBranchHash`], an empty right child counting as [`ZERO_HASH`], and is empty if its left
+//! child is. See kaspad `merkle.go`.
+
+use crate::{
+    hashing,
+    tx::{Transaction, TransactionId},
+};
+use hashes::{Hash, Hasher, MerkleBranchHash, ZERO_HASH};
+
+fn merkle_hash(left: Hash, right: Hash) -> Hash {
+    let mut hasher = MerkleBranchHash::new();
+    hasher.update(left).update(right);
+    hasher.finalize()
+}
+
+/// Returns all the levels of the tree, from the padded leaves up to the root.
+fn merkle_levels(hashes: &[Hash]) -> Vec<Vec<Option<Hash>>> {
+    let mut level: Vec<Option<Hash>> = hashes.iter().copied().map(Some).collect();
+    level.resize(hashes.len().next_power_of_two(), None);
+    let mut levels = vec![level];
+    while levels.last().unwrap().len() > 1 {
+        let next =
+            levels.last().unwrap().chunks(2).map(|pair| pair[0].map(|left| merkle_hash(left, pair[1].unwrap_or(ZERO_HASH)))).collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// Computes the merkle root of `hashes`, [`ZERO_HASH`] if there are none.
+pub fn calc_merkle_root(hashes: &[Hash]) -> Hash {
+    if hashes.is_empty() {
+        return ZERO_HASH;
+    }
+    merkle_levels(hashes).last().unwrap()[0].unwrap()
+}
+
+/// Computes the hash merkle root of a block, committing to its transactions with their signatures.
+pub fn calc_hash_merkle_root<'a>(txs: impl IntoIterator<Item = &'a Transaction>) -> Hash {
+    let hashes: Vec<Hash> = txs.into_iter().map(hashing::tx::hash).collect();
+    calc_merkle_root(&hashes)
+}
+
+/// Computes the accepted ID merkle root of a block, committing to the IDs of the transactions it accepts in any order.
+pub fn calc_accepted_id_merkle_root(accepted_ids: impl IntoIterator<Item = TransactionId>) -> Hash {
+    let mut ids: Vec<TransactionId> = accepted_ids.into_iter().collect();
+    ids.sort();
+    calc_merkle_root(&ids)
+}
+
+/// Proof that a leaf is part of a merkle tree: the siblings of the nodes on the path from the leaf to the root
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof {
+    /// Position of the leaf among the leaves
+    pub index: usize,
+    pub siblings: Vec<Hash>,
+}
+
+impl MerkleProof {
+    /// Builds the proof of the leaf at `index`, or `None` if there is no such leaf.
+    pub fn new(hashes: &[Hash], index: usize) -> Option<Self> {
+        if index >= hashes.len() {
+            return None;
+        }
+        let levels = merkle_levels(hashes);
+        let siblings = levels[..levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(depth, level)| level[(index >> depth) ^ 1].unwrap_or(ZERO_HASH))
+            .collect();
+        Some(Self { index, siblings })
+    }
+
+    /// Computes the root of the tree having `leaf` at the position of the proof.
+    pub fn root(&self, leaf: Hash) -> Hash {
+        self.siblings.iter().enumerate().fold(leaf, |node, (depth, sibling)| match (self.index >> depth) & 1 {
+            0 => merkle_hash(node, *sibling),
+            _ => merkle_hash(*sibling, node),
+        })
+    }
+
+    /// Returns whether `leaf` is at the position of the proof in the tree of the given root.
+    pub fn verify(&self, leaf: Hash, root: Hash) -> bool {
+        self.index >> self.siblings.len() == 0 && self.root(leaf) == root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        subnets::{SubnetworkId, SUBNETWORK_ID_COINBASE, SUBNETWORK_ID_NATIVE},
+        tx::{ScriptPublicKey, TransactionInput, TransactionOutpoint, TransactionOutput},
+    };
+    use std::sync::Arc;
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        let mut bytes = vec![0u8; hex.len() / 2];
+        if !hex.is_empty() {
+            faster_hex::hex_decode(hex.as_bytes(), &mut bytes).unwrap();
+        }
+        bytes
+    }
+
+    fn tx(inputs: &[(&str, u32, &str)], outputs: &[(u64, &str)], subnetwork_id: SubnetworkId, payload: Vec<u8>) -> Transaction {
+        let inputs = inputs
+            .iter()
+            .map(|(id, index, signature_script)| {
+                let outpoint = TransactionOutpoint::new(Hash::from_slice(&from_hex(id)), *index);
+                Arc::new(TransactionInput::new(outpoint, from_hex(signature_script), u64::MAX, 0, None))
+            })
+            .collect();
+        let outputs = outputs
+            .iter()
+            .map(|(value, script)| Arc::new(TransactionOutput::new(*value, Arc::new(ScriptPublicKey::new(from_hex(script), 0)))))
+            .collect();
+        Transaction::new(0, inputs, outputs, 0, subnetwork_id, 0, payload, 0)
+    }
+
+    #[test]
+    fn test_hash_merkle_root() {
+        // Vector from kaspad
+        let txs = vec![
+            tx(&[], &[(0x12a05f200, "a914da1745e9b549bd0bfa1a569971c77eba30cd5a4b87")], SUBNETWORK_ID_COINBASE, vec![9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+            tx(&[("165e38e8b3914595d9c641f3b8eec2f34611896b821a683b7a4edefe2c000000", u32::MAX, ""), ("4bb07535dfd58e0b3cd64fd7155280872a0471bcf83095526ace0e38c6000000", u32::MAX, "")], &[], SUBNETWORK_ID_NATIVE, vec![]),
+            tx(&[("032e38e9c0a84c6046d687d10556dcacc41d275ec55fc00779ac88fdf357a187", 0, "493046022100c352d3dd993a981beba4a63ad15c209275ca9470abfcd57da93b58e4eb5dce82022100840792bc1f456062819f15d33ee7055cf7b5ee1af1ebcc6028d9cdb1c3af7748014104f46db5e9d61a9dc27b8d64ad23e7383a4e6ca164593c2527c038c0857eb67ee8e825dca65046b82c9331586c82e0fd1f633f25f87c161bc6f8a630121df2b3d3")], &[(0x2123e300, "76a914c398efa9c392ba6013c5e04ee729755ef7f58b3288ac"), (0x108e20f00, "76a914948c765a6914d43f2a7ac177da2c2f6b52de3d7c88ac")], SUBNETWORK_ID_NATIVE, vec![]),
+            tx(&[("c33ebff2a709f13d9f9a7569ab16a32786af7d7e2de09265e41c61d078294ecf", 1, "4730440220032d30df5ee6f57fa46cddb5eb8d0d9fe8de6b342d27942ae90a3231e0ba333e02203deee8060fdc70230a7f5b4ad7d7bc3e628cbe219a886b84269eaeb81e26b4fe014104ae31c31bf91278d99b8377a35bbce5b27d9fff15456839e919453fc7b3f721f0ba403ff96c9deeb680e5fd341c0fc3a7b90da4631ee39560639db462e9cb850f")], &[(0xf4240, "76a914b0dcbf97eabf4404e31d952477ce822dadbe7e1088ac"), (0x11d260c0, "76a9146b1281eec25ab4e1e0793ff4e08ab1abb3409cd988ac")], SUBNETWORK_ID_NATIVE, vec![]),
+            tx(&[("0b6072b386d4a773235237f64c1126ac3b240c84b917a3909ba1c43ded5f51f4", 0, "493046022100bb1ad26df930a51cce110cf44f7a48c3c561fd977500b1ae5d6b6fd13d0b3f4a022100c5b42951acedff14abba2736fd574bdb465f3e6f8da12e2c5303954aca7f78f3014104a7135bfe824c97ecc01ec7d7e336185c81e2aa2c41ab175407c09484ce9694b44953fcb751206564a9c24dd094d42fdbfdd5aad3e063ce6af4cfaaea4ea14fbb")], &[(0xf4240, "76a91439aa3d569e06a1d7926dc4be1193c99bf2eb9ee088ac")], SUBNETWORK_ID_NATIVE, vec![]),
+        ];
+        assert_eq!(
+            calc_hash_merkle_root(&txs),
+            Hash::from_slice(&from_hex("46ecf45be3baca349dfe8a78deaf053b0aa6d538974da50fd6efb4d266bc8d21"))
+        );
+        assert_eq!(calc_hash_merkle_root(&[]), ZERO_HASH);
+    }
+
+    #[test]
+    fn test_merkle_proof() {
+        let a = Hash::from_u64_word(1);
+        assert_eq!(calc_merkle_root(&[a]), a);
+        assert_eq!(MerkleProof::new(&[a], 0).unwrap().siblings, vec![]);
+
+        for count in 1..=9 {
+            let hashes: Vec<Hash> = (0..count).map(|i| Hash::from_u64_word(i + 1)).collect();
+            let root = calc_merkle_root(&hashes);
+            for (index, hash) in hashes.iter().enumerate() {
+                let proof = MerkleProof::new(&hashes, index).unwrap();
+                assert_eq!(proof.siblings.len(), hashes.len().next_power_of_two().trailing_zeros() as usize);
+                assert!(proof.verify(*hash, root));
+                assert!(!proof.verify(Hash::from_u64_word(100), root));
+                assert!(!MerkleProof { index: index ^ 1, ..proof.clone() }.verify(*hash, root));
+            }
+            assert!(MerkleProof::new(&hashes, hashes.len()).is_none());
+        }
+
+        // The accepted IDs are committed to regardless of their order
+        let ids = [Hash::from_u64_word(3), Hash::from_u64_word(1), Hash::from_u64_word(2)];
+        assert_eq!(calc_accepted_id_merkle_root(ids), calc_accepted_id_merkle_root(ids.into_iter().rev()));
+    }
+}
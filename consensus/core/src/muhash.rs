@@ -0,0 +1,76 @@
+//! Commitments to UTXO sets with MuHash, each UTXO being an element of the multiset
+//!
+//! See kaspad `utxo_set_hash.go`.
+
+use crate::tx::{TransactionOutpoint, UtxoEntry};
+use hashes::Hash;
+use muhash::MuHash;
+
+pub trait MuHashExtensions {
+    fn add_utxo(&mut self, outpoint: &TransactionOutpoint, entry: &UtxoEntry);
+    fn remove_utxo(&mut self, outpoint: &TransactionOutpoint, entry: &UtxoEntry);
+}
+
+impl MuHashExtensions for MuHash {
+    fn add_utxo(&mut self, outpoint: &TransactionOutpoint, entry: &UtxoEntry) {
+        self.add_element(&serialize_utxo(outpoint, entry));
+    }
+
+    fn remove_utxo(&mut self, outpoint: &TransactionOutpoint, entry: &UtxoEntry) {
+        self.remove_element(&serialize_utxo(outpoint, entry));
+    }
+}
+
+/// Computes the commitment to a whole UTXO set, as found in the `utxo_commitment` of block headers.
+pub fn calc_utxo_commitment<'a>(utxos: impl IntoIterator<Item = (&'a TransactionOutpoint, &'a UtxoEntry)>) -> Hash {
+    let mut muhash = MuHash::new();
+    utxos.into_iter().for_each(|(outpoint, entry)| muhash.add_utxo(outpoint, entry));
+    muhash.finalize()
+}
+
+fn serialize_utxo(outpoint: &TransactionOutpoint, entry: &UtxoEntry) -> Vec<u8> {
+    let script = &entry.script_public_key.script;
+    let mut bytes = Vec::with_capacity(32 + 4 + 8 + 8 + 1 + 2 + 8 + script.len());
+    bytes.extend(outpoint.transaction_id.as_bytes());
+    bytes.extend(outpoint.index.to_le_bytes());
+    bytes.extend(entry.block_daa_score.to_le_bytes());
+    bytes.extend(entry.amount.to_le_bytes());
+    bytes.push(entry.is_coinbase as u8);
+    bytes.extend(entry.script_public_key.version.to_le_bytes());
+    bytes.extend((script.len() as u64).to_le_bytes());
+    bytes.extend(script);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx::ScriptPublicKey;
+    use muhash::EMPTY_MUHASH;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_utxo_commitment() {
+        let script_public_key = Arc::new(ScriptPublicKey::new(vec![0x51], 0));
+        let utxos: Vec<(TransactionOutpoint, UtxoEntry)> = (0..4)
+            .map(|i| {
+                (
+                    TransactionOutpoint::new(Hash::from_u64_word(i), i as u32),
+                    UtxoEntry::new(i * 100, script_public_key.clone(), i, i == 0),
+                )
+            })
+            .collect();
+        assert_eq!(calc_utxo_commitment([]), EMPTY_MUHASH);
+
+        // The commitment doesn't depend on the order of the UTXOs nor on the way the set was reached
+        let commitment = calc_utxo_commitment(utxos.iter().map(|(outpoint, entry)| (outpoint, entry)));
+        assert_eq!(commitment, calc_utxo_commitment(utxos.iter().rev().map(|(outpoint, entry)| (outpoint, entry))));
+        let mut muhash = MuHash::new();
+        let spent = (TransactionOutpoint::new(Hash::from_u64_word(9), 0), UtxoEntry::new(1, script_public_key, 0, false));
+        muhash.add_utxo(&spent.0, &spent.1);
+        utxos.iter().for_each(|(outpoint, entry)| muhash.add_utxo(outpoint, entry));
+        muhash.remove_utxo(&spent.0, &spent.1);
+        assert_eq!(muhash.finalize(), commitment);
+        assert_ne!(commitment, calc_utxo_commitment(utxos[1..].iter().map(|(outpoint, entry)| (outpoint, entry))));
+    }
+}
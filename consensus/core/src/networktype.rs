@@ -0,0 +1,75 @@
+use addresses::Prefix;
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error)]
+#[error("Invalid network type {0}")]
+pub struct NetworkTypeError(String);
+
+/// The networks a node may run against
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub enum NetworkType {
+    Mainnet,
+    Testnet,
+    Simnet,
+    Devnet,
+}
+
+impl NetworkType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NetworkType::Mainnet => "mainnet",
+            NetworkType::Testnet => "testnet",
+            NetworkType::Simnet => "simnet",
+            NetworkType::Devnet => "devnet",
+        }
+    }
+}
+
+impl Display for NetworkType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NetworkType {
+    type Err = NetworkTypeError;
+
+    fn from_str(network_type: &str) -> Result<Self, Self::Err> {
+        match network_type {
+            "mainnet" => Ok(NetworkType::Mainnet),
+            "testnet" => Ok(NetworkType::Testnet),
+            "simnet" => Ok(NetworkType::Simnet),
+            "devnet" => Ok(NetworkType::Devnet),
+            _ => Err(NetworkTypeError(network_type.to_string())),
+        }
+    }
+}
+
+/// The address prefix of a network
+impl From<NetworkType> for Prefix {
+    fn from(network_type: NetworkType) -> Self {
+        match network_type {
+            NetworkType::Mainnet => Prefix::Mainnet,
+            NetworkType::Testnet => Prefix::Testnet,
+            NetworkType::Simnet => Prefix::Simnet,
+            NetworkType::Devnet => Prefix::Devnet,
+        }
+    }
+}
+
+impl From<Prefix> for NetworkType {
+    fn from(prefix: Prefix) -> Self {
+        match prefix {
+            Prefix::Mainnet => NetworkType::Mainnet,
+            Prefix::Testnet => NetworkType::Testnet,
+            Prefix::Simnet => NetworkType::Simnet,
+            Prefix::Devnet => NetworkType::Devnet,
+        }
+    }
+}
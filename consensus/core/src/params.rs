@@ -0,0 +1,118 @@
+//! Parameters of the networks a node may run against
+//!
+//! See kaspad `dagconfig/params.go`.
+
+use crate::networktype::NetworkType;
+use addresses::Prefix;
+use hashes::Hash;
+
+/// Parameters defining a network
+#[derive(Clone, Debug)]
+pub struct Params {
+    pub network_type: NetworkType,
+
+    /// Human readable name of the network
+    pub name: &'static str,
+
+    /// Prefix of the addresses of the network
+    pub prefix: Prefix,
+
+    /// Hash of the genesis block of the network
+    pub genesis_hash: Hash,
+
+    /// Target time between blocks, in milliseconds
+    pub target_time_per_block: u64,
+
+    /// Port the RPC server listens to by default
+    pub default_rpc_port: u16,
+
+    /// Number of blocks after which a block is final
+    pub finality_depth: u64,
+}
+
+impl Params {
+    /// Expected time for a block to become final, in milliseconds
+    pub fn finality_duration(&self) -> u64 {
+        self.target_time_per_block * self.finality_depth
+    }
+}
+
+impl From<NetworkType> for &'static Params {
+    fn from(network_type: NetworkType) -> Self {
+        match network_type {
+            NetworkType::Mainnet => &MAINNET_PARAMS,
+            NetworkType::Testnet => &TESTNET_PARAMS,
+            NetworkType::Simnet => &SIMNET_PARAMS,
+            NetworkType::Devnet => &DEVNET_PARAMS,
+        }
+    }
+}
+
+pub const MAINNET_PARAMS: Params = Params {
+    network_type: NetworkType::Mainnet,
+    name: "kaspa-mainnet",
+    prefix: Prefix::Mainnet,
+    genesis_hash: Hash::from_bytes([
+        0x58, 0xc2, 0xd4, 0x19, 0x9e, 0x21, 0xf9, 0x10, 0xd1, 0x57, 0x1d, 0x11, 0x49, 0x69, 0xce, 0xce, 0xf4, 0x8f, 0x09, 0xf9, 0x34,
+        0xd4, 0x2c, 0xcb, 0x6a, 0x28, 0x1a, 0x15, 0x86, 0x8f, 0x29, 0x99,
+    ]),
+    target_time_per_block: 1000,
+    default_rpc_port: 16110,
+    finality_depth: 86400,
+};
+
+pub const TESTNET_PARAMS: Params = Params {
+    network_type: NetworkType::Testnet,
+    name: "kaspa-testnet-10",
+    prefix: Prefix::Testnet,
+    genesis_hash: Hash::from_bytes([
+        0xf8, 0x96, 0xa3, 0x03, 0x48, 0x73, 0xbe, 0x17, 0x39, 0xfc, 0x43, 0x59, 0x23, 0x68, 0x99, 0xfd, 0x3d, 0x65, 0xd2, 0xbc, 0x94,
+        0xf9, 0x78, 0x0d, 0xf0, 0xd0, 0xda, 0x3e, 0xb1, 0xcc, 0x43, 0x70,
+    ]),
+    target_time_per_block: 1000,
+    default_rpc_port: 16210,
+    finality_depth: 86400,
+};
+
+pub const SIMNET_PARAMS: Params = Params {
+    network_type: NetworkType::Simnet,
+    name: "kaspa-simnet",
+    prefix: Prefix::Simnet,
+    genesis_hash: Hash::from_bytes([
+        0x41, 0x1f, 0x8c, 0xd2, 0x6f, 0x3d, 0x41, 0xae, 0xa3, 0x9e, 0x78, 0x57, 0x39, 0x27, 0xda, 0x24, 0xd2, 0x39, 0x95, 0x70, 0x5b,
+        0x57, 0x9f, 0x30, 0x95, 0x9b, 0x91, 0x27, 0xe9, 0x6b, 0x79, 0xe3,
+    ]),
+    target_time_per_block: 1000,
+    default_rpc_port: 16510,
+    finality_depth: 86400,
+};
+
+pub const DEVNET_PARAMS: Params = Params {
+    network_type: NetworkType::Devnet,
+    name: "kaspa-devnet",
+    prefix: Prefix::Devnet,
+    genesis_hash: Hash::from_bytes([
+        0xb3, 0x13, 0x87, 0x0a, 0x32, 0xc7, 0x04, 0xbd, 0xf1, 0x21, 0x4a, 0x3b, 0x27, 0x0c, 0xc4, 0x75, 0xd9, 0x42, 0xc2, 0x09, 0x2d,
+        0x37, 0x9b, 0xc8, 0x70, 0x0a, 0xb0, 0x43, 0x31, 0x9e, 0xf8, 0x46,
+    ]),
+    target_time_per_block: 1000,
+    default_rpc_port: 16610,
+    finality_depth: 86400,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params() {
+        for network_type in [NetworkType::Mainnet, NetworkType::Testnet, NetworkType::Simnet, NetworkType::Devnet] {
+            let params: &Params = network_type.into();
+            assert_eq!(params.network_type, network_type);
+            assert_eq!(params.prefix, network_type.into());
+            assert!(params.name.ends_with(network_type.as_str()) || params.name.starts_with("kaspa-testnet"));
+        }
+        assert_eq!(MAINNET_PARAMS.genesis_hash.to_string(), "58c2d4199e21f910d1571d114969cecef48f09f934d42ccb6a281a15868f2999");
+        assert_eq!(MAINNET_PARAMS.finality_duration(), 24 * 60 * 60 * 1000);
+    }
+}
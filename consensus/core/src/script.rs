@@ -0,0 +1,205 @@
+//! Standard script templates: classification, address extraction and script generation
+//!
+//! See kaspad `txscript/standard.go`.
+
+use crate::tx::ScriptPublicKey;
+use addresses::{Address, AddressError, Prefix, Version};
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+
+pub mod opcodes {
+    pub const OP_DATA_32: u8 = 0x20;
+    pub const OP_DATA_33: u8 = 0x21;
+    pub const OP_EQUAL: u8 = 0x87;
+    pub const OP_BLAKE2B: u8 = 0xaa;
+    pub const OP_CHECKSIGECDSA: u8 = 0xab;
+    pub const OP_CHECKSIG: u8 = 0xac;
+}
+
+use opcodes::*;
+
+/// The only script version having standard templates
+pub const MAX_STANDARD_SCRIPT_VERSION: u16 = 0;
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum ScriptError {
+    #[error("script is not of a standard form")]
+    NonStandardScript,
+
+    #[error("{0}")]
+    AddressError(#[from] AddressError),
+}
+
+pub type ScriptResult<T> = std::result::Result<T, ScriptError>;
+
+/// Class of a script public key, identifying the standard templates
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ScriptClass {
+    /// None of the recognized forms
+    NonStandard,
+
+    /// Pay to a Schnorr public key: OP_DATA_32 <public key> OP_CHECKSIG
+    PubKey,
+
+    /// Pay to an ECDSA public key: OP_DATA_33 <public key> OP_CHECKSIGECDSA
+    PubKeyECDSA,
+
+    /// Pay to a script hash: OP_BLAKE2B OP_DATA_32 <script hash> OP_EQUAL
+    ScriptHash,
+}
+
+impl ScriptClass {
+    pub fn from_script(script_public_key: &ScriptPublicKey) -> Self {
+        if script_public_key.version > MAX_STANDARD_SCRIPT_VERSION {
+            return ScriptClass::NonStandard;
+        }
+        let script = script_public_key.script.as_slice();
+        match script {
+            [OP_DATA_32, .., OP_CHECKSIG] if script.len() == 34 => ScriptClass::PubKey,
+            [OP_DATA_33, .., OP_CHECKSIGECDSA] if script.len() == 35 => ScriptClass::PubKeyECDSA,
+            [OP_BLAKE2B, OP_DATA_32, .., OP_EQUAL] if script.len() == 35 => ScriptClass::ScriptHash,
+            _ => ScriptClass::NonStandard,
+        }
+    }
+
+    /// Version of the addresses paid by scripts of this class
+    pub fn address_version(&self) -> Option<Version> {
+        match self {
+            ScriptClass::NonStandard => None,
+            ScriptClass::PubKey => Some(Version::PubKey),
+            ScriptClass::PubKeyECDSA => Some(Version::PubKeyECDSA),
+            ScriptClass::ScriptHash => Some(Version::ScriptHash),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScriptClass::NonStandard => "nonstandard",
+            ScriptClass::PubKey => "pubkey",
+            ScriptClass::PubKeyECDSA => "pubkeyecdsa",
+            ScriptClass::ScriptHash => "scripthash",
+        }
+    }
+}
+
+impl Display for ScriptClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Extracts the address paid by a standard script public key.
+pub fn extract_script_pub_key_address(script_public_key: &ScriptPublicKey, prefix: Prefix) -> ScriptResult<Address> {
+    let class = ScriptClass::from_script(script_public_key);
+    let script = script_public_key.script.as_slice();
+    let payload = match class {
+        ScriptClass::NonStandard => return Err(ScriptError::NonStandardScript),
+        ScriptClass::PubKey => &script[1..33],
+        ScriptClass::PubKeyECDSA => &script[1..34],
+        ScriptClass::ScriptHash => &script[2..34],
+    };
+    Ok(Address::new(prefix, class.address_version().unwrap(), payload)?)
+}
+
+/// Generates the standard script public key paying to an address.
+pub fn pay_to_address_script(address: &Address) -> ScriptPublicKey {
+    let script = match address.version {
+        Version::PubKey => [&[OP_DATA_32], address.payload.as_slice(), &[OP_CHECKSIG]].concat(),
+        Version::PubKeyECDSA => [&[OP_DATA_33], address.payload.as_slice(), &[OP_CHECKSIGECDSA]].concat(),
+        Version::ScriptHash => [&[OP_BLAKE2B, OP_DATA_32], address.payload.as_slice(), &[OP_EQUAL]].concat(),
+    };
+    ScriptPublicKey::new(script, MAX_STANDARD_SCRIPT_VERSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        let mut bytes = vec![0u8; hex.len() / 2];
+        if !hex.is_empty() {
+            faster_hex::hex_decode(hex.as_bytes(), &mut bytes).unwrap();
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_standard_scripts() {
+        struct Test {
+            name: &'static str,
+            script: &'static str,
+            version: u16,
+            prefix: Prefix,
+            class: ScriptClass,
+            address: Option<&'static str>,
+        }
+
+        // Vectors from kaspad
+        let tests = vec![
+            Test {
+                name: "mainnet p2pk schnorr",
+                script: "207bc04196f1125e4f2676cd09ed14afb77223b1f62177da5488346323eaa91a69ac",
+                version: 0,
+                prefix: Prefix::Mainnet,
+                class: ScriptClass::PubKey,
+                address: Some("kaspa:qpauqsvk7yf9unexwmxsnmg547mhyga37csh0kj53q6xxgl24ydxjsgzthw5j"),
+            },
+            Test {
+                name: "testnet p2pk ecdsa",
+                script: "21ba01fc5f4e9d9879599c69a3dafdb835a7255e5f2e934e9322ecd3af190ab0f60eab",
+                version: 0,
+                prefix: Prefix::Testnet,
+                class: ScriptClass::PubKeyECDSA,
+                address: Some("kaspatest:qxaqrlzlf6wes72en3568khahq66wf27tuhfxn5nytkd8tcep2c0vrse6gdmpks"),
+            },
+            Test {
+                name: "testnet non standard script",
+                script: "2001fc5f4e9d9879599c69a3dafdb835a7255e5f2e934e9322ecd3af190ab0f60eab",
+                version: 0,
+                prefix: Prefix::Testnet,
+                class: ScriptClass::NonStandard,
+                address: None,
+            },
+            Test {
+                name: "mainnet script with unknown version",
+                script: "207bc04196f1125e4f2676cd09ed14afb77223b1f62177da5488346323eaa91a69ac",
+                version: 1,
+                prefix: Prefix::Mainnet,
+                class: ScriptClass::NonStandard,
+                address: None,
+            },
+            Test {
+                name: "empty script",
+                script: "",
+                version: 0,
+                prefix: Prefix::Mainnet,
+                class: ScriptClass::NonStandard,
+                address: None,
+            },
+        ];
+
+        for test in tests {
+            let script_public_key = ScriptPublicKey::new(from_hex(test.script), test.version);
+            assert_eq!(ScriptClass::from_script(&script_public_key), test.class, "{}", test.name);
+
+            let address = extract_script_pub_key_address(&script_public_key, test.prefix);
+            match test.address {
+                Some(expected) => {
+                    let address = address.unwrap();
+                    assert_eq!(address.to_string(), expected, "{}", test.name);
+                    assert_eq!(pay_to_address_script(&address), script_public_key, "{}", test.name);
+                }
+                None => assert_eq!(address, Err(ScriptError::NonStandardScript), "{}", test.name),
+            }
+        }
+    }
+
+    #[test]
+    fn test_script_hash_round_trip() {
+        let address = Address::new(Prefix::Simnet, Version::ScriptHash, &[0x5a; 32]).unwrap();
+        let script_public_key = pay_to_address_script(&address);
+        assert_eq!(script_public_key.script.len(), 35);
+        assert_eq!(ScriptClass::from_script(&script_public_key), ScriptClass::ScriptHash);
+        assert_eq!(extract_script_pub_key_address(&script_public_key, Prefix::Simnet).unwrap(), address);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::block::Block;
+use crate::{api::BlockDagContext, block::Block, networktype::NetworkType};
 
 #[derive(Debug, Clone)]
 pub enum Notification {
@@ -8,4 +8,10 @@ pub enum Notification {
 #[derive(Debug, Clone)]
 pub struct BlockAddedNotification {
     pub block: Block,
+
+    /// Relations of the block in the DAG at the time of the notification
+    pub context: BlockDagContext,
+
+    /// Network of the block, defining the prefix of the addresses its outputs pay to
+    pub network_type: NetworkType,
 }
@@ -0,0 +1,84 @@
+//! Proof of work: kHeavyHash and its check against the target claimed by a header
+//!
+//! The PoW value of a header is `kHeavyHash(matrix, PowHash(pre_pow_hash || timestamp || 32 zero bytes || nonce))`,
+//! the pre-PoW hash being the header hash with a zero nonce and timestamp, and the matrix being generated
+//! from that same hash. See kaspad `pow.go`.
+
+mod matrix;
+mod xoshiro;
+
+pub use matrix::Matrix;
+
+use consensus_core::{hashing, header::Header};
+use hashes::PowHash;
+use math::Uint256;
+
+/// Values of a header fixed while searching a nonce, so that mining only has to hash the nonce.
+pub struct State {
+    matrix: Matrix,
+    target: Uint256,
+    // PRE_POW_HASH || TIME || 32 zero byte padding, without the nonce
+    hasher: PowHash,
+}
+
+impl State {
+    pub fn new(header: &Header) -> Self {
+        let target = Uint256::from_compact_target_bits(header.bits);
+        let pre_pow_hash = hashing::header::hash_override_nonce_time(header, 0, 0);
+        let hasher = PowHash::new(pre_pow_hash, header.timestamp);
+        let matrix = Matrix::generate(pre_pow_hash);
+        Self { matrix, target, hasher }
+    }
+
+    /// Target decoded from the header bits
+    pub fn target(&self) -> Uint256 {
+        self.target
+    }
+
+    #[must_use]
+    pub fn calculate_pow(&self, nonce: u64) -> Uint256 {
+        let hash = self.hasher.clone().finalize_with_nonce(nonce);
+        let hash = self.matrix.heavy_hash(hash);
+        Uint256::from_le_bytes(hash.as_bytes())
+    }
+
+    /// Returns whether the PoW value of `nonce` is at most the target, along with that value.
+    #[must_use]
+    pub fn check_pow(&self, nonce: u64) -> (bool, Uint256) {
+        let pow = self.calculate_pow(nonce);
+        (pow <= self.target, pow)
+    }
+}
+
+/// Checks the PoW of a header with its own nonce.
+pub fn check_header_pow(header: &Header) -> bool {
+    State::new(header).check_pow(header.nonce).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hashes::Hash;
+
+    #[test]
+    fn test_check_pow() {
+        // The easiest target: about half of the nonces are valid
+        let mut header = Header::new(1, vec![Hash::from_u64_word(1)], Default::default(), 1_668_000_000_000, 0x207fffff, 0, 1, 1, 1);
+        let state = State::new(&header);
+        let valid = (0..64).filter(|nonce| state.check_pow(*nonce).0).count();
+        assert!(valid > 0 && valid < 64);
+
+        let nonce = (0..).find(|nonce| state.check_pow(*nonce).0).unwrap();
+        header.nonce = nonce;
+        header.finalize();
+        assert!(check_header_pow(&header));
+
+        // Changing any other field of the header changes the PoW value
+        header.timestamp += 1;
+        assert_ne!(State::new(&header).calculate_pow(nonce), state.calculate_pow(nonce));
+
+        // A zero target, as decoded from a negative mantissa, can't be reached
+        header.bits = 0x04923456;
+        assert!(!check_header_pow(&header));
+    }
+}
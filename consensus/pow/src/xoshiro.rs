@@ -0,0 +1,35 @@
+use hashes::Hash;
+use std::num::Wrapping;
+
+/// The xoshiro256++ generator, seeded with the words of a hash.
+///
+/// See <https://prng.di.unimi.it/xoshiro256plusplus.c>
+pub(crate) struct XoShiRo256PlusPlus {
+    s0: Wrapping<u64>,
+    s1: Wrapping<u64>,
+    s2: Wrapping<u64>,
+    s3: Wrapping<u64>,
+}
+
+impl XoShiRo256PlusPlus {
+    #[inline]
+    pub(crate) fn new(hash: Hash) -> Self {
+        let hash = hash.to_le_u64();
+        Self { s0: Wrapping(hash[0]), s1: Wrapping(hash[1]), s2: Wrapping(hash[2]), s3: Wrapping(hash[3]) }
+    }
+
+    #[inline]
+    pub(crate) fn u64(&mut self) -> u64 {
+        let res = self.s0 + Wrapping((self.s0 + self.s3).0.rotate_left(23));
+        let t = self.s1 << 17;
+        self.s2 ^= self.s0;
+        self.s3 ^= self.s1;
+        self.s1 ^= self.s2;
+        self.s0 ^= self.s3;
+
+        self.s2 ^= t;
+        self.s3 = Wrapping(self.s3.0.rotate_left(45));
+
+        res.0
+    }
+}
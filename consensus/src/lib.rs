@@ -1 +1,3 @@
 pub mod notifiy;
+pub mod simulator;
+pub mod store;
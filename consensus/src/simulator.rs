@@ -0,0 +1,901 @@
+//! A seeded deterministic block DAG simulator
+//!
+//! Blocks are produced in rounds. All the blocks of a round are built in parallel on top of the
+//! tips known at the start of the round, so the DAG gets as wide as [`SimulatorConfig::parallelism`].
+//! Every block gets a selected parent, a merge set colored blue and red in a GHOSTDAG-like fashion
+//! and the matching blue score, blue work and DAA score. Blocks carry a coinbase transaction paying
+//! a simulated wallet and transfer transactions spending previously created UTXOs.
+//!
+//! Timestamps follow a simulated clock so a given config always produces the same DAG.
+
+use crate::{notifiy::ConsensusNotificationChannel, store::BlockStore};
+use async_std::channel::{Receiver, Sender};
+use consensus_core::{
+    api::{ConsensusApi, VirtualState},
+    block::Block,
+    difficulty,
+    errors::{ConsensusError, ConsensusResult},
+    ghostdag::GhostdagData,
+    header::Header,
+    merkle,
+    muhash::MuHashExtensions,
+    networktype::NetworkType,
+    stubs::{BlockAddedNotification, Notification as ConsensusNotification},
+    subnets::{SUBNETWORK_ID_COINBASE, SUBNETWORK_ID_NATIVE},
+    subsidy,
+    tx::{ScriptPublicKey, Transaction, TransactionId, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry},
+    BlueWorkType,
+};
+use hashes::Hash;
+use kaspa_core::{error, trace};
+use kaspa_utils::channel::Channel;
+use muhash::MuHash;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use thiserror::Error;
+
+/// Settings of a [`DagSimulator`]
+#[derive(Clone, Debug)]
+pub struct SimulatorConfig {
+    /// Seed of the random generator, simulators sharing a same config producing the same DAG
+    pub seed: u64,
+
+    /// Average number of blocks produced per second
+    pub block_rate: f64,
+
+    /// Number of blocks produced in a round, all on top of the same tips
+    pub parallelism: usize,
+
+    /// Maximum number of parents of a block
+    pub max_parents: usize,
+
+    /// GHOSTDAG K parameter, ie. the maximum number of blues in the anticone of a blue block
+    pub ghostdag_k: usize,
+
+    /// Difficulty bits of all blocks, which must encode an easy target since the simulator mines its blocks
+    pub bits: u32,
+
+    /// Timestamp of the genesis block in milliseconds since the Unix epoch
+    pub genesis_timestamp: u64,
+
+    /// Number of simulated wallets receiving and spending coins
+    pub wallets: usize,
+
+    /// Maximum number of transfer transactions in a block
+    pub max_transfers_per_block: usize,
+
+    /// Fee paid by every transfer transaction, in sompi
+    pub transfer_fee: u64,
+
+    /// DAA score delay before a coinbase output can be spent
+    pub coinbase_maturity: u64,
+
+    /// Network the simulated blocks belong to
+    pub network_type: NetworkType,
+}
+
+impl SimulatorConfig {
+    /// Checks that the settings describe a DAG the simulator can produce.
+    pub fn validate(&self) -> Result<(), SimulatorConfigError> {
+        if !self.block_rate.is_finite() || self.block_rate <= 0.0 {
+            return Err(SimulatorConfigError::InvalidBlockRate(self.block_rate));
+        }
+        match Duration::try_from_secs_f64(self.parallelism.max(1) as f64 / self.block_rate) {
+            Ok(interval) if !interval.is_zero() => Ok(()),
+            _ => Err(SimulatorConfigError::InvalidRoundInterval(self.parallelism, self.block_rate)),
+        }
+    }
+
+    /// Simulated duration of a round
+    ///
+    /// Panics if the config is not valid, see [`SimulatorConfig::validate`].
+    pub fn round_interval(&self) -> Duration {
+        Duration::from_secs_f64(self.parallelism.max(1) as f64 / self.block_rate)
+    }
+}
+
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum SimulatorConfigError {
+    #[error("block rate must be a positive finite number of blocks per second, got {0}")]
+    InvalidBlockRate(f64),
+
+    #[error("{0} blocks per round at {1} blocks per second give a round interval out of range")]
+    InvalidRoundInterval(usize, f64),
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            block_rate: 1.0,
+            parallelism: 2,
+            max_parents: 10,
+            ghostdag_k: 18,
+            bits: 0x207fffff,
+            genesis_timestamp: 1_668_000_000_000,
+            wallets: 8,
+            max_transfers_per_block: 4,
+            transfer_fee: 2_000,
+            coinbase_maturity: 100,
+            network_type: NetworkType::Simnet,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct DagState {
+    config: SimulatorConfig,
+    rng: StdRng,
+    store: Arc<BlockStore>,
+    tips: Vec<Hash>,
+    round: u64,
+
+    /// Script public keys of the simulated wallets
+    wallets: Vec<Arc<ScriptPublicKey>>,
+
+    /// Spendable outputs, kept in a vector so that random picks are deterministic
+    utxos: Vec<(TransactionOutpoint, UtxoEntry)>,
+
+    /// Outputs of blocks not yet in the past of all tips, along with the hash of their block
+    pending_utxos: Vec<(Hash, TransactionOutpoint, UtxoEntry)>,
+
+    /// Rolling commitment to the virtual UTXO set, spendable and pending outputs alike
+    utxo_muhash: MuHash,
+}
+
+impl DagState {
+    fn new(config: SimulatorConfig, store: Arc<BlockStore>) -> (Self, Block) {
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let wallets = (0..config.wallets.max(1)).map(|_| Arc::new(p2pk_script(rng.gen()))).collect();
+        let genesis =
+            Block::from_header(Header::new(1, vec![], Default::default(), config.genesis_timestamp, config.bits, 0, 0, 0, 0));
+        store.insert(&genesis, GhostdagData::genesis()).unwrap();
+        let state = Self {
+            config,
+            rng,
+            store,
+            tips: vec![genesis.hash()],
+            round: 0,
+            wallets,
+            utxos: Vec::new(),
+            pending_utxos: Vec::new(),
+            utxo_muhash: MuHash::new(),
+        };
+        (state, genesis)
+    }
+
+    fn is_ancestor(&self, ancestor: Hash, block: Hash) -> bool {
+        self.store.is_dag_ancestor_of(ancestor, block).unwrap()
+    }
+
+    fn is_in_anticone(&self, a: Hash, b: Hash) -> bool {
+        self.store.is_in_anticone(a, b).unwrap()
+    }
+
+    fn blue_work(&self, hash: Hash) -> BlueWorkType {
+        self.store.get_ghostdag_data(hash).unwrap().blue_work
+    }
+
+    fn ghostdag(&self, parents: &[Hash]) -> GhostdagData {
+        let selected_parent = *parents.iter().max_by_key(|hash| (self.blue_work(**hash), **hash)).unwrap();
+
+        // The merge set is the past of the block not in the past of its selected parent
+        let mut mergeset = Vec::new();
+        let mut visited = HashSet::new();
+        let mut queue: Vec<Hash> = parents.iter().copied().filter(|hash| *hash != selected_parent).collect();
+        while let Some(hash) = queue.pop() {
+            if !visited.insert(hash) || self.is_ancestor(hash, selected_parent) {
+                continue;
+            }
+            mergeset.push(hash);
+            queue.extend(self.store.get_header(hash).unwrap().direct_parents().iter().copied());
+        }
+        mergeset.sort_by_key(|hash| (self.blue_work(*hash), *hash));
+
+        let mut mergeset_blues = vec![selected_parent];
+        let mut mergeset_reds = Vec::new();
+        for candidate in mergeset {
+            let blue_anticone_size = mergeset_blues.iter().filter(|blue| self.is_in_anticone(**blue, candidate)).count();
+            if blue_anticone_size <= self.config.ghostdag_k {
+                mergeset_blues.push(candidate);
+            } else {
+                mergeset_reds.push(candidate);
+            }
+        }
+
+        let selected_parent_data = self.store.get_ghostdag_data(selected_parent).unwrap();
+        let blue_score = selected_parent_data.blue_score + mergeset_blues.len() as u64;
+        let blue_work = selected_parent_data.blue_work
+            + mergeset_blues
+                .iter()
+                .map(|hash| difficulty::calc_work(self.store.get_header(*hash).unwrap().bits))
+                .sum::<BlueWorkType>();
+        GhostdagData { blue_score, blue_work, selected_parent, mergeset_blues, mergeset_reds }
+    }
+
+    /// IDs of the transactions accepted by a block having the given GHOSTDAG data, ie. those of its blue merge set
+    fn accepted_ids(&self, ghostdag: &GhostdagData) -> Vec<TransactionId> {
+        ghostdag
+            .mergeset_blues
+            .iter()
+            .flat_map(|hash| self.store.get_block(*hash).unwrap().transactions.iter().map(|tx| tx.id()).collect::<Vec<_>>())
+            .collect()
+    }
+
+    /// DAA score of a block having the given GHOSTDAG data
+    fn daa_score(&self, ghostdag: &GhostdagData) -> u64 {
+        self.store.get_header(ghostdag.selected_parent).unwrap().daa_score + ghostdag.mergeset_size() as u64
+    }
+
+    /// Produces the blocks of the next round.
+    fn produce_round(&mut self) -> Vec<Block> {
+        self.round += 1;
+        let timestamp = self.config.genesis_timestamp + self.round * self.config.round_interval().as_millis() as u64;
+
+        // Tips sorted by decreasing blue work, so the first block of the round merges the heaviest ones
+        let mut tips = self.tips.clone();
+        tips.sort_by_key(|hash| std::cmp::Reverse((self.blue_work(*hash), *hash)));
+        let max_parents = self.config.max_parents.clamp(1, tips.len());
+
+        // All the blocks of the round commit to the UTXO set they were built on, before any of them spends or adds outputs
+        let utxo_commitment = self.utxo_muhash.finalize();
+
+        let mut blocks = Vec::with_capacity(self.config.parallelism);
+        for i in 0..self.config.parallelism.max(1) {
+            let mut parents: Vec<Hash> = match i {
+                0 => tips[..max_parents].to_vec(),
+                _ => {
+                    let count = self.rng.gen_range(1..=max_parents);
+                    tips.choose_multiple(&mut self.rng, count).copied().collect()
+                }
+            };
+            parents.sort();
+
+            let ghostdag = self.ghostdag(&parents);
+            let daa_score = self.daa_score(&ghostdag);
+            let transactions = self.build_transactions(ghostdag.blue_score, daa_score);
+            let mut header = Header::new(
+                1,
+                parents.clone(),
+                merkle::calc_hash_merkle_root(&transactions),
+                timestamp + i as u64,
+                self.config.bits,
+                0,
+                daa_score,
+                ghostdag.blue_work,
+                ghostdag.blue_score,
+            );
+            header.utxo_commitment = utxo_commitment;
+            header.accepted_id_merkle_root = merkle::calc_accepted_id_merkle_root(self.accepted_ids(&ghostdag));
+            header.nonce = self.mine(&header);
+            header.finalize();
+            trace!("simulated block {0} with {1} parents and {2} transactions", header.hash, parents.len(), transactions.len());
+
+            let block = Block { header, transactions: Arc::new(transactions) };
+            self.add_pending_utxos(&block, daa_score);
+            self.store.insert(&block, ghostdag).unwrap();
+            blocks.push(block);
+        }
+
+        let referenced: HashSet<Hash> = blocks.iter().flat_map(|block| block.header.direct_parents().iter().copied()).collect();
+        self.tips.retain(|hash| !referenced.contains(hash));
+        self.tips.extend(blocks.iter().map(|block| block.hash()));
+        self.confirm_pending_utxos();
+        let sink = self.virtual_ghostdag().selected_parent;
+        self.store.update_sink(sink).unwrap();
+        blocks
+    }
+
+    /// Finds a nonce satisfying the target of the header.
+    fn mine(&mut self, header: &Header) -> u64 {
+        let state = consensus_pow::State::new(header);
+        loop {
+            let nonce = self.rng.gen();
+            if state.check_pow(nonce).0 {
+                return nonce;
+            }
+        }
+    }
+
+    /// Adds a block built outside of the simulator, its parents being known and `ghostdag` being its GHOSTDAG data.
+    ///
+    /// The outputs spent by the block are removed from the UTXO set, but its inputs are not validated.
+    fn add_block(&mut self, block: &Block, ghostdag: GhostdagData) {
+        let daa_score = block.header.daa_score;
+        let spent: HashSet<TransactionOutpoint> =
+            block.transactions.iter().flat_map(|tx| tx.inputs.iter().map(|input| input.previous_outpoint)).collect();
+        let muhash = &mut self.utxo_muhash;
+        let mut retain = |outpoint: &TransactionOutpoint, entry: &UtxoEntry| match spent.contains(outpoint) {
+            true => {
+                muhash.remove_utxo(outpoint, entry);
+                false
+            }
+            false => true,
+        };
+        self.utxos.retain(|(outpoint, entry)| retain(outpoint, entry));
+        self.pending_utxos.retain(|(_, outpoint, entry)| retain(outpoint, entry));
+        self.add_pending_utxos(block, daa_score);
+        self.store.insert(block, ghostdag).unwrap();
+
+        self.tips.retain(|hash| !block.header.direct_parents().contains(hash));
+        self.tips.push(block.hash());
+        self.confirm_pending_utxos();
+        let sink = self.virtual_ghostdag().selected_parent;
+        self.store.update_sink(sink).unwrap();
+    }
+
+    fn add_pending_utxos(&mut self, block: &Block, daa_score: u64) {
+        for tx in block.transactions.iter() {
+            for (index, output) in tx.outputs.iter().enumerate() {
+                let outpoint = TransactionOutpoint::new(tx.id(), index as u32);
+                let entry = UtxoEntry::new(output.value, output.script_public_key.clone(), daa_score, tx.is_coinbase());
+                self.utxo_muhash.add_utxo(&outpoint, &entry);
+                self.pending_utxos.push((block.hash(), outpoint, entry));
+            }
+        }
+    }
+
+    fn virtual_ghostdag(&self) -> GhostdagData {
+        let mut parents = self.tips.clone();
+        parents.sort();
+        self.ghostdag(&parents)
+    }
+
+    fn virtual_state(&self) -> VirtualState {
+        let mut parents = self.tips.clone();
+        parents.sort();
+        let ghostdag = self.ghostdag(&parents);
+        VirtualState {
+            parents,
+            selected_parent: ghostdag.selected_parent,
+            blue_score: ghostdag.blue_score,
+            blue_work: ghostdag.blue_work,
+            daa_score: self.daa_score(&ghostdag),
+            bits: self.config.bits,
+            timestamp: self.store.get_header(ghostdag.selected_parent).unwrap().timestamp,
+        }
+    }
+
+    /// Iterates the virtual UTXO set, made of the outputs of all blocks not spent yet.
+    fn virtual_utxos(&self) -> impl Iterator<Item = (&TransactionOutpoint, &UtxoEntry)> {
+        self.utxos
+            .iter()
+            .map(|(outpoint, entry)| (outpoint, entry))
+            .chain(self.pending_utxos.iter().map(|(_, outpoint, entry)| (outpoint, entry)))
+    }
+
+    /// Makes spendable the outputs of blocks in the past of all tips, and thus of any future block.
+    fn confirm_pending_utxos(&mut self) {
+        let pending = std::mem::take(&mut self.pending_utxos);
+        let (confirmed, pending): (Vec<_>, Vec<_>) =
+            pending.into_iter().partition(|(hash, _, _)| self.tips.iter().all(|tip| self.is_ancestor(*hash, *tip)));
+        self.utxos.extend(confirmed.into_iter().map(|(_, outpoint, entry)| (outpoint, entry)));
+        self.pending_utxos = pending;
+    }
+
+    fn build_transactions(&mut self, blue_score: u64, daa_score: u64) -> Vec<Transaction> {
+        let mut transactions = Vec::new();
+
+        // Coinbase minting the subsidy, with some extra data in the payload making its id unique among parallel blocks
+        let miner = self.wallets.choose(&mut self.rng).unwrap().clone();
+        let subsidy = subsidy::calc_block_subsidy(daa_score);
+        let extra_data: u64 = self.rng.gen();
+        let payload = [blue_score.to_le_bytes(), subsidy.to_le_bytes(), extra_data.to_le_bytes()].concat();
+        let outputs = vec![Arc::new(TransactionOutput::new(subsidy, miner))];
+        transactions.push(Transaction::new(0, vec![], outputs, 0, SUBNETWORK_ID_COINBASE, 0, payload, 0));
+
+        // Transfers
+        let transfers = self.rng.gen_range(0..=self.config.max_transfers_per_block);
+        for _ in 0..transfers {
+            let (outpoint, entry) = match self.pick_spendable_utxo(daa_score) {
+                Some(utxo) => utxo,
+                None => break,
+            };
+            let amount = entry.amount - self.config.transfer_fee;
+            let change = self.rng.gen_range(0..amount);
+            let outputs = [amount - change, change]
+                .into_iter()
+                .filter(|value| *value > 0)
+                .map(|value| Arc::new(TransactionOutput::new(value, self.wallets.choose(&mut self.rng).unwrap().clone())))
+                .collect();
+            let signature_script = signature_script(&mut self.rng);
+            let input = Arc::new(TransactionInput::new(outpoint, signature_script, 0, 1, Some(entry)));
+            transactions.push(Transaction::new(0, vec![input], outputs, 0, SUBNETWORK_ID_NATIVE, 0, vec![], self.config.transfer_fee));
+        }
+
+        transactions
+    }
+
+    /// Removes a random spendable output from the UTXO set, giving up after a few unlucky picks.
+    fn pick_spendable_utxo(&mut self, daa_score: u64) -> Option<(TransactionOutpoint, UtxoEntry)> {
+        for _ in 0..8 {
+            if self.utxos.is_empty() {
+                return None;
+            }
+            let index = self.rng.gen_range(0..self.utxos.len());
+            let entry = &self.utxos[index].1;
+            let is_mature = !entry.is_coinbase || entry.block_daa_score + self.config.coinbase_maturity <= daa_score;
+            if is_mature && entry.amount > self.config.transfer_fee {
+                let (outpoint, entry) = self.utxos.swap_remove(index);
+                self.utxo_muhash.remove_utxo(&outpoint, &entry);
+                return Some((outpoint, entry));
+            }
+        }
+        None
+    }
+}
+
+/// A pay-to-pubkey script: OP_DATA_32 <public key> OP_CHECKSIG
+fn p2pk_script(public_key: [u8; 32]) -> ScriptPublicKey {
+    ScriptPublicKey::new([&[0x20], &public_key[..], &[0xac]].concat(), 0)
+}
+
+/// A made up Schnorr signature script: OP_DATA_65 <signature> SIGHASH_ALL
+fn signature_script(rng: &mut StdRng) -> Vec<u8> {
+    let mut script = vec![0x41];
+    script.extend((0..64).map(|_| rng.gen::<u8>()));
+    script.push(0x01);
+    script
+}
+
+/// A consensus simulator producing an internally consistent block DAG, see the [module doc](self)
+#[derive(Debug)]
+pub struct DagSimulator {
+    config: SimulatorConfig,
+    state: Mutex<DagState>,
+    store: Arc<BlockStore>,
+    genesis: Block,
+    channel: ConsensusNotificationChannel,
+    terminate: Arc<AtomicBool>,
+}
+
+impl DagSimulator {
+    /// Creates a simulator holding the genesis block only, failing if the config is not valid.
+    pub fn new(config: SimulatorConfig) -> Result<Self, SimulatorConfigError> {
+        config.validate()?;
+        let store = Arc::new(BlockStore::new());
+        let (state, genesis) = DagState::new(config.clone(), store.clone());
+        Ok(Self {
+            config,
+            state: Mutex::new(state),
+            store,
+            genesis,
+            channel: Channel::default(),
+            terminate: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    pub fn config(&self) -> &SimulatorConfig {
+        &self.config
+    }
+
+    pub fn genesis(&self) -> &Block {
+        &self.genesis
+    }
+
+    /// Store of all the blocks of the DAG
+    pub fn store(&self) -> &Arc<BlockStore> {
+        &self.store
+    }
+
+    /// Current tips of the DAG
+    pub fn tips(&self) -> Vec<Hash> {
+        self.state.lock().unwrap().tips.clone()
+    }
+
+    /// Number of blocks in the DAG, genesis included
+    pub fn block_count(&self) -> usize {
+        self.store.len()
+    }
+
+    /// Produces the blocks of the next round without notifying them.
+    pub fn produce_round(&self) -> Vec<Block> {
+        self.state.lock().unwrap().produce_round()
+    }
+
+    /// Starts producing a round every [`SimulatorConfig::round_interval`], notifying every new block.
+    pub async fn start(self: &Arc<Self>) -> Receiver<Arc<ConsensusNotification>> {
+        let simulator = self.clone();
+        let sender = self.channel.sender();
+
+        tokio::spawn(async move {
+            let interval = simulator.config.round_interval();
+            while !simulator.terminate.load(Ordering::SeqCst) {
+                tokio::time::sleep(interval).await;
+                for block in simulator.produce_round() {
+                    simulator.notify_block_added(&sender, block);
+                }
+            }
+        });
+
+        self.channel.receiver()
+    }
+
+    fn notify_block_added(&self, sender: &Sender<Arc<ConsensusNotification>>, block: Block) {
+        trace!("Emit block {0}", block.header.hash);
+        let context = self.get_block_dag_context(block.hash()).unwrap();
+        let notification =
+            ConsensusNotification::BlockAdded(BlockAddedNotification { block, context, network_type: self.config.network_type });
+        if let Err(err) = sender.try_send(Arc::new(notification)) {
+            error!("Emit error: {:?}", err);
+        }
+    }
+
+    pub fn stop(self: Arc<Self>) {
+        self.terminate.store(true, Ordering::SeqCst)
+    }
+}
+
+impl ConsensusApi for DagSimulator {
+    fn network_type(&self) -> NetworkType {
+        self.config.network_type
+    }
+
+    /// Checks the parents, the difficulty, the proof of work, the merkle roots, the scores and the UTXO commitment of the block
+    /// before adding it to the DAG and notifying it.
+    fn validate_and_insert_block(&self, block: Block) -> ConsensusResult<()> {
+        let hash = block.hash();
+        let mut state = self.state.lock().unwrap();
+        if self.store.has(hash) {
+            return Err(ConsensusError::DuplicateBlock(hash));
+        }
+        let parents = block.header.direct_parents();
+        if parents.is_empty() {
+            return Err(ConsensusError::NoParents(hash));
+        }
+        let missing: Vec<Hash> = parents.iter().copied().filter(|parent| !self.store.has(*parent)).collect();
+        if !missing.is_empty() {
+            return Err(ConsensusError::MissingParents(hash, missing));
+        }
+        // The proof of work is checked against the bits of the header, which must thus be the bits of the network
+        if block.header.bits != self.config.bits {
+            return Err(ConsensusError::UnexpectedDifficulty(hash, block.header.bits, self.config.bits));
+        }
+        if !consensus_pow::check_header_pow(&block.header) {
+            return Err(ConsensusError::InvalidPoW(hash));
+        }
+        let hash_merkle_root = merkle::calc_hash_merkle_root(block.transactions.iter());
+        if hash_merkle_root != block.header.hash_merkle_root {
+            return Err(ConsensusError::BadMerkleRoot(hash, block.header.hash_merkle_root, hash_merkle_root));
+        }
+        let ghostdag = state.ghostdag(parents);
+        let daa_score = state.daa_score(&ghostdag);
+        if block.header.daa_score != daa_score {
+            return Err(ConsensusError::UnexpectedHeaderDaaScore(hash, block.header.daa_score, daa_score));
+        }
+        if block.header.blue_score != ghostdag.blue_score {
+            return Err(ConsensusError::UnexpectedHeaderBlueScore(hash, block.header.blue_score, ghostdag.blue_score));
+        }
+        if block.header.blue_work != ghostdag.blue_work {
+            return Err(ConsensusError::UnexpectedHeaderBlueWork(hash, block.header.blue_work, ghostdag.blue_work));
+        }
+        let accepted_id_merkle_root = merkle::calc_accepted_id_merkle_root(state.accepted_ids(&ghostdag));
+        if accepted_id_merkle_root != block.header.accepted_id_merkle_root {
+            return Err(ConsensusError::BadAcceptedIdMerkleRoot(hash, block.header.accepted_id_merkle_root, accepted_id_merkle_root));
+        }
+        // Like the produced ones, the block commits to the UTXO set before it gets added
+        let utxo_commitment = state.utxo_muhash.finalize();
+        if utxo_commitment != block.header.utxo_commitment {
+            return Err(ConsensusError::BadUtxoCommitment(hash, block.header.utxo_commitment, utxo_commitment));
+        }
+        state.add_block(&block, ghostdag);
+        drop(state);
+
+        self.notify_block_added(&self.channel.sender(), block);
+        Ok(())
+    }
+
+    fn get_genesis_hash(&self) -> Hash {
+        self.genesis.hash()
+    }
+
+    fn get_block(&self, hash: Hash) -> ConsensusResult<Block> {
+        self.store.get_block(hash).map_err(|_| ConsensusError::BlockNotFound(hash))
+    }
+
+    fn get_header(&self, hash: Hash) -> ConsensusResult<Header> {
+        self.store.get_header(hash).map(|header| header.as_ref().clone()).map_err(|_| ConsensusError::HeaderNotFound(hash))
+    }
+
+    fn get_ghostdag_data(&self, hash: Hash) -> ConsensusResult<GhostdagData> {
+        self.store.get_ghostdag_data(hash).map(|data| data.as_ref().clone()).map_err(|_| ConsensusError::BlockNotFound(hash))
+    }
+
+    fn get_block_children(&self, hash: Hash) -> ConsensusResult<Vec<Hash>> {
+        self.store.get_children(hash).map_err(|_| ConsensusError::BlockNotFound(hash))
+    }
+
+    fn is_chain_block(&self, hash: Hash) -> ConsensusResult<bool> {
+        self.store.is_chain_block(hash).map_err(|_| ConsensusError::BlockNotFound(hash))
+    }
+
+    fn get_tips(&self) -> Vec<Hash> {
+        self.tips()
+    }
+
+    fn get_virtual_state(&self) -> VirtualState {
+        self.state.lock().unwrap().virtual_state()
+    }
+
+    fn get_utxo(&self, outpoint: &TransactionOutpoint) -> Option<UtxoEntry> {
+        self.state.lock().unwrap().virtual_utxos().find(|(x, _)| *x == outpoint).map(|(_, entry)| entry.clone())
+    }
+
+    fn get_utxos_by_script_public_keys(&self, script_public_keys: &[ScriptPublicKey]) -> Vec<(TransactionOutpoint, UtxoEntry)> {
+        self.state
+            .lock()
+            .unwrap()
+            .virtual_utxos()
+            .filter(|(_, entry)| script_public_keys.contains(&entry.script_public_key))
+            .map(|(outpoint, entry)| (*outpoint, entry.clone()))
+            .collect()
+    }
+
+    fn get_circulating_supply(&self) -> u64 {
+        self.state.lock().unwrap().virtual_utxos().map(|(_, entry)| entry.amount).sum()
+    }
+
+    /// The simulator produces all the blocks by itself so it is always synced.
+    fn is_synced(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_core::muhash::calc_utxo_commitment;
+    use std::collections::HashMap;
+
+    fn run(config: SimulatorConfig, rounds: usize) -> (DagSimulator, Vec<Block>) {
+        let simulator = DagSimulator::new(config).unwrap();
+        let blocks = (0..rounds).flat_map(|_| simulator.produce_round()).collect();
+        (simulator, blocks)
+    }
+
+    #[test]
+    fn test_determinism() {
+        let config = SimulatorConfig { parallelism: 3, ..Default::default() };
+        let (a, a_blocks) = run(config.clone(), 20);
+        let (b, b_blocks) = run(config.clone(), 20);
+        let hashes = |blocks: &Vec<Block>| blocks.iter().map(|block| block.hash()).collect::<Vec<_>>();
+        assert_eq!(hashes(&a_blocks), hashes(&b_blocks));
+        assert_eq!(a.tips(), b.tips());
+
+        let (_, c_blocks) = run(SimulatorConfig { seed: 1, ..config }, 20);
+        assert_ne!(hashes(&a_blocks), hashes(&c_blocks));
+    }
+
+    #[test]
+    fn test_dag_consistency() {
+        let config = SimulatorConfig { parallelism: 4, max_parents: 3, coinbase_maturity: 2, ..Default::default() };
+        let (simulator, blocks) = run(config, 30);
+        assert_eq!(simulator.block_count(), blocks.len() + 1);
+
+        let mut known: HashMap<Hash, &Block> = HashMap::from([(simulator.genesis().hash(), simulator.genesis())]);
+        let mut outputs = HashSet::new();
+        let mut spent = HashSet::new();
+        for block in blocks.iter() {
+            let header = &block.header;
+            assert!(!header.direct_parents().is_empty() && header.direct_parents().len() <= 3);
+
+            // Parents are known, and the selected parent has a lower score
+            let parents: Vec<&Block> = header.direct_parents().iter().map(|hash| known[hash]).collect();
+            assert!(parents.iter().all(|parent| parent.header.blue_score < header.blue_score));
+            assert!(parents.iter().all(|parent| parent.header.daa_score < header.daa_score));
+            assert!(parents.iter().any(|parent| parent.header.blue_work < header.blue_work));
+
+            // A coinbase first, then transfers spending known outputs only once
+            assert!(block.transactions[0].is_coinbase());
+            for tx in block.transactions.iter() {
+                for input in tx.inputs.iter() {
+                    assert!(outputs.contains(&input.previous_outpoint));
+                    assert!(spent.insert(input.previous_outpoint));
+                    let entry = input.utxo_entry.as_ref().unwrap();
+                    let total_out: u64 = tx.outputs.iter().map(|output| output.value).sum();
+                    assert_eq!(entry.amount, total_out + tx.fee);
+                }
+            }
+            for tx in block.transactions.iter() {
+                for index in 0..tx.outputs.len() {
+                    outputs.insert(TransactionOutpoint::new(tx.id(), index as u32));
+                }
+            }
+            known.insert(block.hash(), block);
+        }
+        assert!(!spent.is_empty());
+
+        // Tips are not referenced by any block
+        let referenced: HashSet<Hash> = blocks.iter().flat_map(|block| block.header.direct_parents().iter().copied()).collect();
+        assert!(simulator.tips().iter().all(|tip| !referenced.contains(tip)));
+    }
+
+    #[test]
+    fn test_consensus_api() {
+        let (simulator, blocks) = run(SimulatorConfig { parallelism: 2, ..Default::default() }, 10);
+        let last = blocks.last().unwrap();
+        assert_eq!(simulator.get_block(last.hash()).unwrap().hash(), last.hash());
+        assert_eq!(simulator.get_header(last.hash()).unwrap().hash, last.hash());
+        assert!(matches!(simulator.get_block(Hash::default()), Err(ConsensusError::BlockNotFound(_))));
+
+        let virtual_state = simulator.get_virtual_state();
+        let mut tips = simulator.get_tips();
+        tips.sort();
+        assert_eq!(virtual_state.parents, tips);
+        assert!(tips.contains(&virtual_state.selected_parent));
+        assert!(blocks.iter().all(|block| block.header.blue_score < virtual_state.blue_score));
+
+        let coinbase = &blocks[0].transactions[0];
+        let outpoint = TransactionOutpoint::new(coinbase.id(), 0);
+        let entry = simulator.get_utxo(&outpoint);
+        let utxos = simulator.get_utxos_by_script_public_keys(&[(*coinbase.outputs[0].script_public_key).clone()]);
+        assert_eq!(entry.is_some(), utxos.iter().any(|(x, _)| *x == outpoint));
+        assert!(utxos.iter().all(|(_, entry)| entry.script_public_key == coinbase.outputs[0].script_public_key));
+    }
+
+    #[test]
+    fn test_circulating_supply() {
+        let config = SimulatorConfig::default();
+        let (simulator, blocks) = run(config.clone(), 10);
+
+        // Blocks mint the pre-deflationary subsidy and transfers burn their fees
+        let transfers: u64 = blocks.iter().map(|block| block.transactions.len() as u64 - 1).sum();
+        let expected = blocks.len() as u64 * subsidy::PRE_DEFLATIONARY_PHASE_BASE_SUBSIDY - transfers * config.transfer_fee;
+        assert_eq!(simulator.get_circulating_supply(), expected);
+        assert!(simulator.get_circulating_supply() <= subsidy::MAX_SOMPI);
+    }
+
+    #[test]
+    fn test_block_relations() {
+        let (simulator, blocks) = run(SimulatorConfig { parallelism: 3, ..Default::default() }, 15);
+        for block in blocks.iter() {
+            let ghostdag_data = simulator.get_ghostdag_data(block.hash()).unwrap();
+            assert_eq!(ghostdag_data.blue_score, block.header.blue_score);
+            assert_eq!(ghostdag_data.mergeset_blues[0], ghostdag_data.selected_parent);
+            for parent in block.header.direct_parents() {
+                assert!(simulator.get_block_children(*parent).unwrap().contains(&block.hash()));
+            }
+        }
+
+        // The selected chain goes from the virtual selected parent down to genesis
+        let mut chain = HashSet::new();
+        let mut current = simulator.get_virtual_state().selected_parent;
+        while current != simulator.genesis().hash() {
+            chain.insert(current);
+            current = simulator.get_ghostdag_data(current).unwrap().selected_parent;
+        }
+        for block in blocks.iter() {
+            assert_eq!(simulator.is_chain_block(block.hash()).unwrap(), chain.contains(&block.hash()));
+        }
+        assert!(simulator.is_chain_block(simulator.genesis().hash()).unwrap());
+    }
+
+    #[test]
+    fn test_difficulty() {
+        // A single block per second, each of the easiest target and thus of 2 expected hashes
+        let (simulator, blocks) = run(SimulatorConfig { parallelism: 1, ..Default::default() }, 10);
+        let context = simulator.get_block_dag_context(blocks[0].hash()).unwrap();
+        assert_eq!(context.difficulty, 1.0);
+        assert_eq!(blocks.last().unwrap().header.blue_work, 20);
+
+        assert_eq!(simulator.estimate_network_hashes_per_second(None, 5).unwrap(), 2);
+        assert_eq!(simulator.estimate_network_hashes_per_second(Some(blocks[4].hash()), 100).unwrap(), 2);
+        assert_eq!(simulator.estimate_network_hashes_per_second(None, 1).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_utxo_commitment() {
+        let config = SimulatorConfig { parallelism: 4, coinbase_maturity: 2, ..Default::default() };
+        let (simulator, blocks) = run(config, 10);
+        assert!(blocks[..4].iter().all(|block| block.header.utxo_commitment == muhash::EMPTY_MUHASH));
+
+        // All the blocks of a round commit to the virtual UTXO set they are built on, even when their siblings spend outputs
+        for _ in 0..5 {
+            let commitment = calc_utxo_commitment(simulator.state.lock().unwrap().virtual_utxos());
+            let round = simulator.produce_round();
+            assert_eq!(round.len(), 4);
+            assert!(round.iter().skip(1).any(|block| block.transactions.len() > 1));
+            assert!(round.iter().all(|block| block.header.utxo_commitment == commitment));
+        }
+        assert_ne!(
+            calc_utxo_commitment(simulator.state.lock().unwrap().virtual_utxos()),
+            blocks.last().unwrap().header.utxo_commitment
+        );
+    }
+
+    #[test]
+    fn test_validate_and_insert_block() {
+        let (simulator, blocks) = run(SimulatorConfig::default(), 5);
+        assert!(blocks.iter().all(|block| consensus_pow::check_header_pow(&block.header)));
+        assert!(blocks.iter().all(|block| merkle::calc_hash_merkle_root(block.transactions.iter()) == block.header.hash_merkle_root));
+
+        // A block on top of all tips has the GHOSTDAG data of the virtual
+        let virtual_state = simulator.get_virtual_state();
+        let tips = virtual_state.parents.clone();
+        let mine = |mut header: Header, valid: bool| {
+            let state = consensus_pow::State::new(&header);
+            header.nonce = (0..).find(|nonce| state.check_pow(*nonce).0 == valid).unwrap();
+            header.finalize();
+            header
+        };
+        let (accepted_id_merkle_root, utxo_commitment) = {
+            let mut state = simulator.state.lock().unwrap();
+            (merkle::calc_accepted_id_merkle_root(state.accepted_ids(&state.virtual_ghostdag())), state.utxo_muhash.finalize())
+        };
+        let header = |parents: Vec<Hash>, valid: bool| {
+            let (bits, daa_score, blue_work, blue_score) =
+                (simulator.config().bits, virtual_state.daa_score, virtual_state.blue_work, virtual_state.blue_score);
+            let mut header = Header::new(1, parents, Default::default(), 1_700_000_000_000, bits, 0, daa_score, blue_work, blue_score);
+            header.accepted_id_merkle_root = accepted_id_merkle_root;
+            header.utxo_commitment = utxo_commitment;
+            mine(header, valid)
+        };
+
+        let invalid = Block::from_header(header(tips.clone(), false));
+        assert!(matches!(simulator.validate_and_insert_block(invalid), Err(ConsensusError::InvalidPoW(_))));
+
+        // An easier target than the one of the network is rejected even though the proof of work satisfies it
+        let mut easier = header(tips.clone(), true);
+        easier.bits = 0x2100ffff;
+        let easier = Block::from_header(mine(easier, true));
+        assert!(consensus_pow::check_header_pow(&easier.header));
+        assert!(matches!(simulator.validate_and_insert_block(easier), Err(ConsensusError::UnexpectedDifficulty(_, _, _))));
+        let orphan = Block::from_header(header(vec![Hash::from_u64_word(1)], true));
+        assert!(matches!(simulator.validate_and_insert_block(orphan), Err(ConsensusError::MissingParents(_, _))));
+        let unrooted = Block { header: header(tips.clone(), true), transactions: blocks[0].transactions.clone() };
+        assert!(matches!(simulator.validate_and_insert_block(unrooted), Err(ConsensusError::BadMerkleRoot(_, _, _))));
+        let mut header_accepted_ids = header(tips.clone(), true);
+        header_accepted_ids.accepted_id_merkle_root = Default::default();
+        let block = Block::from_header(mine(header_accepted_ids, true));
+        assert!(matches!(simulator.validate_and_insert_block(block), Err(ConsensusError::BadAcceptedIdMerkleRoot(_, _, _))));
+        let mut header_utxo_commitment = header(tips.clone(), true);
+        header_utxo_commitment.utxo_commitment = muhash::EMPTY_MUHASH;
+        let block = Block::from_header(mine(header_utxo_commitment, true));
+        assert!(matches!(simulator.validate_and_insert_block(block), Err(ConsensusError::BadUtxoCommitment(_, _, _))));
+
+        // Scores must match the GHOSTDAG data computed from the parents
+        let mut header_daa_score = header(tips.clone(), true);
+        header_daa_score.daa_score += 1;
+        let block = Block::from_header(mine(header_daa_score, true));
+        assert!(matches!(simulator.validate_and_insert_block(block), Err(ConsensusError::UnexpectedHeaderDaaScore(_, _, _))));
+        let mut header_blue_score = header(tips.clone(), true);
+        header_blue_score.blue_score -= 1;
+        let block = Block::from_header(mine(header_blue_score, true));
+        assert!(matches!(simulator.validate_and_insert_block(block), Err(ConsensusError::UnexpectedHeaderBlueScore(_, _, _))));
+        let mut header_blue_work = header(tips.clone(), true);
+        header_blue_work.blue_work = 0;
+        let block = Block::from_header(mine(header_blue_work, true));
+        assert!(matches!(simulator.validate_and_insert_block(block), Err(ConsensusError::UnexpectedHeaderBlueWork(_, _, _))));
+        assert_eq!(simulator.block_count(), blocks.len() + 1);
+
+        let valid = Block::from_header(header(tips, true));
+        let hash = valid.hash();
+        simulator.validate_and_insert_block(valid.clone()).unwrap();
+        assert_eq!(simulator.tips(), vec![hash]);
+        assert!(simulator.is_chain_block(hash).unwrap());
+        assert!(matches!(simulator.validate_and_insert_block(valid), Err(ConsensusError::DuplicateBlock(_))));
+
+        // Produced blocks build on the submitted one
+        assert_eq!(simulator.produce_round()[0].header.direct_parents(), &vec![hash]);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(DagSimulator::new(SimulatorConfig::default()).is_ok());
+        for block_rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let config = SimulatorConfig { block_rate, ..Default::default() };
+            assert!(matches!(DagSimulator::new(config), Err(SimulatorConfigError::InvalidBlockRate(_))));
+        }
+        for block_rate in [f64::MIN_POSITIVE, f64::MAX] {
+            let config = SimulatorConfig { block_rate, ..Default::default() };
+            assert!(matches!(DagSimulator::new(config), Err(SimulatorConfigError::InvalidRoundInterval(_, _))));
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//! Thread-safe in-memory storage of blocks and of their relations in the DAG
+
+use consensus_core::{block::Block, ghostdag::GhostdagData, header::Header, tx::Transaction};
+use hashes::{Hash, HASH_SIZE, ZERO_HASH};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::{Arc, RwLock},
+};
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum StoreError {
+    #[error("key {0} not found in store")]
+    KeyNotFound(Hash),
+
+    #[error("key {0} already exists in store")]
+    KeyAlreadyExists(Hash),
+}
+
+pub type StoreResult<T> = std::result::Result<T, StoreError>;
+
+#[derive(Debug, Default)]
+struct StoreInner {
+    headers: HashMap<Hash, Arc<Header>>,
+    transactions: HashMap<Hash, Arc<Vec<Transaction>>>,
+    ghostdag: HashMap<Hash, Arc<GhostdagData>>,
+    children: HashMap<Hash, Vec<Hash>>,
+
+    /// All blocks ordered by blue score, for range queries
+    by_blue_score: BTreeSet<(u64, Hash)>,
+
+    /// Blocks of the selected parent chain, from genesis to the sink
+    selected_chain: HashSet<Hash>,
+    sink: Option<Hash>,
+}
+
+impl StoreInner {
+    fn header(&self, hash: Hash) -> StoreResult<&Arc<Header>> {
+        self.headers.get(&hash).ok_or(StoreError::KeyNotFound(hash))
+    }
+
+    fn ghostdag(&self, hash: Hash) -> StoreResult<&Arc<GhostdagData>> {
+        self.ghostdag.get(&hash).ok_or(StoreError::KeyNotFound(hash))
+    }
+
+    /// Ancestors always have a lower DAA score, which bounds the search to a thin slice of the DAG.
+    fn is_dag_ancestor_of(&self, ancestor: Hash, block: Hash) -> StoreResult<bool> {
+        let floor = self.header(ancestor)?.daa_score;
+        let mut queue = self.header(block)?.direct_parents().clone();
+        let mut visited = HashSet::new();
+        while let Some(hash) = queue.pop() {
+            if hash == ancestor {
+                return Ok(true);
+            }
+            if !visited.insert(hash) {
+                continue;
+            }
+            let header = self.header(hash)?;
+            if header.daa_score > floor {
+                queue.extend(header.direct_parents().iter().copied());
+            }
+        }
+        Ok(false)
+    }
+
+    /// Collects all the blocks reachable from `hash` by following `next`, `hash` excluded.
+    fn reachable<'a>(&'a self, hash: Hash, next: impl Fn(Hash) -> &'a [Hash]) -> HashSet<Hash> {
+        let mut reached = HashSet::new();
+        let mut queue = next(hash).to_vec();
+        while let Some(hash) = queue.pop() {
+            if reached.insert(hash) {
+                queue.extend(next(hash).iter().copied());
+            }
+        }
+        reached
+    }
+}
+
+/// An in-memory block store keyed by block hash.
+///
+/// Along with headers and transactions, it keeps the GHOSTDAG data and the children of every block,
+/// and tracks the selected parent chain ending at the sink, ie. the selected parent of the virtual block.
+#[derive(Debug, Default)]
+pub struct BlockStore {
+    inner: RwLock<StoreInner>,
+}
+
+impl BlockStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a block along with its GHOSTDAG data. All its parents must already be stored.
+    pub fn insert(&self, block: &Block, ghostdag_data: GhostdagData) -> StoreResult<()> {
+        let hash = block.hash();
+        let mut inner = self.inner.write().unwrap();
+        if inner.headers.contains_key(&hash) {
+            return Err(StoreError::KeyAlreadyExists(hash));
+        }
+        if let Some(parent) = block.header.direct_parents().iter().find(|parent| !inner.headers.contains_key(parent)) {
+            return Err(StoreError::KeyNotFound(*parent));
+        }
+        for parent in block.header.direct_parents().iter() {
+            inner.children.get_mut(parent).unwrap().push(hash);
+        }
+        inner.by_blue_score.insert((ghostdag_data.blue_score, hash));
+        inner.headers.insert(hash, Arc::new(block.header.clone()));
+        inner.transactions.insert(hash, block.transactions.clone());
+        inner.ghostdag.insert(hash, Arc::new(ghostdag_data));
+        inner.children.insert(hash, Vec::new());
+        if inner.sink.is_none() {
+            inner.selected_chain.insert(hash);
+            inner.sink = Some(hash);
+        }
+        Ok(())
+    }
+
+    pub fn has(&self, hash: Hash) -> bool {
+        self.inner.read().unwrap().headers.contains_key(&hash)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.read().unwrap().headers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get_block(&self, hash: Hash) -> StoreResult<Block> {
+        let inner = self.inner.read().unwrap();
+        let header = inner.header(hash)?.as_ref().clone();
+        Ok(Block { header, transactions: inner.transactions[&hash].clone() })
+    }
+
+    pub fn get_header(&self, hash: Hash) -> StoreResult<Arc<Header>> {
+        self.inner.read().unwrap().header(hash).cloned()
+    }
+
+    pub fn get_transactions(&self, hash: Hash) -> StoreResult<Arc<Vec<Transaction>>> {
+        self.inner.read().unwrap().transactions.get(&hash).cloned().ok_or(StoreError::KeyNotFound(hash))
+    }
+
+    pub fn get_ghostdag_data(&self, hash: Hash) -> StoreResult<Arc<GhostdagData>> {
+        self.inner.read().unwrap().ghostdag(hash).cloned()
+    }
+
+    pub fn get_selected_parent(&self, hash: Hash) -> StoreResult<Hash> {
+        Ok(self.inner.read().unwrap().ghostdag(hash)?.selected_parent)
+    }
+
+    pub fn get_mergeset_blues(&self, hash: Hash) -> StoreResult<Vec<Hash>> {
+        Ok(self.inner.read().unwrap().ghostdag(hash)?.mergeset_blues.clone())
+    }
+
+    pub fn get_mergeset_reds(&self, hash: Hash) -> StoreResult<Vec<Hash>> {
+        Ok(self.inner.read().unwrap().ghostdag(hash)?.mergeset_reds.clone())
+    }
+
+    pub fn get_children(&self, hash: Hash) -> StoreResult<Vec<Hash>> {
+        self.inner.read().unwrap().children.get(&hash).cloned().ok_or(StoreError::KeyNotFound(hash))
+    }
+
+    /// Returns true if `ancestor` is in the past of `block`.
+    pub fn is_dag_ancestor_of(&self, ancestor: Hash, block: Hash) -> StoreResult<bool> {
+        self.inner.read().unwrap().is_dag_ancestor_of(ancestor, block)
+    }
+
+    /// Returns true if neither block is in the past of the other.
+    pub fn is_in_anticone(&self, a: Hash, b: Hash) -> StoreResult<bool> {
+        let inner = self.inner.read().unwrap();
+        Ok(a != b && !inner.is_dag_ancestor_of(a, b)? && !inner.is_dag_ancestor_of(b, a)?)
+    }
+
+    /// Returns the blocks neither in the past nor in the future of `hash`, ordered by blue score.
+    pub fn get_anticone(&self, hash: Hash) -> StoreResult<Vec<Hash>> {
+        let inner = self.inner.read().unwrap();
+        inner.header(hash)?;
+        let past = inner.reachable(hash, |hash| inner.headers[&hash].direct_parents());
+        let future = inner.reachable(hash, |hash| &inner.children[&hash]);
+        Ok(inner
+            .by_blue_score
+            .iter()
+            .map(|(_, block)| *block)
+            .filter(|block| *block != hash && !past.contains(block) && !future.contains(block))
+            .collect())
+    }
+
+    /// Returns the blocks with a blue score in `low..=high`, ordered by blue score and hash.
+    pub fn get_range_by_blue_score(&self, low: u64, high: u64) -> Vec<Hash> {
+        if low > high {
+            return vec![];
+        }
+        let inner = self.inner.read().unwrap();
+        inner.by_blue_score.range((low, ZERO_HASH)..=(high, Hash::from_bytes([0xff; HASH_SIZE]))).map(|(_, hash)| *hash).collect()
+    }
+
+    /// Returns the selected parent of the virtual block, if any block is stored.
+    pub fn sink(&self) -> Option<Hash> {
+        self.inner.read().unwrap().sink
+    }
+
+    /// Returns true if the block is in the selected parent chain ending at the sink.
+    pub fn is_chain_block(&self, hash: Hash) -> StoreResult<bool> {
+        let inner = self.inner.read().unwrap();
+        inner.header(hash)?;
+        Ok(inner.selected_chain.contains(&hash))
+    }
+
+    /// Moves the sink to `new_sink`, reorganizing the selected parent chain accordingly.
+    ///
+    /// Returns the blocks removed from and added to the chain, both ordered from the highest block down.
+    pub fn update_sink(&self, new_sink: Hash) -> StoreResult<(Vec<Hash>, Vec<Hash>)> {
+        let mut inner = self.inner.write().unwrap();
+        inner.header(new_sink)?;
+
+        // Walk down the new chain until reaching the current one
+        let mut added = Vec::new();
+        let mut fork = new_sink;
+        while !inner.selected_chain.contains(&fork) {
+            added.push(fork);
+            fork = inner.ghostdag(fork)?.selected_parent;
+        }
+
+        // Walk down the current chain until reaching the fork point
+        let mut removed = Vec::new();
+        let mut current = inner.sink.unwrap();
+        while current != fork {
+            removed.push(current);
+            current = inner.ghostdag(current)?.selected_parent;
+        }
+
+        for hash in removed.iter() {
+            inner.selected_chain.remove(hash);
+        }
+        inner.selected_chain.extend(added.iter().copied());
+        inner.sink = Some(new_sink);
+        Ok((removed, added))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stores a block with the given parents, the first one being its selected parent.
+    fn add_block(store: &BlockStore, parents: Vec<Hash>, blue_score: u64) -> Hash {
+        let block = Block::new(1, parents.clone(), 0, 0, store.len() as u64, blue_score, 0, blue_score);
+        let selected_parent = parents.first().copied().unwrap_or_default();
+        let ghostdag_data = GhostdagData { blue_score, selected_parent, mergeset_blues: parents, ..Default::default() };
+        store.insert(&block, ghostdag_data).unwrap();
+        block.hash()
+    }
+
+    #[test]
+    fn test_block_store() {
+        //    genesis
+        //    /     \
+        //   a       b
+        //   |       |
+        //   c       |
+        //    \     /
+        //       d
+        let store = BlockStore::new();
+        let genesis = add_block(&store, vec![], 0);
+        let a = add_block(&store, vec![genesis], 1);
+        let b = add_block(&store, vec![genesis], 1);
+        let c = add_block(&store, vec![a], 2);
+        let d = add_block(&store, vec![c, b], 4);
+        assert_eq!(store.len(), 5);
+
+        let block = Block::new(1, vec![genesis], 0, 0, 99, 1, 0, 1);
+        let ghostdag_data = GhostdagData { blue_score: 1, selected_parent: genesis, ..Default::default() };
+        assert_eq!(store.insert(&block, ghostdag_data.clone()), Ok(()));
+        assert_eq!(store.insert(&block, ghostdag_data), Err(StoreError::KeyAlreadyExists(block.hash())));
+        let orphan = Block::new(1, vec![Hash::from_u64_word(1)], 0, 0, 1, 1, 0, 1);
+        assert_eq!(store.insert(&orphan, Default::default()), Err(StoreError::KeyNotFound(Hash::from_u64_word(1))));
+
+        let mut children = store.get_children(genesis).unwrap();
+        children.sort();
+        let mut expected = vec![a, b, block.hash()];
+        expected.sort();
+        assert_eq!(children, expected);
+        assert_eq!(store.get_selected_parent(d).unwrap(), c);
+        assert_eq!(store.get_mergeset_blues(d).unwrap(), vec![c, b]);
+
+        assert!(store.is_dag_ancestor_of(genesis, d).unwrap());
+        assert!(store.is_dag_ancestor_of(b, d).unwrap());
+        assert!(!store.is_dag_ancestor_of(b, c).unwrap());
+        assert!(store.is_in_anticone(b, c).unwrap());
+        let mut anticone = store.get_anticone(a).unwrap();
+        anticone.sort();
+        let mut expected = vec![b, block.hash()];
+        expected.sort();
+        assert_eq!(anticone, expected);
+
+        assert_eq!(store.get_range_by_blue_score(2, 4), vec![c, d]);
+        assert_eq!(store.get_range_by_blue_score(0, 0), vec![genesis]);
+        assert!(store.get_range_by_blue_score(5, 10).is_empty());
+
+        // The chain follows the selected parents from the sink
+        assert_eq!(store.update_sink(d).unwrap(), (vec![], vec![d, c, a]));
+        assert!([genesis, a, c, d].iter().all(|hash| store.is_chain_block(*hash).unwrap()));
+        assert!(!store.is_chain_block(b).unwrap());
+        assert_eq!(store.update_sink(b).unwrap(), (vec![d, c, a], vec![b]));
+        assert!(store.is_chain_block(b).unwrap() && !store.is_chain_block(a).unwrap());
+        assert_eq!(store.is_chain_block(Hash::from_u64_word(1)), Err(StoreError::KeyNotFound(Hash::from_u64_word(1))));
+    }
+}
@@ -1,17 +1,304 @@
+//! Levelled logging facility.
+//!
+//! Records are emitted through the `error!`, `warn!`, `info!`, `debug!` and `trace!` macros.
+//! Each record carries a target, which defaults to the module path of the call site. A record is
+//! emitted only if its level is enabled for its target, so that production nodes can run at `info`
+//! while a specific subsystem is switched to `debug` or `trace`.
+//!
+//! Filters are configured with [`init_logger`] using a comma separated spec such as
+//! `info,rpc_grpc::server=debug,rpc_core::notify=trace`. A bare level sets the default, while
+//! `target=level` entries override it for every target starting with the given prefix
+//! (the longest matching prefix wins).
+//!
+//! On native targets records go to stdout (`warn` and `error` to stderr). On wasm32 they go to
+//! the browser console.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::RwLock;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Level> {
+        match value {
+            1 => Some(Level::Error),
+            2 => Some(Level::Warn),
+            3 => Some(Level::Info),
+            4 => Some(Level::Debug),
+            5 => Some(Level::Trace),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" | "warning" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            other => Err(format!("unknown log level `{other}`")),
+        }
+    }
+}
+
+/// Sentinel stored in the level atomics meaning "logging disabled".
+const LEVEL_OFF: u8 = 0;
+
+/// Default level applied to targets without a specific filter.
+static DEFAULT_LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+/// Most verbose level enabled for any target, used as a lock-free fast path.
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+/// Per-target overrides, kept sorted by descending prefix length.
+static TARGET_LEVELS: RwLock<Vec<(String, u8)>> = RwLock::new(Vec::new());
+
+/// Configures the logger from a filter spec, replacing any previous configuration.
+///
+/// See the module documentation for the syntax. An empty spec resets to `info`.
+pub fn init_logger(spec: &str) -> Result<(), String> {
+    let mut default = Level::Info as u8;
+    let mut targets = Vec::new();
+    for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+        match directive.split_once('=') {
+            Some((target, level)) => targets.push((target.trim().to_string(), parse_level(level)?)),
+            None => default = parse_level(directive)?,
+        }
+    }
+    targets.sort_by_key(|(target, _): &(String, u8)| std::cmp::Reverse(target.len()));
+
+    let mut filters = TARGET_LEVELS.write().unwrap();
+    *filters = targets;
+    DEFAULT_LEVEL.store(default, Ordering::SeqCst);
+    update_max_level(&filters);
+    Ok(())
+}
+
+/// Sets the default level, leaving per-target overrides untouched.
+pub fn set_log_level(level: Level) {
+    let filters = TARGET_LEVELS.read().unwrap();
+    DEFAULT_LEVEL.store(level as u8, Ordering::SeqCst);
+    update_max_level(&filters);
+}
+
+/// Sets the level of every target starting with `target`.
+pub fn set_target_log_level(target: &str, level: Level) {
+    let mut filters = TARGET_LEVELS.write().unwrap();
+    match filters.iter_mut().find(|(t, _)| t == target) {
+        Some(entry) => entry.1 = level as u8,
+        None => {
+            filters.push((target.to_string(), level as u8));
+            filters.sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        }
+    }
+    update_max_level(&filters);
+}
+
+/// Returns the default level, or `None` if logging is off by default.
+pub fn log_level() -> Option<Level> {
+    Level::from_u8(DEFAULT_LEVEL.load(Ordering::Relaxed))
+}
+
+/// Returns true if a record of `level` for `target` would be emitted.
+pub fn log_enabled(level: Level, target: &str) -> bool {
+    let level = level as u8;
+    if level > MAX_LEVEL.load(Ordering::Relaxed) {
+        return false;
+    }
+    let filters = TARGET_LEVELS.read().unwrap();
+    let enabled = filters
+        .iter()
+        .find(|(prefix, _)| target_matches(target, prefix))
+        .map_or_else(|| DEFAULT_LEVEL.load(Ordering::Relaxed), |(_, level)| *level);
+    level <= enabled
+}
+
+fn parse_level(s: &str) -> Result<u8, String> {
+    if s.trim().eq_ignore_ascii_case("off") {
+        Ok(LEVEL_OFF)
+    } else {
+        Ok(s.parse::<Level>()? as u8)
+    }
+}
+
+fn update_max_level(filters: &[(String, u8)]) {
+    let max = filters.iter().map(|(_, level)| *level).fold(DEFAULT_LEVEL.load(Ordering::SeqCst), u8::max);
+    MAX_LEVEL.store(max, Ordering::SeqCst);
+}
+
+/// `prefix` matches `target` on module path boundaries, so `rpc_core::notify` matches
+/// `rpc_core::notify::notifier` but not `rpc_core::notifyx`.
+fn target_matches(target: &str, prefix: &str) -> bool {
+    match target.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with("::"),
+        None => false,
+    }
+}
+
+#[doc(hidden)]
+pub fn __private_log(level: Level, target: &str, args: fmt::Arguments) {
+    let record = format!("{:<5} [{}] {}", level, target, args);
+    write_record(level, &record);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_record(level: Level, record: &str) {
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+    let timestamp = format_timestamp(now.as_millis() as u64);
+    match level {
+        Level::Error | Level::Warn => eprintln!("{timestamp} {record}"),
+        _ => println!("{timestamp} {record}"),
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
-#[macro_export]
-macro_rules! trace {
-    ($($t:tt)*) => (
-        #[allow(unused_unsafe)]
-        unsafe { core::console::log(&format_args!($($t)*).to_string()) }
-    )
+fn write_record(level: Level, record: &str) {
+    match level {
+        Level::Error => crate::console::error(record),
+        Level::Warn => crate::console::warn(record),
+        _ => crate::console::log(record),
+    }
 }
 
+/// Formats unix milliseconds as a UTC `YYYY-MM-DD HH:MM:SS.mmm` timestamp.
 #[cfg(not(target_arch = "wasm32"))]
+fn format_timestamp(unix_millis: u64) -> String {
+    let secs = unix_millis / 1000;
+    let (days, rem) = (secs / 86400, secs % 86400);
+    // Civil date from days since epoch (Howard Hinnant's algorithm)
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}", year, month, day, rem / 3600, rem % 3600 / 60, rem % 60, unix_millis % 1000)
+}
+
+/// Logs a record at the given level, e.g. `log!(Level::Info, "x = {}", x)`.
+#[macro_export]
+macro_rules! log {
+    (target: $target:expr, $level:expr, $($arg:tt)+) => {{
+        let level = $level;
+        let target = $target;
+        if $crate::log::log_enabled(level, target) {
+            $crate::log::__private_log(level, target, format_args!($($arg)+));
+        }
+    }};
+    ($level:expr, $($arg:tt)+) => {
+        $crate::log!(target: module_path!(), $level, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! error {
+    (target: $target:expr, $($arg:tt)+) => { $crate::log!(target: $target, $crate::log::Level::Error, $($arg)+) };
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! warn {
+    (target: $target:expr, $($arg:tt)+) => { $crate::log!(target: $target, $crate::log::Level::Warn, $($arg)+) };
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    (target: $target:expr, $($arg:tt)+) => { $crate::log!(target: $target, $crate::log::Level::Info, $($arg)+) };
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    (target: $target:expr, $($arg:tt)+) => { $crate::log!(target: $target, $crate::log::Level::Debug, $($arg)+) };
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Debug, $($arg)+) };
+}
+
 #[macro_export]
 macro_rules! trace {
-    ($($t:tt)*) => (
-        #[allow(unused_unsafe)]
-        unsafe { println!("{}",&format_args!($($t)*).to_string()) }
-    )
+    (target: $target:expr, $($arg:tt)+) => { $crate::log!(target: $target, $crate::log::Level::Trace, $($arg)+) };
+    ($($arg:tt)+) => { $crate::log!($crate::log::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_parsing() {
+        assert_eq!("Info".parse::<Level>(), Ok(Level::Info));
+        assert_eq!("warning".parse::<Level>(), Ok(Level::Warn));
+        assert!("verbose".parse::<Level>().is_err());
+        assert!(Level::Error < Level::Trace);
+    }
+
+    #[test]
+    fn test_target_matches() {
+        assert!(target_matches("rpc_core::notify::notifier", "rpc_core::notify"));
+        assert!(target_matches("rpc_core::notify", "rpc_core::notify"));
+        assert!(!target_matches("rpc_core::notifyx", "rpc_core::notify"));
+        assert!(!target_matches("rpc_grpc::server", "rpc_core"));
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00.000");
+        assert_eq!(format_timestamp(1_666_000_000_123), "2022-10-17 09:46:40.123");
+    }
+
+    // Filters are global, so all filter assertions live in a single test
+    #[test]
+    fn test_filters() {
+        init_logger("warn,rpc_grpc=debug,rpc_grpc::client=off").unwrap();
+        assert!(log_enabled(Level::Warn, "consensus::notifiy"));
+        assert!(!log_enabled(Level::Info, "consensus::notifiy"));
+        assert!(log_enabled(Level::Debug, "rpc_grpc::server::service"));
+        assert!(!log_enabled(Level::Trace, "rpc_grpc::server::service"));
+        assert!(!log_enabled(Level::Error, "rpc_grpc::client::resolver"));
+
+        set_target_log_level("consensus", Level::Trace);
+        assert!(log_enabled(Level::Trace, "consensus::notifiy"));
+
+        set_log_level(Level::Error);
+        assert_eq!(log_level(), Some(Level::Error));
+        assert!(!log_enabled(Level::Warn, "kaspa_core"));
+
+        assert!(init_logger("info,rpc_core=loud").is_err());
+        init_logger("").unwrap();
+        assert_eq!(log_level(), Some(Level::Info));
+    }
 }
@@ -0,0 +1,118 @@
+//! The cashaddr flavor of bech32 used by Kaspa addresses
+//!
+//! See <https://github.com/bitcoincashorg/bitcoincash.org/blob/master/spec/cashaddr.md>
+
+use crate::{AddressError, Prefix};
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Length of the checksum in 5-bit groups
+const CHECKSUM_LEN: usize = 8;
+
+fn polymod(values: impl Iterator<Item = u8>) -> u64 {
+    let mut c = 1u64;
+    for d in values {
+        let c0 = c >> 35;
+        c = ((c & 0x07ffffffff) << 5) ^ (d as u64);
+        if c0 & 0x01 != 0 {
+            c ^= 0x98f2bc8e61;
+        }
+        if c0 & 0x02 != 0 {
+            c ^= 0x79b76d99e2;
+        }
+        if c0 & 0x04 != 0 {
+            c ^= 0xf33e5fb3c4;
+        }
+        if c0 & 0x08 != 0 {
+            c ^= 0xae2eabe2a8;
+        }
+        if c0 & 0x10 != 0 {
+            c ^= 0x1e4f43e470;
+        }
+    }
+    c ^ 1
+}
+
+/// Computes the 40 bits checksum of a 5-bit payload, committing to the lower 5 bits of the prefix characters.
+fn checksum(prefix: Prefix, payload: &[u8]) -> u64 {
+    let prefix = prefix.as_str().bytes().map(|c| c & 0x1f);
+    polymod(prefix.chain([0]).chain(payload.iter().copied()).chain([0; CHECKSUM_LEN]))
+}
+
+/// Regroups 8-bit bytes into 5-bit groups, padding the last group with zeros.
+fn conv8to5(payload: &[u8]) -> Vec<u8> {
+    let mut five_bit = Vec::with_capacity((payload.len() * 8).div_ceil(5));
+    let mut buffer = 0u16;
+    let mut bits = 0;
+    for byte in payload.iter() {
+        buffer = (buffer << 8) | *byte as u16;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            five_bit.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    if bits > 0 {
+        five_bit.push((buffer << (5 - bits)) as u8);
+    }
+    five_bit
+}
+
+/// Regroups 5-bit groups into 8-bit bytes, dropping the padding bits.
+fn conv5to8(payload: &[u8]) -> Vec<u8> {
+    let mut eight_bit = Vec::with_capacity(payload.len() * 5 / 8);
+    let mut buffer = 0u16;
+    let mut bits = 0;
+    for group in payload.iter() {
+        buffer = (buffer << 5) | *group as u16;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            eight_bit.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    eight_bit
+}
+
+/// Encodes the bytes of an address, version included, followed by their checksum.
+pub(crate) fn encode(prefix: Prefix, payload: &[u8]) -> String {
+    let payload = conv8to5(payload);
+    let checksum = checksum(prefix, &payload);
+    let checksum = conv8to5(&checksum.to_be_bytes()[3..]);
+    payload.iter().chain(checksum.iter()).map(|group| CHARSET[*group as usize] as char).collect()
+}
+
+/// Decodes the part of an address following the prefix, verifying its checksum.
+pub(crate) fn decode(prefix: Prefix, encoded: &str) -> Result<Vec<u8>, AddressError> {
+    let groups = encoded
+        .chars()
+        .map(|c| CHARSET.iter().position(|x| *x as char == c).map(|group| group as u8).ok_or(AddressError::DecodingError(c)))
+        .collect::<Result<Vec<u8>, AddressError>>()?;
+    if groups.len() <= CHECKSUM_LEN {
+        return Err(AddressError::BadPayload);
+    }
+
+    let (payload, checksum_groups) = groups.split_at(groups.len() - CHECKSUM_LEN);
+    let expected = checksum_groups.iter().fold(0u64, |acc, group| (acc << 5) | *group as u64);
+    if checksum(prefix, payload) != expected {
+        return Err(AddressError::BadChecksum);
+    }
+    Ok(conv5to8(payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        for len in 0..40 {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 37 + 11) as u8).collect();
+            let groups = conv8to5(&bytes);
+            assert!(groups.iter().all(|group| *group < 32));
+            assert_eq!(conv5to8(&groups), bytes);
+        }
+    }
+}
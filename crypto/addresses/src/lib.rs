@@ -0,0 +1,284 @@
+//! Kaspa addresses: a network prefix, a version and a payload, encoded with the cashaddr flavor of bech32
+//!
+//! An address reads `<prefix>:<encoded version, payload and checksum>`, for instance
+//! `kaspa:qp0l70zd5x85ttwd6jv7g3s3a8llzj96d8dncn4zmhv4tlzx5k2jyqh70xmfj`.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+use thiserror::Error;
+
+mod bech32;
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum AddressError {
+    #[error("Invalid prefix {0}")]
+    InvalidPrefix(String),
+
+    #[error("Prefix is missing")]
+    MissingPrefix,
+
+    #[error("Invalid version {0}")]
+    InvalidVersion(u8),
+
+    #[error("Invalid character {0}")]
+    DecodingError(char),
+
+    #[error("Invalid payload")]
+    BadPayload,
+
+    #[error("Invalid payload length {1} for version {0}")]
+    BadPayloadLength(Version, usize),
+
+    #[error("Checksum is invalid")]
+    BadChecksum,
+}
+
+/// Network prefix of an address
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub enum Prefix {
+    Mainnet,
+    Testnet,
+    Simnet,
+    Devnet,
+}
+
+impl Prefix {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Prefix::Mainnet => "kaspa",
+            Prefix::Testnet => "kaspatest",
+            Prefix::Simnet => "kaspasim",
+            Prefix::Devnet => "kaspadev",
+        }
+    }
+}
+
+impl Display for Prefix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Prefix {
+    type Err = AddressError;
+
+    fn from_str(prefix: &str) -> Result<Self, Self::Err> {
+        match prefix {
+            "kaspa" => Ok(Prefix::Mainnet),
+            "kaspatest" => Ok(Prefix::Testnet),
+            "kaspasim" => Ok(Prefix::Simnet),
+            "kaspadev" => Ok(Prefix::Devnet),
+            _ => Err(AddressError::InvalidPrefix(prefix.to_string())),
+        }
+    }
+}
+
+/// Kind of script an address pays to, stored as the first byte of the encoded payload
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[repr(u8)]
+pub enum Version {
+    /// Pay to a Schnorr public key
+    PubKey = 0,
+
+    /// Pay to an ECDSA public key
+    PubKeyECDSA = 1,
+
+    /// Pay to a script hash
+    ScriptHash = 8,
+}
+
+impl Version {
+    /// Length of the payload of an address of this version
+    pub fn payload_len(&self) -> usize {
+        match self {
+            Version::PubKey => 32,
+            Version::PubKeyECDSA => 33,
+            Version::ScriptHash => 32,
+        }
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Version::PubKey => f.write_str("PubKey"),
+            Version::PubKeyECDSA => f.write_str("PubKeyECDSA"),
+            Version::ScriptHash => f.write_str("ScriptHash"),
+        }
+    }
+}
+
+impl TryFrom<u8> for Version {
+    type Error = AddressError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Version::PubKey),
+            1 => Ok(Version::PubKeyECDSA),
+            8 => Ok(Version::ScriptHash),
+            _ => Err(AddressError::InvalidVersion(value)),
+        }
+    }
+}
+
+/// A Kaspa address, serialized as its string form
+#[derive(
+    Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct Address {
+    pub prefix: Prefix,
+    pub version: Version,
+    pub payload: Vec<u8>,
+}
+
+impl Address {
+    /// Builds an address, the payload length having to match the version.
+    pub fn new(prefix: Prefix, version: Version, payload: &[u8]) -> Result<Self, AddressError> {
+        if payload.len() != version.payload_len() {
+            return Err(AddressError::BadPayloadLength(version, payload.len()));
+        }
+        Ok(Self { prefix, version, payload: payload.to_vec() })
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let payload = [&[self.version as u8], self.payload.as_slice()].concat();
+        write!(f, "{}:{}", self.prefix, bech32::encode(self.prefix, &payload))
+    }
+}
+
+impl From<Address> for String {
+    fn from(address: Address) -> Self {
+        address.to_string()
+    }
+}
+
+impl FromStr for Address {
+    type Err = AddressError;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        let (prefix, encoded) = address.split_once(':').ok_or(AddressError::MissingPrefix)?;
+        let prefix: Prefix = prefix.parse()?;
+        let payload = bech32::decode(prefix, encoded)?;
+        match payload.split_first() {
+            Some((version, payload)) => Self::new(prefix, (*version).try_into()?, payload),
+            None => Err(AddressError::BadPayload),
+        }
+    }
+}
+
+impl TryFrom<&str> for Address {
+    type Error = AddressError;
+
+    fn try_from(address: &str) -> Result<Self, Self::Error> {
+        address.parse()
+    }
+}
+
+impl TryFrom<String> for Address {
+    type Error = AddressError;
+
+    fn try_from(address: String) -> Result<Self, Self::Error> {
+        address.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_address_vectors() {
+        struct Test {
+            prefix: Prefix,
+            version: Version,
+            payload: Vec<u8>,
+            address: &'static str,
+        }
+
+        // Vectors from kaspad
+        let tests = vec![
+            Test {
+                prefix: Prefix::Mainnet,
+                version: Version::PubKey,
+                payload: vec![0; 32],
+                address: "kaspa:qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqkx9awp4e",
+            },
+            Test {
+                prefix: Prefix::Mainnet,
+                version: Version::PubKey,
+                payload: from_hex("5fff3c4da18f45adcdd499e44611e9fff148ba69db3c4ea2ddd955fc46a59522"),
+                address: "kaspa:qp0l70zd5x85ttwd6jv7g3s3a8llzj96d8dncn4zmhv4tlzx5k2jyqh70xmfj",
+            },
+            Test {
+                prefix: Prefix::Testnet,
+                version: Version::PubKey,
+                payload: vec![0; 32],
+                address: "kaspatest:qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqhqrxplya",
+            },
+            Test {
+                prefix: Prefix::Testnet,
+                version: Version::PubKeyECDSA,
+                payload: vec![0; 33],
+                address: "kaspatest:qyqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqhe837j2d",
+            },
+            Test {
+                prefix: Prefix::Testnet,
+                version: Version::PubKeyECDSA,
+                payload: from_hex("ba01fc5f4e9d9879599c69a3dafdb835a7255e5f2e934e9322ecd3af190ab0f60e"),
+                address: "kaspatest:qxaqrlzlf6wes72en3568khahq66wf27tuhfxn5nytkd8tcep2c0vrse6gdmpks",
+            },
+        ];
+
+        for test in tests {
+            let address = Address::new(test.prefix, test.version, &test.payload).unwrap();
+            assert_eq!(address.to_string(), test.address);
+            assert_eq!(test.address.parse::<Address>().unwrap(), address);
+        }
+
+        for prefix in [Prefix::Simnet, Prefix::Devnet] {
+            let address = Address::new(prefix, Version::ScriptHash, &[7; 32]).unwrap();
+            assert!(address.to_string().starts_with(prefix.as_str()));
+            assert_eq!(address.to_string().parse::<Address>().unwrap(), address);
+        }
+    }
+
+    #[test]
+    fn test_address_errors() {
+        let parse = |address: &str| address.parse::<Address>();
+        assert_eq!(
+            parse("kaspa:qqqqqqqqqqqqq1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqkx9awp4e"),
+            Err(AddressError::DecodingError('1'))
+        );
+        assert_eq!(parse("qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqkx9awp4e"), Err(AddressError::MissingPrefix));
+        assert_eq!(
+            parse("kaspb:qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqkx9awp4e"),
+            Err(AddressError::InvalidPrefix("kaspb".to_string()))
+        );
+        assert_eq!(parse("kaspa:qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqkx9awp4l"), Err(AddressError::BadChecksum));
+        // Valid for testnet only
+        assert_eq!(parse("kaspa:qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqhqrxplya"), Err(AddressError::BadChecksum));
+        assert_eq!(
+            Address::new(Prefix::Mainnet, Version::PubKeyECDSA, &[0; 32]),
+            Err(AddressError::BadPayloadLength(Version::PubKeyECDSA, 32))
+        );
+    }
+
+    #[test]
+    fn test_address_serde() {
+        let address = Address::new(Prefix::Mainnet, Version::PubKey, &[0; 32]).unwrap();
+        let json = format!("\"{}\"", address);
+        assert_eq!(serde_json::to_string(&address).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Address>(&json).unwrap(), address);
+    }
+}
@@ -0,0 +1,253 @@
+//! MuHash3072: a rolling hash of a multiset, elements being added or removed in any order
+//!
+//! Each element is hashed to a 3072-bit number with [`MuHashElementHash`] and a ChaCha20 keystream. The multiset
+//! is the product of its elements modulo a 3072-bit prime, kept as a fraction so that removals are as cheap as
+//! additions, and its hash is the [`MuHashFinalizeHash`] of that product. See kaspad `muhash.go`.
+
+use hashes::{Hash, Hasher, MuHashElementHash, MuHashFinalizeHash};
+use rand_chacha::{
+    rand_core::{RngCore, SeedableRng},
+    ChaCha20Rng,
+};
+use thiserror::Error;
+use u3072::U3072;
+
+mod u3072;
+
+/// Size of a serialized MuHash
+pub const SERIALIZED_MUHASH_SIZE: usize = u3072::BYTES;
+
+/// Hash of the empty multiset
+pub const EMPTY_MUHASH: Hash = Hash::from_bytes([
+    0x54, 0x4e, 0xb3, 0x14, 0x2c, 0x00, 0x0f, 0x0a, 0xd2, 0xc7, 0x6a, 0xc4, 0x1f, 0x42, 0x22, 0xab, 0xba, 0xba, 0xbe, 0xd8, 0x30,
+    0xee, 0xaf, 0xee, 0x4b, 0x6d, 0xc5, 0x6b, 0x52, 0xd5, 0xca, 0xc0,
+]);
+
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum MuHashError {
+    #[error("serialized MuHash overflows the field")]
+    Overflow,
+}
+
+#[derive(Clone, Debug)]
+pub struct MuHash {
+    numerator: U3072,
+    denominator: U3072,
+}
+
+impl MuHash {
+    /// Creates the MuHash of the empty multiset.
+    pub fn new() -> Self {
+        Self { numerator: U3072::one(), denominator: U3072::one() }
+    }
+
+    /// Adds `data` to the multiset.
+    pub fn add_element(&mut self, data: &[u8]) {
+        self.numerator *= data_to_element(data);
+    }
+
+    /// Removes `data` from the multiset, which needs not be in it yet.
+    pub fn remove_element(&mut self, data: &[u8]) {
+        self.denominator *= data_to_element(data);
+    }
+
+    /// Adds all the elements of `other` to the multiset, removing those it removes.
+    pub fn combine(&mut self, other: &Self) {
+        self.numerator *= other.numerator;
+        self.denominator *= other.denominator;
+    }
+
+    pub fn finalize(&mut self) -> Hash {
+        MuHashFinalizeHash::hash(self.serialize())
+    }
+
+    /// Serializes the multiset as a single 3072-bit little endian number.
+    pub fn serialize(&mut self) -> [u8; SERIALIZED_MUHASH_SIZE] {
+        self.normalize();
+        self.numerator.to_le_bytes()
+    }
+
+    pub fn deserialize(data: [u8; SERIALIZED_MUHASH_SIZE]) -> Result<Self, MuHashError> {
+        let numerator = U3072::from_le_bytes(data);
+        match numerator.is_overflow() {
+            true => Err(MuHashError::Overflow),
+            false => Ok(Self { numerator, denominator: U3072::one() }),
+        }
+    }
+
+    /// Divides out the denominator, which is the only costly operation.
+    fn normalize(&mut self) {
+        if self.denominator != U3072::one() {
+            self.numerator *= self.denominator.inverse();
+            self.denominator = U3072::one();
+        }
+    }
+}
+
+impl Default for MuHash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn data_to_element(data: &[u8]) -> U3072 {
+    let hash = MuHashElementHash::hash(data);
+    let mut stream = ChaCha20Rng::from_seed(hash.as_bytes());
+    let mut bytes = [0u8; SERIALIZED_MUHASH_SIZE];
+    stream.fill_bytes(&mut bytes);
+    U3072::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestVector {
+        data: &'static [u8],
+        multiset_hash: Hash,
+        cumulative_hash: Hash,
+    }
+
+    const TEST_VECTORS: [TestVector; 3] = [
+        TestVector {
+            data: &[
+                152, 32, 81, 253, 30, 75, 167, 68, 187, 190, 104, 14, 31, 238, 20, 103, 123, 161, 163, 195, 84, 11, 247, 177, 205,
+                182, 6, 232, 87, 35, 62, 14, 0, 0, 0, 0, 1, 0, 0, 0, 1, 0, 242, 5, 42, 1, 0, 0, 0, 67, 65, 4, 150, 181, 56, 232, 83,
+                81, 156, 114, 106, 44, 145, 230, 30, 193, 22, 0, 174, 19, 144, 129, 58, 98, 124, 102, 251, 139, 231, 148, 123, 230,
+                60, 82, 218, 117, 137, 55, 149, 21, 212, 224, 166, 4, 248, 20, 23, 129, 230, 34, 148, 114, 17, 102, 191, 98, 30, 115,
+                168, 44, 191, 35, 66, 200, 88, 238, 172,
+            ],
+            multiset_hash: Hash::from_bytes([
+                44, 55, 150, 32, 253, 244, 236, 10, 194, 83, 203, 228, 186, 130, 194, 187, 220, 15, 237, 172, 127, 224, 228, 82, 149,
+                125, 147, 117, 123, 191, 245, 193,
+            ]),
+            cumulative_hash: Hash::from_bytes([
+                44, 55, 150, 32, 253, 244, 236, 10, 194, 83, 203, 228, 186, 130, 194, 187, 220, 15, 237, 172, 127, 224, 228, 82, 149,
+                125, 147, 117, 123, 191, 245, 193,
+            ]),
+        },
+        TestVector {
+            data: &[
+                213, 253, 204, 84, 30, 37, 222, 28, 122, 90, 221, 237, 242, 72, 88, 184, 187, 102, 92, 159, 54, 239, 116, 78, 228, 44,
+                49, 96, 34, 201, 15, 155, 0, 0, 0, 0, 2, 0, 0, 0, 1, 0, 242, 5, 42, 1, 0, 0, 0, 67, 65, 4, 114, 17, 168, 36, 245, 91,
+                80, 82, 40, 228, 195, 213, 25, 76, 31, 207, 170, 21, 164, 86, 171, 223, 55, 249, 185, 217, 122, 64, 64, 175, 192, 115,
+                222, 230, 200, 144, 100, 152, 79, 3, 56, 82, 55, 217, 33, 103, 193, 62, 35, 100, 70, 180, 23, 171, 121, 160, 252, 174,
+                65, 42, 227, 49, 107, 119, 172,
+            ],
+            multiset_hash: Hash::from_bytes([
+                102, 139, 178, 146, 239, 21, 44, 84, 219, 15, 87, 20, 191, 69, 255, 141, 167, 177, 212, 28, 12, 80, 38, 173, 101, 91,
+                47, 158, 27, 230, 126, 33,
+            ]),
+            cumulative_hash: Hash::from_bytes([
+                177, 91, 209, 18, 74, 107, 82, 230, 78, 218, 60, 48, 35, 197, 135, 228, 85, 167, 158, 116, 140, 140, 149, 77, 215, 65,
+                29, 13, 189, 151, 56, 99,
+            ]),
+        },
+        TestVector {
+            data: &[
+                68, 246, 114, 34, 96, 144, 216, 93, 185, 169, 242, 251, 254, 95, 15, 150, 9, 179, 135, 175, 123, 229, 183, 251, 183,
+                161, 118, 124, 131, 28, 158, 153, 0, 0, 0, 0, 3, 0, 0, 0, 1, 0, 242, 5, 42, 1, 0, 0, 0, 67, 65, 4, 148, 185, 211, 231,
+                108, 91, 22, 41, 236, 249, 127, 255, 149, 215, 164, 187, 218, 200, 124, 194, 96, 153, 173, 162, 128, 102, 198, 255,
+                30, 185, 25, 18, 35, 205, 137, 113, 148, 160, 141, 12, 39, 38, 197, 116, 127, 29, 180, 158, 140, 249, 14, 117, 220,
+                62, 53, 80, 174, 155, 48, 8, 111, 60, 213, 170, 172,
+            ],
+            multiset_hash: Hash::from_bytes([
+                244, 11, 32, 189, 196, 62, 242, 240, 26, 23, 59, 118, 124, 185, 198, 184, 219, 86, 2, 235, 83, 95, 203, 152, 39, 56,
+                95, 155, 14, 58, 250, 244,
+            ]),
+            cumulative_hash: Hash::from_bytes([
+                230, 156, 110, 5, 4, 16, 118, 22, 72, 206, 98, 118, 168, 28, 128, 68, 185, 239, 177, 113, 94, 166, 246, 251, 159, 140,
+                247, 168, 193, 232, 3, 150,
+            ]),
+        },
+    ];
+
+    fn element_from_byte(b: u8) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        out[0] = b;
+        out
+    }
+
+    #[test]
+    fn test_vectors() {
+        // Vectors from kaspad
+        assert_eq!(MuHash::new().finalize(), EMPTY_MUHASH);
+
+        let mut muhash = MuHash::new();
+        muhash.add_element(&element_from_byte(0));
+        muhash.add_element(&element_from_byte(1));
+        muhash.remove_element(&element_from_byte(2));
+        assert_eq!(muhash.finalize().to_string(), "b557f7cfc13cf9abc31374832715e7bff2cf5859897523337a0ead9dde012974");
+
+        let mut cumulative = MuHash::new();
+        for test in TEST_VECTORS.iter() {
+            let mut muhash = MuHash::new();
+            muhash.add_element(test.data);
+            assert_eq!(muhash.finalize(), test.multiset_hash);
+            cumulative.add_element(test.data);
+            assert_eq!(cumulative.finalize(), test.cumulative_hash);
+        }
+        for (i, test) in TEST_VECTORS.iter().enumerate().rev() {
+            cumulative.remove_element(test.data);
+            let expected = if i == 0 { EMPTY_MUHASH } else { TEST_VECTORS[i - 1].cumulative_hash };
+            assert_eq!(cumulative.finalize(), expected);
+        }
+    }
+
+    #[test]
+    fn test_combine_and_order() {
+        let mut added = MuHash::new();
+        let mut removed = MuHash::new();
+        for test in TEST_VECTORS.iter() {
+            added.add_element(test.data);
+            removed.remove_element(test.data);
+        }
+        added.combine(&removed);
+        assert_eq!(added.finalize(), EMPTY_MUHASH);
+
+        // Removing an element before adding it is the same as never having it
+        let mut early = MuHash::new();
+        early.remove_element(TEST_VECTORS[0].data);
+        early.add_element(TEST_VECTORS[1].data);
+        early.add_element(TEST_VECTORS[0].data);
+        let mut single = MuHash::new();
+        single.add_element(TEST_VECTORS[1].data);
+        assert_eq!(early.finalize(), single.finalize());
+    }
+
+    #[test]
+    fn test_serialize() {
+        // Vector from kaspad
+        let expected = [
+            50, 5, 73, 166, 198, 210, 31, 202, 37, 64, 219, 222, 57, 158, 121, 89, 67, 188, 211, 73, 217, 251, 250, 178, 135, 196, 39,
+            250, 122, 202, 56, 228, 146, 233, 249, 16, 68, 9, 255, 158, 152, 84, 168, 146, 121, 81, 181, 60, 96, 141, 114, 26, 127,
+            140, 164, 90, 87, 187, 24, 4, 187, 151, 135, 91, 9, 249, 103, 124, 91, 55, 72, 202, 43, 241, 196, 243, 201, 237, 141, 158,
+            166, 125, 185, 26, 201, 232, 80, 72, 3, 7, 248, 152, 116, 148, 44, 250, 108, 167, 175, 61, 128, 159, 48, 148, 28, 247, 22,
+            158, 40, 130, 41, 154, 93, 184, 199, 177, 0, 170, 212, 159, 61, 233, 131, 243, 16, 17, 246, 132, 114, 31, 155, 37, 25, 97,
+            107, 11, 100, 17, 23, 61, 12, 218, 176, 129, 173, 148, 221, 6, 152, 157, 112, 106, 90, 5, 215, 0, 133, 133, 41, 241, 217,
+            237, 6, 202, 106, 252, 196, 244, 209, 141, 220, 236, 40, 221, 219, 122, 222, 96, 27, 189, 60, 69, 150, 124, 29, 78, 206,
+            249, 146, 179, 191, 11, 187, 178, 48, 114, 127, 155, 74, 137, 140, 109, 182, 88, 192, 120, 71, 141, 197, 93, 178, 179,
+            254, 252, 167, 251, 245, 77, 112, 186, 216, 30, 239, 147, 168, 67, 89, 96, 14, 102, 165, 187, 163, 232, 51, 77, 117, 134,
+            160, 254, 89, 201, 57, 113, 76, 137, 99, 101, 233, 35, 46, 213, 124, 38, 247, 12, 125, 203, 220, 54, 114, 68, 242, 192,
+            107, 216, 226, 140, 66, 78, 65, 166, 255, 4, 2, 89, 247, 184, 204, 145, 54, 105, 210, 209, 195, 248, 63, 207, 199, 218,
+            253, 92, 150, 190, 212, 216, 23, 121, 18, 14, 27, 35, 191, 203, 50, 238, 10, 190, 192, 47, 210, 100, 58, 38, 201, 103,
+            199, 59, 32, 72, 37, 221, 104, 87, 120, 222, 61, 144, 107, 107, 114, 27, 152, 88, 232, 113, 97, 184, 69, 116, 17, 59, 245,
+            151, 99, 140, 167, 85, 47, 28, 51, 198, 140, 233, 21, 92, 211, 79, 1, 68, 217, 131, 37, 19, 5, 107, 51, 219, 141, 109,
+            155, 196, 183, 148, 16, 113, 227, 141, 202, 215, 191, 50, 241, 244,
+        ];
+        let mut muhash = MuHash::new();
+        muhash.add_element(&element_from_byte(1));
+        muhash.add_element(&element_from_byte(2));
+        let serialized = muhash.serialize();
+        assert_eq!(serialized, expected);
+        assert_eq!(MuHash::deserialize(serialized).unwrap().finalize(), muhash.finalize());
+
+        assert_eq!(MuHash::deserialize([255; SERIALIZED_MUHASH_SIZE]).unwrap_err(), MuHashError::Overflow);
+        // p itself overflows, p - 1 doesn't
+        let mut serialized = [255; SERIALIZED_MUHASH_SIZE];
+        serialized[0..3].copy_from_slice(&[155, 40, 239]);
+        assert_eq!(MuHash::deserialize(serialized).unwrap_err(), MuHashError::Overflow);
+        serialized[0] = 154;
+        assert!(MuHash::deserialize(serialized).is_ok());
+    }
+}
@@ -0,0 +1,242 @@
+//! Elements of the prime field of MuHash3072, modulo `p = 2^3072 - 1103717`
+
+use std::ops::{Mul, MulAssign};
+
+pub(crate) const LIMBS: usize = 48;
+pub(crate) const BYTES: usize = LIMBS * 8;
+
+/// `2^3072 - p`
+const PRIME_DIFF: u64 = 1103717;
+
+/// A field element, kept below `2^3072` but not necessarily below `p` between multiplications
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct U3072 {
+    limbs: [u64; LIMBS],
+}
+
+impl U3072 {
+    pub const fn zero() -> Self {
+        Self { limbs: [0; LIMBS] }
+    }
+
+    pub const fn one() -> Self {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = 1;
+        Self { limbs }
+    }
+
+    const PRIME: Self = {
+        let mut limbs = [u64::MAX; LIMBS];
+        limbs[0] = u64::MAX - PRIME_DIFF + 1;
+        Self { limbs }
+    };
+
+    pub fn from_le_bytes(bytes: [u8; BYTES]) -> Self {
+        let mut limbs = [0; LIMBS];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        Self { limbs }
+    }
+
+    /// Serializes the reduced value.
+    pub fn to_le_bytes(self) -> [u8; BYTES] {
+        let mut bytes = [0; BYTES];
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(self.reduced().limbs) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Returns whether the value is at least `p`, and thus not a canonical field element.
+    pub fn is_overflow(&self) -> bool {
+        self.limbs[0] > u64::MAX - PRIME_DIFF && self.limbs[1..].iter().all(|limb| *limb == u64::MAX)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|limb| *limb == 0)
+    }
+
+    fn is_one(&self) -> bool {
+        *self == Self::one()
+    }
+
+    fn is_even(&self) -> bool {
+        self.limbs[0] & 1 == 0
+    }
+
+    /// The unique representative below `p`
+    fn reduced(self) -> Self {
+        match self.is_overflow() {
+            true => self.overflowing_sub(&Self::PRIME).0,
+            false => self,
+        }
+    }
+
+    fn overflowing_add(&self, other: &Self) -> (Self, bool) {
+        let mut carry = false;
+        let mut limbs = [0; LIMBS];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let (sum, overflow1) = self.limbs[i].overflowing_add(other.limbs[i]);
+            let (sum, overflow2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = overflow1 || overflow2;
+        }
+        (Self { limbs }, carry)
+    }
+
+    fn overflowing_sub(&self, other: &Self) -> (Self, bool) {
+        let mut borrow = false;
+        let mut limbs = [0; LIMBS];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let (diff, overflow1) = self.limbs[i].overflowing_sub(other.limbs[i]);
+            let (diff, overflow2) = diff.overflowing_sub(borrow as u64);
+            *limb = diff;
+            borrow = overflow1 || overflow2;
+        }
+        (Self { limbs }, borrow)
+    }
+
+    /// Shifts right by one bit, `high_bit` being shifted in as the 3072th bit.
+    fn shr1(&mut self, high_bit: bool) {
+        for i in 0..LIMBS - 1 {
+            self.limbs[i] = (self.limbs[i] >> 1) | (self.limbs[i + 1] << 63);
+        }
+        self.limbs[LIMBS - 1] = (self.limbs[LIMBS - 1] >> 1) | ((high_bit as u64) << 63);
+    }
+
+    /// Halves a reduced element modulo `p`.
+    fn half_mod(&mut self) {
+        if self.is_even() {
+            self.shr1(false);
+        } else {
+            let (sum, carry) = self.overflowing_add(&Self::PRIME);
+            *self = sum;
+            self.shr1(carry);
+        }
+    }
+
+    /// Subtracts reduced elements modulo `p`.
+    fn sub_mod(&self, other: &Self) -> Self {
+        match self.overflowing_sub(other) {
+            (diff, true) => diff.overflowing_add(&Self::PRIME).0,
+            (diff, false) => diff,
+        }
+    }
+
+    /// Adds `value * 2^3072` as `value * PRIME_DIFF` until the result fits.
+    fn fold_carry(&mut self, mut value: u64) {
+        while value != 0 {
+            let mut carry = value as u128 * PRIME_DIFF as u128;
+            for limb in self.limbs.iter_mut() {
+                if carry == 0 {
+                    break;
+                }
+                let sum = *limb as u128 + (carry as u64) as u128;
+                *limb = sum as u64;
+                carry = (carry >> 64) + (sum >> 64);
+            }
+            value = carry as u64;
+        }
+    }
+
+    /// Multiplicative inverse with the binary extended Euclidean algorithm, zero having none.
+    pub fn inverse(&self) -> Self {
+        let mut u = self.reduced();
+        if u.is_zero() {
+            return u;
+        }
+        let mut v = Self::PRIME;
+        let (mut x1, mut x2) = (Self::one(), Self::zero());
+        // Invariants: x1 * a = u and x2 * a = v modulo p
+        while !u.is_one() && !v.is_one() {
+            while u.is_even() {
+                u.shr1(false);
+                x1.half_mod();
+            }
+            while v.is_even() {
+                v.shr1(false);
+                x2.half_mod();
+            }
+            match u.overflowing_sub(&v) {
+                (diff, false) => {
+                    u = diff;
+                    x1 = x1.sub_mod(&x2);
+                }
+                (_, true) => {
+                    v = v.overflowing_sub(&u).0;
+                    x2 = x2.sub_mod(&x1);
+                }
+            }
+        }
+        match u.is_one() {
+            true => x1,
+            false => x2,
+        }
+    }
+}
+
+impl Mul for U3072 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        // Schoolbook product over 96 limbs
+        let mut product = [0u64; 2 * LIMBS];
+        for i in 0..LIMBS {
+            let mut carry = 0u128;
+            for j in 0..LIMBS {
+                let t = self.limbs[i] as u128 * other.limbs[j] as u128 + product[i + j] as u128 + carry;
+                product[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            product[i + LIMBS] = carry as u64;
+        }
+
+        // 2^3072 = PRIME_DIFF modulo p, so the high half folds into the low one multiplied by PRIME_DIFF
+        let mut result = Self { limbs: product[..LIMBS].try_into().unwrap() };
+        let mut carry = 0u128;
+        for i in 0..LIMBS {
+            let t = product[LIMBS + i] as u128 * PRIME_DIFF as u128 + result.limbs[i] as u128 + carry;
+            result.limbs[i] = t as u64;
+            carry = t >> 64;
+        }
+        result.fold_carry(carry as u64);
+        result
+    }
+}
+
+impl MulAssign for U3072 {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_u64(n: u64) -> U3072 {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = n;
+        U3072 { limbs }
+    }
+
+    #[test]
+    fn test_field_arithmetic() {
+        let minus_one = U3072::PRIME.overflowing_sub(&U3072::one()).0;
+        assert!(!minus_one.is_overflow() && U3072::PRIME.is_overflow());
+        assert_eq!((minus_one * minus_one).to_le_bytes(), U3072::one().to_le_bytes());
+        assert_eq!((from_u64(3) * from_u64(5)).to_le_bytes(), from_u64(15).to_le_bytes());
+
+        // 2^3071 * 2 wraps around to PRIME_DIFF
+        let mut high = U3072::zero();
+        high.limbs[LIMBS - 1] = 1 << 63;
+        assert_eq!((high * from_u64(2)).to_le_bytes(), from_u64(PRIME_DIFF).to_le_bytes());
+
+        for n in [1, 2, 3, 7, PRIME_DIFF, u64::MAX] {
+            let x = from_u64(n) * minus_one * high;
+            assert_eq!((x * x.inverse()).to_le_bytes(), U3072::one().to_le_bytes());
+        }
+        assert_eq!(U3072::zero().inverse(), U3072::zero());
+    }
+}
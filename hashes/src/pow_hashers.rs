@@ -3,6 +3,7 @@ use crate::Hash;
 #[derive(Clone)]
 pub struct PowHash([u64; 25]);
 
+/// The final hashing step of kHeavyHash, applied to the pre-PoW hash once multiplied by the matrix of the block
 #[derive(Clone)]
 pub struct KHeavyHash;
 
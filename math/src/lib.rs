@@ -0,0 +1,5 @@
+//! Fixed size unsigned integers wider than the native ones
+
+mod uint256;
+
+pub use uint256::Uint256;
@@ -0,0 +1,357 @@
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Display, Formatter},
+    ops::{Add, Div, Not, Shl, Shr, Sub},
+};
+
+/// A 256 bits unsigned integer, stored as little endian 64 bits words
+#[derive(Clone, Copy, Default, Hash, PartialEq, Eq)]
+pub struct Uint256(pub [u64; 4]);
+
+impl Uint256 {
+    pub const ZERO: Self = Self([0; 4]);
+    pub const MAX: Self = Self([u64::MAX; 4]);
+    pub const BITS: u32 = 256;
+    pub const BYTES: usize = 32;
+
+    #[inline]
+    pub fn from_u64(n: u64) -> Self {
+        Self([n, 0, 0, 0])
+    }
+
+    #[inline]
+    pub fn from_u128(n: u128) -> Self {
+        Self([n as u64, (n >> 64) as u64, 0, 0])
+    }
+
+    /// Returns the value if it fits in 128 bits.
+    #[inline]
+    pub fn as_u128(&self) -> Option<u128> {
+        match self.0[2] == 0 && self.0[3] == 0 {
+            true => Some(self.0[0] as u128 | (self.0[1] as u128) << 64),
+            false => None,
+        }
+    }
+
+    /// The nearest floating point value
+    #[inline]
+    pub fn as_f64(&self) -> f64 {
+        self.0.iter().rev().fold(0f64, |acc, word| acc * 18446744073709551616f64 + *word as f64)
+    }
+
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|word| *word == 0)
+    }
+
+    /// Number of bits needed to represent the value, that is the position of its highest set bit plus one
+    #[inline]
+    pub fn bits(&self) -> u32 {
+        match self.0.iter().rposition(|word| *word != 0) {
+            Some(i) => 64 * i as u32 + (64 - self.0[i].leading_zeros()),
+            None => 0,
+        }
+    }
+
+    #[inline]
+    pub fn from_le_bytes(bytes: [u8; Self::BYTES]) -> Self {
+        let mut words = [0u64; 4];
+        for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(8)) {
+            *word = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        Self(words)
+    }
+
+    #[inline]
+    pub fn to_le_bytes(self) -> [u8; Self::BYTES] {
+        let mut bytes = [0u8; Self::BYTES];
+        for (chunk, word) in bytes.chunks_exact_mut(8).zip(self.0.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[inline]
+    pub fn to_be_bytes(self) -> [u8; Self::BYTES] {
+        let mut bytes = self.to_le_bytes();
+        bytes.reverse();
+        bytes
+    }
+
+    /// Decodes the compact representation of a target found in the `bits` field of block headers.
+    ///
+    /// The representation is a floating point number whose mantissa is the lower 3 bytes and whose
+    /// base 256 exponent is the higher byte minus 3. A negative mantissa decodes to zero.
+    pub fn from_compact_target_bits(bits: u32) -> Self {
+        let exponent = bits >> 24;
+        let (mantissa, shift) = match exponent <= 3 {
+            true => ((bits & 0xffffff) >> (8 * (3 - exponent)), 0),
+            false => (bits & 0xffffff, 8 * (exponent - 3)),
+        };
+        if mantissa > 0x7fffff {
+            return Self::ZERO;
+        }
+        Self::from_u64(mantissa as u64) << shift
+    }
+
+    /// Encodes the value in the compact representation of [`Self::from_compact_target_bits`], truncating
+    /// the mantissa to its 3 most significant bytes.
+    pub fn compact_target_bits(&self) -> u32 {
+        let mut size = self.bits().div_ceil(8);
+        let mut compact = match size <= 3 {
+            true => (self.0[0] << (8 * (3 - size))) as u32,
+            false => (*self >> (8 * (size - 3))).0[0] as u32,
+        };
+        // Keep the mantissa positive
+        if compact & 0x00800000 != 0 {
+            compact >>= 8;
+            size += 1;
+        }
+        compact | (size << 24)
+    }
+
+    /// Adds, returning whether the result wrapped around.
+    pub fn overflowing_add(self, other: Self) -> (Self, bool) {
+        let mut result = Self::ZERO;
+        let mut carry = false;
+        for i in 0..4 {
+            let (sum, carry1) = self.0[i].overflowing_add(other.0[i]);
+            let (sum, carry2) = sum.overflowing_add(carry as u64);
+            result.0[i] = sum;
+            carry = carry1 || carry2;
+        }
+        (result, carry)
+    }
+
+    /// Subtracts, returning whether the result wrapped around.
+    pub fn overflowing_sub(self, other: Self) -> (Self, bool) {
+        let mut result = Self::ZERO;
+        let mut borrow = false;
+        for i in 0..4 {
+            let (diff, borrow1) = self.0[i].overflowing_sub(other.0[i]);
+            let (diff, borrow2) = diff.overflowing_sub(borrow as u64);
+            result.0[i] = diff;
+            borrow = borrow1 || borrow2;
+        }
+        (result, borrow)
+    }
+
+    /// Divides by binary long division, returning the quotient and the remainder.
+    ///
+    /// # Panics
+    /// Panics if `divisor` is zero.
+    pub fn div_rem(self, divisor: Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "division by zero");
+        let mut quotient = Self::ZERO;
+        let mut remainder = Self::ZERO;
+        for i in (0..self.bits()).rev() {
+            remainder = remainder << 1;
+            remainder.0[0] |= (self.0[i as usize / 64] >> (i % 64)) & 1;
+            if remainder >= divisor {
+                remainder = remainder - divisor;
+                quotient.0[i as usize / 64] |= 1 << (i % 64);
+            }
+        }
+        (quotient, remainder)
+    }
+}
+
+impl Add for Uint256 {
+    type Output = Self;
+
+    /// # Panics
+    /// Panics on overflow.
+    fn add(self, other: Self) -> Self {
+        let (result, overflow) = self.overflowing_add(other);
+        assert!(!overflow, "attempt to add with overflow");
+        result
+    }
+}
+
+impl Sub for Uint256 {
+    type Output = Self;
+
+    /// # Panics
+    /// Panics on underflow.
+    fn sub(self, other: Self) -> Self {
+        let (result, overflow) = self.overflowing_sub(other);
+        assert!(!overflow, "attempt to subtract with overflow");
+        result
+    }
+}
+
+impl Div for Uint256 {
+    type Output = Self;
+
+    fn div(self, divisor: Self) -> Self {
+        self.div_rem(divisor).0
+    }
+}
+
+impl Not for Uint256 {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(self.0.map(|word| !word))
+    }
+}
+
+impl Shl<u32> for Uint256 {
+    type Output = Self;
+
+    /// Shifts to the left, dropping the overflowing bits.
+    fn shl(self, shift: u32) -> Self {
+        let mut result = Self::ZERO;
+        let (words, bits) = ((shift / 64) as usize, shift % 64);
+        for i in words..4 {
+            result.0[i] = self.0[i - words] << bits;
+            if bits > 0 && i > words {
+                result.0[i] |= self.0[i - words - 1] >> (64 - bits);
+            }
+        }
+        result
+    }
+}
+
+impl Shr<u32> for Uint256 {
+    type Output = Self;
+
+    /// Shifts to the right, dropping the underflowing bits.
+    fn shr(self, shift: u32) -> Self {
+        let mut result = Self::ZERO;
+        let (words, bits) = ((shift / 64) as usize, shift % 64);
+        for i in 0..4usize.saturating_sub(words) {
+            result.0[i] = self.0[i + words] >> bits;
+            if bits > 0 && i + words + 1 < 4 {
+                result.0[i] |= self.0[i + words + 1] << (64 - bits);
+            }
+        }
+        result
+    }
+}
+
+impl PartialOrd for Uint256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Uint256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl From<u64> for Uint256 {
+    fn from(n: u64) -> Self {
+        Self::from_u64(n)
+    }
+}
+
+impl From<u128> for Uint256 {
+    fn from(n: u128) -> Self {
+        Self::from_u128(n)
+    }
+}
+
+impl Display for Uint256 {
+    /// Formats as a big endian hex string
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut hex = [0u8; Self::BYTES * 2];
+        faster_hex::hex_encode(&self.to_be_bytes(), &mut hex).expect("The output is exactly twice the size of the input");
+        f.write_str(std::str::from_utf8(&hex).expect("hex is always valid UTF-8"))
+    }
+}
+
+impl Debug for Uint256 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shl_and_ordering() {
+        let one = Uint256::from_u64(1);
+        assert_eq!(one << 0, one);
+        assert_eq!(one << 64, Uint256([0, 1, 0, 0]));
+        assert_eq!(Uint256::from_u64(0x8000_0000_0000_0001) << 1, Uint256([2, 1, 0, 0]));
+        assert_eq!(one << 255, Uint256([0, 0, 0, 1 << 63]));
+        assert_eq!(one << 256, Uint256::ZERO);
+        assert!(Uint256([0, 0, 0, 1]) > Uint256([u64::MAX, u64::MAX, u64::MAX, 0]));
+        assert!(Uint256::from_u64(2) > one && one > Uint256::ZERO);
+        assert_eq!((one << 200).bits(), 201);
+        assert_eq!(Uint256::ZERO.bits(), 0);
+    }
+
+    #[test]
+    fn test_compact_target_bits() {
+        let tests = [
+            (0x00000000, Uint256::ZERO),
+            (0x0112_3456, Uint256::from_u64(0x12)),
+            (0x0100_3456, Uint256::ZERO),
+            (0x0212_3456, Uint256::from_u64(0x1234)),
+            (0x0312_3456, Uint256::from_u64(0x123456)),
+            (0x0412_3456, Uint256::from_u64(0x12345600)),
+            (0x0500_9234, Uint256::from_u64(0x92340000)),
+            (0x2000_1234, Uint256::from_u64(0x1234) << 232),
+            (0x207f_ffff, Uint256::from_u64(0x7fffff) << 232),
+            // Negative mantissa
+            (0x0492_3456, Uint256::ZERO),
+        ];
+        for (bits, target) in tests {
+            assert_eq!(Uint256::from_compact_target_bits(bits), target, "bits {bits:#x}");
+        }
+
+        let tests = [
+            (Uint256::ZERO, 0),
+            (Uint256::from_u64(0x12), 0x0112_0000),
+            (Uint256::from_u64(0x80), 0x0200_8000),
+            (Uint256::from_u64(0x1234), 0x0212_3400),
+            (Uint256::from_u64(0x12345600), 0x0412_3456),
+            (Uint256::from_u64(0x92340000), 0x0500_9234),
+            (Uint256::from_u64(0x1234) << 232, 0x1f12_3400),
+            (Uint256::from_u64(0x7fffff) << 232, 0x207f_ffff),
+            // Truncated mantissa
+            (Uint256::from_u64(0x123456789), 0x0501_2345),
+        ];
+        for (target, bits) in tests {
+            assert_eq!(target.compact_target_bits(), bits, "target {target}");
+            assert!(Uint256::from_compact_target_bits(bits) <= target);
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Uint256([u64::MAX, 7, 0, 1]);
+        let b = Uint256::from_u128(0x1234_5678_9abc_def0_1122_3344);
+        assert_eq!(a + b - b, a);
+        assert_eq!(Uint256::MAX.overflowing_add(Uint256::from_u64(1)), (Uint256::ZERO, true));
+        assert_eq!(Uint256::ZERO.overflowing_sub(Uint256::from_u64(1)), (Uint256::MAX, true));
+        assert_eq!(!Uint256::ZERO, Uint256::MAX);
+
+        let (x, y) = (0xffee_ddcc_bbaa_9988_7766_5544_3322_1100u128, 0x1_0000_0001u128);
+        assert_eq!(Uint256::from_u128(x).div_rem(Uint256::from_u128(y)), (Uint256::from_u128(x / y), Uint256::from_u128(x % y)));
+        assert!(a.div_rem(b).1 < b);
+        assert_eq!(Uint256::from_u64(1000).div_rem(Uint256::from_u64(7)), (Uint256::from_u64(142), Uint256::from_u64(6)));
+        assert_eq!((Uint256::from_u64(1) << 200) / (Uint256::from_u64(1) << 100), Uint256::from_u64(1) << 100);
+
+        assert_eq!((Uint256::from_u64(1) << 200) >> 136, Uint256::from_u64(1) << 64);
+        assert_eq!(Uint256([0, 0, 0, 1 << 63]) >> 255, Uint256::from_u64(1));
+        assert_eq!(Uint256::MAX >> 256, Uint256::ZERO);
+        assert_eq!(b.as_u128(), Some(0x1234_5678_9abc_def0_1122_3344));
+        assert_eq!(a.as_u128(), None);
+        assert_eq!((Uint256::from_u64(3) << 192).as_f64(), 3.0 * 2f64.powi(192));
+    }
+
+    #[test]
+    fn test_bytes() {
+        let value = Uint256([1, 2, 3, 4]);
+        assert_eq!(Uint256::from_le_bytes(value.to_le_bytes()), value);
+        assert_eq!(value.to_be_bytes()[31], 1);
+        assert_eq!(value.to_string(), "0000000000000004000000000000000300000000000000020000000000000001");
+    }
+}
@@ -1,5 +1,6 @@
 use crate::model::message::*;
 use crate::stubs::*;
+use crate::RpcAddress;
 use async_std::channel::{Receiver, Sender};
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Serialize};
@@ -12,7 +13,7 @@ pub enum NotificationType {
     VirtualSelectedParentChainChanged,
     FinalityConflicts,
     FinalityConflictResolved,
-    UtxosChanged(Vec<RpcUtxoAddress>),
+    UtxosChanged(Vec<RpcAddress>),
     VirtualSelectedParentBlueScoreChanged,
     VirtualDaaScoreChanged,
     PruningPointUTXOSetOverride,
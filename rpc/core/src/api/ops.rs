@@ -40,8 +40,49 @@ pub enum RpcApiOps {
 
     // Server to client notification
     Notification,
+
+    GetMetrics,
 }
 
+// TODO: write a macro or use an external crate to get this
+pub const RPC_API_OPS_ARRAY: &[RpcApiOps] = &[
+    RpcApiOps::Ping,
+    RpcApiOps::GetCurrentNetwork,
+    RpcApiOps::SubmitBlock,
+    RpcApiOps::GetBlockTemplate,
+    RpcApiOps::GetPeerAddresses,
+    RpcApiOps::GetSelectedTipHash,
+    RpcApiOps::GetMempoolEntry,
+    RpcApiOps::GetMempoolEntries,
+    RpcApiOps::GetConnectedPeerInfo,
+    RpcApiOps::AddPeer,
+    RpcApiOps::SubmitTransaction,
+    RpcApiOps::GetBlock,
+    RpcApiOps::GetSubnetwork,
+    RpcApiOps::GetVirtualSelectedParentChainFromBlock,
+    RpcApiOps::GetBlocks,
+    RpcApiOps::GetBlockCount,
+    RpcApiOps::GetBlockDagInfo,
+    RpcApiOps::ResolveFinalityConflict,
+    RpcApiOps::Shutdown,
+    RpcApiOps::GetHeaders,
+    RpcApiOps::GetUtxosByAddresses,
+    RpcApiOps::GetBalanceByAddress,
+    RpcApiOps::GetBalancesByAddresses,
+    RpcApiOps::GetVirtualSelectedParentBlueScore,
+    RpcApiOps::Ban,
+    RpcApiOps::Unban,
+    RpcApiOps::GetInfo,
+    RpcApiOps::EstimateNetworkHashesPerSecond,
+    RpcApiOps::GetMempoolEntriesByAddresses,
+    RpcApiOps::GetCoinSupply,
+    RpcApiOps::NotifyBlockAdded,
+    RpcApiOps::Notification,
+    RpcApiOps::GetMetrics,
+];
+
+pub(crate) const RPC_API_OPS_COUNT: usize = RPC_API_OPS_ARRAY.len();
+
 impl From<RpcApiOps> for u32 {
     fn from(item: RpcApiOps) -> Self {
         item as u32
@@ -73,10 +114,17 @@ impl From<i32> for SubscribeCommand {
 
 #[cfg(test)]
 mod tests {
-    use super::RpcApiOps;
+    use super::{RpcApiOps, RPC_API_OPS_ARRAY};
 
     #[test]
     fn test_rpc_api_ops_convert() {
         assert_eq!(0 as u32, RpcApiOps::Ping.into());
     }
+
+    #[test]
+    fn test_rpc_api_ops_array() {
+        for (i, op) in RPC_API_OPS_ARRAY.iter().enumerate() {
+            assert_eq!(i as u32, u32::from(op.clone()));
+        }
+    }
 }
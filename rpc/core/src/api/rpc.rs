@@ -16,20 +16,14 @@ use async_trait::async_trait;
 
 #[async_trait]
 pub trait RpcApi: Sync + Send {
-    // async fn ping(
-    //     &self,
-    //     msg : String
-    // ) -> RpcResult<String>;
+    /// Checks that the node is alive and responsive.
+    async fn ping(&self, req: PingRequest) -> RpcResult<PingResponse>;
 
-    // async fn get_current_network(
-    //     &self
-    // ) -> RpcResult<NetworkType>;
+    /// Returns the network the node is running against.
+    async fn get_current_network(&self, req: GetCurrentNetworkRequest) -> RpcResult<GetCurrentNetworkResponse>;
 
-    // async fn submit_block(
-    //     &self,
-    //     block: RpcBlock,
-    //     allow_non_daa_blocks : bool
-    // ) -> RpcResult<SubmitBlockResponse>;
+    /// Submits a block into the DAG, the response reporting whether consensus accepted it.
+    async fn submit_block(&self, req: SubmitBlockRequest) -> RpcResult<SubmitBlockResponse>;
 
     // async fn get_block_template(
     //     &self,
@@ -40,9 +34,7 @@ pub trait RpcApi: Sync + Send {
     //     &self
     // ) -> RpcResult<GetPeerAddressesResponse>;
 
-    // async fn get_selected_tip_hash(
-    //     &self
-    // ) -> RpcResult<GetSelectedTipHashResponse>;
+    async fn get_selected_tip_hash(&self, req: GetSelectedTipHashRequest) -> RpcResult<GetSelectedTipHashResponse>;
 
     // async fn get_mempool_entry(
     //     &self,
@@ -102,9 +94,10 @@ pub trait RpcApi: Sync + Send {
     //     req: ResolveFinalityConflictRequest
     // ) -> RpcResult<ResolveFinalityConflictResponse>;
 
-    // async fn shutdown(
-    //     &self
-    // ) -> RpcResult<()>;
+    /// Requests the node to shut down gracefully.
+    ///
+    /// Transports expose this operation to authorized clients only.
+    async fn shutdown(&self, req: ShutdownRequest) -> RpcResult<ShutdownResponse>;
 
     // async fn get_headers(
     //     &self,
@@ -142,19 +135,19 @@ pub trait RpcApi: Sync + Send {
 
     async fn get_info(&self, req: GetInfoRequest) -> RpcResult<GetInfoResponse>;
 
-    // async fn estimate_network_hashes_per_second(
-    //     &self,
-    //     req: EstimateNetworkHashesPerSecondRequest
-    // ) -> RpcResult<u64>;
+    async fn estimate_network_hashes_per_second(
+        &self,
+        req: EstimateNetworkHashesPerSecondRequest,
+    ) -> RpcResult<EstimateNetworkHashesPerSecondResponse>;
 
     // async fn get_mempool_entries_by_addresses(
     //     &self,
     //     req: GetMempoolEntriesByAddressesRequest
     // ) -> RpcResult<GetMempoolEntriesByAddressesResponse>;
 
-    // async fn get_coin_supply(
-    //     &self
-    // ) -> RpcResult<GetCoinSupplyResponse>;
+    async fn get_coin_supply(&self, req: GetCoinSupplyRequest) -> RpcResult<GetCoinSupplyResponse>;
+
+    async fn get_metrics(&self, req: GetMetricsRequest) -> RpcResult<GetMetricsResponse>;
 
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
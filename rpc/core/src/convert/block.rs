@@ -1,33 +1,50 @@
 use std::sync::Arc;
 
-use crate::{RpcBlock, RpcBlockVerboseData, RpcError, RpcResult};
-use consensus_core::block::Block;
+use crate::{RpcBlock, RpcBlockVerboseData, RpcError, RpcResult, RpcTransaction};
+use addresses::Prefix;
+use consensus_core::{api::BlockDagContext, block::Block, tx::Transaction};
 
 // ----------------------------------------------------------------------------
 // consensus_core to rpc_core
 // ----------------------------------------------------------------------------
 
-impl From<&Block> for RpcBlock {
-    fn from(item: &Block) -> Self {
-        Self { header: (&item.header).into(), transactions: vec![], verbose_data: item.into() }
+impl RpcBlock {
+    /// Converts a block along with its relations in the DAG.
+    ///
+    /// The transactions are only converted if `include_transactions` is set, their verbose data
+    /// then referring to the block and their output addresses being encoded with `prefix`.
+    /// See kaspad `PopulateBlockWithVerboseData`.
+    pub fn from_block(block: &Block, context: &BlockDagContext, include_transactions: bool, prefix: Prefix) -> Self {
+        let transactions = match include_transactions {
+            true => block
+                .transactions
+                .iter()
+                .map(|tx| {
+                    let mut tx = RpcTransaction::from_transaction(tx, prefix);
+                    tx.verbose_data.block_hash = block.hash();
+                    tx.verbose_data.block_time = block.header.timestamp;
+                    tx
+                })
+                .collect(),
+            false => vec![],
+        };
+        Self { header: (&block.header).into(), transactions, verbose_data: RpcBlockVerboseData::from_block(block, context) }
     }
 }
 
-impl From<&Block> for RpcBlockVerboseData {
-    fn from(item: &Block) -> Self {
-        // FIXME
-        // see kaspad\app\rpc\rpccontext\verbosedata.go PopulateBlockWithVerboseData
+impl RpcBlockVerboseData {
+    pub fn from_block(block: &Block, context: &BlockDagContext) -> Self {
         Self {
-            hash: item.hash(),
-            difficulty: 0.into(),
-            selected_parent_hash: 0.into(),
-            transaction_ids: vec![],
-            is_header_only: true,
-            blue_score: 0u64,
-            children_hashes: vec![],
-            merge_set_blues_hashes: vec![],
-            merge_set_reds_hashes: vec![],
-            is_chain_block: false,
+            hash: block.hash(),
+            difficulty: context.difficulty,
+            selected_parent_hash: context.ghostdag_data.selected_parent,
+            transaction_ids: block.transactions.iter().map(|tx| tx.id()).collect(),
+            is_header_only: block.is_header_only(),
+            blue_score: context.ghostdag_data.blue_score,
+            children_hashes: context.children.clone(),
+            merge_set_blues_hashes: context.ghostdag_data.mergeset_blues.clone(),
+            merge_set_reds_hashes: context.ghostdag_data.mergeset_reds.clone(),
+            is_chain_block: context.is_chain_block,
         }
     }
 }
@@ -41,7 +58,75 @@ impl TryFrom<&RpcBlock> for Block {
     fn try_from(item: &RpcBlock) -> RpcResult<Self> {
         Ok(Self {
             header: (&item.header).try_into()?,
-            transactions: Arc::new(vec![]), // FIXME
+            transactions: Arc::new(item.transactions.iter().map(Transaction::try_from).collect::<RpcResult<Vec<Transaction>>>()?),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_core::{
+        ghostdag::GhostdagData,
+        hashing,
+        header::Header,
+        subnets::SUBNETWORK_ID_COINBASE,
+        tx::{ScriptPublicKey, TransactionOutput},
+    };
+    use hashes::Hash;
+
+    fn block() -> Block {
+        let script_public_key = Arc::new(ScriptPublicKey::new([&[0x20], &[9u8; 32][..], &[0xac]].concat(), 0));
+        let coinbase = Transaction::new(
+            0,
+            vec![],
+            vec![Arc::new(TransactionOutput::new(50_000, script_public_key))],
+            0,
+            SUBNETWORK_ID_COINBASE,
+            0,
+            vec![0; 8],
+            0,
+        );
+        let parents = vec![Hash::from_u64_word(1), Hash::from_u64_word(2)];
+        let header = Header::new(1, parents, Hash::from_u64_word(3), 1_668_000_000_000, 0x207fffff, 42, 123, 456, 78);
+        Block { header, transactions: Arc::new(vec![coinbase]) }
+    }
+
+    #[test]
+    fn test_block_round_trip() {
+        let block = block();
+        let context = BlockDagContext {
+            ghostdag_data: GhostdagData {
+                blue_score: 78,
+                blue_work: 456,
+                selected_parent: Hash::from_u64_word(2),
+                mergeset_blues: vec![Hash::from_u64_word(2), Hash::from_u64_word(1)],
+                mergeset_reds: vec![],
+            },
+            children: vec![Hash::from_u64_word(4)],
+            is_chain_block: true,
+            difficulty: 256.0,
+        };
+
+        let rpc_block = RpcBlock::from_block(&block, &context, true, Prefix::Mainnet);
+        let verbose_data = &rpc_block.verbose_data;
+        assert_eq!(verbose_data.hash, block.hash());
+        assert_eq!(verbose_data.selected_parent_hash, Hash::from_u64_word(2));
+        assert_eq!(verbose_data.children_hashes, context.children);
+        assert_eq!(verbose_data.merge_set_blues_hashes, context.ghostdag_data.mergeset_blues);
+        assert_eq!(verbose_data.transaction_ids, vec![block.transactions[0].id()]);
+        assert!(verbose_data.is_chain_block && !verbose_data.is_header_only);
+        assert_eq!(verbose_data.difficulty, 256.0);
+        assert_eq!(rpc_block.transactions[0].verbose_data.block_hash, block.hash());
+        assert_eq!(rpc_block.transactions[0].verbose_data.block_time, block.header.timestamp);
+
+        let converted = Block::try_from(&rpc_block).unwrap();
+        assert_eq!(hashing::header::hash(&converted.header), block.hash());
+        assert_eq!(converted.hash(), block.hash());
+        assert_eq!(hashing::tx::hash(&converted.transactions[0]), hashing::tx::hash(&block.transactions[0]));
+
+        let rpc_block = RpcBlock::from_block(&block, &context, false, Prefix::Mainnet);
+        assert!(rpc_block.transactions.is_empty());
+        assert_eq!(rpc_block.verbose_data.transaction_ids.len(), 1);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod block;
 pub mod header;
 pub mod notification;
+pub mod tx;
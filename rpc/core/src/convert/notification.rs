@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::{notify::collector::ArcConvert, BlockAddedNotification, Notification};
+use crate::{notify::collector::ArcConvert, BlockAddedNotification, Notification, RpcBlock};
 use consensus_core::stubs;
 
 // ----------------------------------------------------------------------------
@@ -17,7 +17,7 @@ impl From<&stubs::Notification> for Notification {
 
 impl From<&stubs::BlockAddedNotification> for BlockAddedNotification {
     fn from(item: &stubs::BlockAddedNotification) -> Self {
-        Self { block: (&item.block).into() }
+        Self { block: RpcBlock::from_block(&item.block, &item.context, true, item.network_type.into()) }
     }
 }
 
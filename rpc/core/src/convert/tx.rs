@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use crate::{
+    RpcError, RpcOutpoint, RpcResult, RpcScriptClass, RpcScriptPublicKey, RpcTransaction, RpcTransactionInput,
+    RpcTransactionInputVerboseData, RpcTransactionOutput, RpcTransactionOutputVerboseData, RpcTransactionVerboseData, RpcUtxoEntry,
+};
+use addresses::Prefix;
+use consensus_core::{
+    hashing,
+    mass::MassCalculator,
+    script::{extract_script_pub_key_address, ScriptClass},
+    tx::{ScriptPublicKey, Transaction, TransactionInput, TransactionOutpoint, TransactionOutput, UtxoEntry},
+};
+
+// ----------------------------------------------------------------------------
+// consensus_core to rpc_core
+// ----------------------------------------------------------------------------
+
+impl RpcTransaction {
+    /// Converts a transaction not yet known to be part of a block, so the block hash and time
+    /// of its verbose data are left to their default values.
+    ///
+    /// The addresses paid by the outputs are encoded with `prefix`.
+    pub fn from_transaction(item: &Transaction, prefix: Prefix) -> Self {
+        Self {
+            version: item.version.into(),
+            inputs: item.inputs.iter().map(|x| x.as_ref().into()).collect(),
+            outputs: item.outputs.iter().map(|x| RpcTransactionOutput::from_output(x, prefix)).collect(),
+            lock_time: item.lock_time,
+            subnetwork_id: item.subnetwork_id.clone(),
+            gas: item.gas,
+            payload: (&item.payload).into(),
+            verbose_data: item.into(),
+        }
+    }
+}
+
+impl From<&Transaction> for RpcTransactionVerboseData {
+    fn from(item: &Transaction) -> Self {
+        Self {
+            transaction_id: item.id(),
+            hash: hashing::tx::hash(item),
+            mass: MassCalculator::default().calc_tx_mass(item),
+            block_hash: Default::default(),
+            block_time: 0,
+        }
+    }
+}
+
+impl From<&TransactionInput> for RpcTransactionInput {
+    fn from(item: &TransactionInput) -> Self {
+        Self {
+            previous_outpoint: (&item.previous_outpoint).into(),
+            signature_script: (&item.signature_script).into(),
+            sequence: item.sequence,
+            sig_op_count: item.sig_op_count.into(),
+            verbose_data: Some(RpcTransactionInputVerboseData {}),
+        }
+    }
+}
+
+impl RpcTransactionOutput {
+    /// Converts an output, the address it pays to being encoded with `prefix` if its script is standard.
+    pub fn from_output(item: &TransactionOutput, prefix: Prefix) -> Self {
+        Self {
+            amount: item.value,
+            script_public_key: item.script_public_key.as_ref().into(),
+            verbose_data: RpcTransactionOutputVerboseData {
+                script_public_key_type: ScriptClass::from_script(&item.script_public_key).into(),
+                script_public_key_address: extract_script_pub_key_address(&item.script_public_key, prefix).ok(),
+            },
+        }
+    }
+}
+
+impl From<&TransactionOutpoint> for RpcOutpoint {
+    fn from(item: &TransactionOutpoint) -> Self {
+        Self { transaction_id: item.transaction_id, index: item.index }
+    }
+}
+
+impl From<&UtxoEntry> for RpcUtxoEntry {
+    fn from(item: &UtxoEntry) -> Self {
+        Self {
+            amount: item.amount,
+            script_public_key: item.script_public_key.as_ref().into(),
+            block_daa_score: item.block_daa_score,
+            is_coinbase: item.is_coinbase,
+        }
+    }
+}
+
+impl From<&ScriptPublicKey> for RpcScriptPublicKey {
+    fn from(item: &ScriptPublicKey) -> Self {
+        Self { script_public_key: (&item.script).into(), version: item.version }
+    }
+}
+
+impl From<ScriptClass> for RpcScriptClass {
+    fn from(item: ScriptClass) -> Self {
+        match item {
+            ScriptClass::NonStandard => RpcScriptClass::NonStandardTy,
+            ScriptClass::PubKey => RpcScriptClass::PubKeyTy,
+            ScriptClass::PubKeyECDSA => RpcScriptClass::PubKeyECDSATy,
+            ScriptClass::ScriptHash => RpcScriptClass::ScriptHashTy,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// rpc_core to consensus_core
+// ----------------------------------------------------------------------------
+
+/// The fee and the UTXO entries of the inputs are not part of the RPC model, so they are
+/// left unset and must be populated by consensus.
+impl TryFrom<&RpcTransaction> for Transaction {
+    type Error = RpcError;
+    fn try_from(item: &RpcTransaction) -> RpcResult<Self> {
+        Ok(Transaction::new(
+            item.version.try_into()?,
+            item.inputs.iter().map(|x| Ok(Arc::new(x.try_into()?))).collect::<RpcResult<Vec<_>>>()?,
+            item.outputs.iter().map(|x| Arc::new(x.into())).collect(),
+            item.lock_time,
+            item.subnetwork_id.clone(),
+            item.gas,
+            item.payload.as_ref().clone(),
+            0,
+        ))
+    }
+}
+
+impl TryFrom<&RpcTransactionInput> for TransactionInput {
+    type Error = RpcError;
+    fn try_from(item: &RpcTransactionInput) -> RpcResult<Self> {
+        Ok(Self::new(
+            (&item.previous_outpoint).into(),
+            item.signature_script.as_ref().clone(),
+            item.sequence,
+            item.sig_op_count.try_into()?,
+            None,
+        ))
+    }
+}
+
+impl From<&RpcTransactionOutput> for TransactionOutput {
+    fn from(item: &RpcTransactionOutput) -> Self {
+        Self::new(item.amount, Arc::new((&item.script_public_key).into()))
+    }
+}
+
+impl From<&RpcOutpoint> for TransactionOutpoint {
+    fn from(item: &RpcOutpoint) -> Self {
+        Self::new(item.transaction_id, item.index)
+    }
+}
+
+impl From<&RpcUtxoEntry> for UtxoEntry {
+    fn from(item: &RpcUtxoEntry) -> Self {
+        Self::new(item.amount, Arc::new((&item.script_public_key).into()), item.block_daa_score, item.is_coinbase)
+    }
+}
+
+impl From<&RpcScriptPublicKey> for ScriptPublicKey {
+    fn from(item: &RpcScriptPublicKey) -> Self {
+        Self::new(item.script_public_key.as_ref().clone(), item.version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_core::subnets::SUBNETWORK_ID_NATIVE;
+    use hashes::Hash;
+
+    #[test]
+    fn test_output_verbose_data() {
+        let p2pk = TransactionOutput::new(1, Arc::new(ScriptPublicKey::new([&[0x20], &[1u8; 32][..], &[0xac]].concat(), 0)));
+        let p2sh = TransactionOutput::new(1, Arc::new(ScriptPublicKey::new([&[0xaa, 0x20], &[1u8; 32][..], &[0x87]].concat(), 0)));
+        let non_standard = TransactionOutput::new(1, Arc::new(ScriptPublicKey::new(vec![0x51], 0)));
+
+        let verbose_data = RpcTransactionOutput::from_output(&p2pk, Prefix::Mainnet).verbose_data;
+        assert!(matches!(verbose_data.script_public_key_type, RpcScriptClass::PubKeyTy));
+        let address = verbose_data.script_public_key_address.unwrap();
+        assert_eq!((address.prefix, address.payload), (Prefix::Mainnet, vec![1u8; 32]));
+
+        let verbose_data = RpcTransactionOutput::from_output(&p2sh, Prefix::Testnet).verbose_data;
+        assert!(matches!(verbose_data.script_public_key_type, RpcScriptClass::ScriptHashTy));
+        assert_eq!(verbose_data.script_public_key_address.unwrap().prefix, Prefix::Testnet);
+
+        let verbose_data = RpcTransactionOutput::from_output(&non_standard, Prefix::Mainnet).verbose_data;
+        assert!(matches!(verbose_data.script_public_key_type, RpcScriptClass::NonStandardTy));
+        assert!(verbose_data.script_public_key_address.is_none());
+    }
+
+    #[test]
+    fn test_transaction_round_trip() {
+        let script_public_key = Arc::new(ScriptPublicKey::new([&[0x20], &[7u8; 32][..], &[0xac]].concat(), 0));
+        let outpoint = TransactionOutpoint::new(Hash::from_u64_word(3), 1);
+        let entry = UtxoEntry::new(10_000, script_public_key.clone(), 12, false);
+        let tx = Transaction::new(
+            0,
+            vec![Arc::new(TransactionInput::new(outpoint, vec![1, 2, 3], u64::MAX, 1, Some(entry.clone())))],
+            vec![Arc::new(TransactionOutput::new(9_000, script_public_key))],
+            0,
+            SUBNETWORK_ID_NATIVE,
+            0,
+            vec![4, 5],
+            1_000,
+        );
+
+        let rpc_tx = RpcTransaction::from_transaction(&tx, Prefix::Simnet);
+        assert_eq!(rpc_tx.verbose_data.transaction_id, tx.id());
+        assert_eq!(rpc_tx.verbose_data.hash, hashing::tx::hash(&tx));
+        assert_eq!(rpc_tx.verbose_data.mass, MassCalculator::default().calc_tx_mass(&tx));
+        assert!(rpc_tx.verbose_data.mass > 0);
+        assert!(matches!(rpc_tx.outputs[0].verbose_data.script_public_key_type, RpcScriptClass::PubKeyTy));
+
+        let converted = Transaction::try_from(&rpc_tx).unwrap();
+        assert_eq!(converted.id(), tx.id());
+        assert_eq!(hashing::tx::hash(&converted), hashing::tx::hash(&tx));
+        assert_eq!(UtxoEntry::from(&RpcUtxoEntry::from(&entry)), entry);
+    }
+}
@@ -21,12 +21,27 @@ pub enum RpcError {
     #[error("Invalid script class: {0}")]
     InvalidRpcScriptClass(String),
 
+    #[error("Address error: {0}")]
+    AddressError(#[from] addresses::AddressError),
+
+    #[error("{0}")]
+    NetworkTypeError(#[from] consensus_core::networktype::NetworkTypeError),
+
     #[error("Missing required field {0}.{1}")]
     MissingRpcFieldError(String, String),
 
     #[error("Feature not supported")]
     UnsupportedFeature,
 
+    #[error("Rate limit exceeded")]
+    RateLimitExceeded,
+
+    #[error("Operation {0} is not authorized")]
+    Unauthorized(String),
+
+    #[error("Consensus error: {0}")]
+    ConsensusError(#[from] consensus_core::errors::ConsensusError),
+
     #[error("{0}")]
     General(String),
 }
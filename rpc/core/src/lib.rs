@@ -1,6 +1,7 @@
 pub mod api;
 pub mod convert;
 pub mod errors;
+pub mod metrics;
 pub mod model;
 pub mod notify;
 pub mod server;
@@ -8,12 +9,14 @@ pub mod stubs;
 
 pub mod prelude {
     pub use super::api::notifications::*;
+    pub use super::model::address::*;
     pub use super::model::block::*;
     pub use super::model::blue_work::*;
     pub use super::model::hash::*;
     pub use super::model::header::*;
     pub use super::model::hex_data::*;
     pub use super::model::message::*;
+    pub use super::model::metrics::*;
     pub use super::model::script_class::*;
     pub use super::model::subnets::*;
     pub use super::model::tx::*;
@@ -22,12 +25,14 @@ pub mod prelude {
 pub use api::notifications::*;
 pub use convert::*;
 pub use errors::*;
+pub use model::address::*;
 pub use model::block::*;
 pub use model::blue_work::*;
 pub use model::hash::*;
 pub use model::header::*;
 pub use model::hex_data::*;
 pub use model::message::*;
+pub use model::metrics::*;
 pub use model::script_class::*;
 pub use model::subnets::*;
 pub use model::tx::*;
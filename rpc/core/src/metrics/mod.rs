@@ -0,0 +1,227 @@
+//! Metrics of the RPC layer
+//!
+//! A [`Metrics`] registry is shared by all the components of an RPC stack. Components update
+//! their own counters and gauges while notifiers are registered once and sampled when a
+//! snapshot is taken.
+
+use crate::{
+    api::ops::{RpcApiOps, RPC_API_OPS_ARRAY, RPC_API_OPS_COUNT},
+    notify::notifier::Notifier,
+    GetMetricsResponse, RpcNotificationMetrics, RpcOpMetrics, LATENCY_BUCKETS_MICROS,
+};
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub mod prometheus;
+
+/// A monotonically increasing counter
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    pub fn add(&self, value: u64) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A value that can go up and down
+#[derive(Debug, Default)]
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn dec(&self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A latency histogram with buckets bounded by [`LATENCY_BUCKETS_MICROS`]
+#[derive(Debug, Default)]
+pub struct Histogram {
+    /// Non-cumulative counts, the last bucket collecting all values above the highest bound
+    buckets: [Counter; LATENCY_BUCKETS_MICROS.len() + 1],
+    sum_micros: Counter,
+}
+
+impl Histogram {
+    pub fn observe(&self, elapsed: Duration) {
+        let micros = elapsed.as_micros() as u64;
+        let index = LATENCY_BUCKETS_MICROS.iter().position(|bound| micros <= *bound).unwrap_or(LATENCY_BUCKETS_MICROS.len());
+        self.buckets[index].inc();
+        self.sum_micros.add(micros);
+    }
+
+    /// Returns the cumulative counts of the bounded buckets
+    pub fn cumulative_buckets(&self) -> Vec<u64> {
+        self.buckets[..LATENCY_BUCKETS_MICROS.len()]
+            .iter()
+            .scan(0, |total, bucket| {
+                *total += bucket.get();
+                Some(*total)
+            })
+            .collect()
+    }
+
+    pub fn sum_micros(&self) -> u64 {
+        self.sum_micros.get()
+    }
+}
+
+/// Call metrics of an RPC operation
+#[derive(Debug, Default)]
+pub struct OpMetrics {
+    pub calls: Counter,
+    pub errors: Counter,
+    pub latency: Histogram,
+}
+
+/// The metrics registry of an RPC stack
+#[derive(Debug)]
+pub struct Metrics {
+    ops: Vec<OpMetrics>,
+
+    /// Active gRPC connections of a server
+    active_connections: Gauge,
+
+    /// Calls of a client waiting for a response
+    pending_calls: Gauge,
+
+    notifiers: Mutex<Vec<(String, Arc<Notifier>)>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            ops: (0..RPC_API_OPS_COUNT).map(|_| OpMetrics::default()).collect(),
+            active_connections: Gauge::default(),
+            pending_calls: Gauge::default(),
+            notifiers: Mutex::new(vec![]),
+        }
+    }
+
+    /// Records a call of `op` that took `elapsed` to complete.
+    pub fn record_call(&self, op: RpcApiOps, elapsed: Duration, is_error: bool) {
+        let metrics = self.op(op);
+        metrics.calls.inc();
+        if is_error {
+            metrics.errors.inc();
+        }
+        metrics.latency.observe(elapsed);
+    }
+
+    pub fn op(&self, op: RpcApiOps) -> &OpMetrics {
+        &self.ops[op as usize]
+    }
+
+    pub fn active_connections(&self) -> &Gauge {
+        &self.active_connections
+    }
+
+    pub fn pending_calls(&self) -> &Gauge {
+        &self.pending_calls
+    }
+
+    /// Registers a notifier whose dispatching statistics get reported under `name`.
+    pub fn register_notifier(&self, name: &str, notifier: Arc<Notifier>) {
+        self.notifiers.lock().unwrap().push((name.to_string(), notifier));
+    }
+
+    pub fn snapshot(&self) -> GetMetricsResponse {
+        let ops = RPC_API_OPS_ARRAY
+            .iter()
+            .zip(self.ops.iter())
+            .filter(|(_, metrics)| metrics.calls.get() > 0)
+            .map(|(op, metrics)| RpcOpMetrics {
+                op: format!("{:?}", op),
+                calls: metrics.calls.get(),
+                errors: metrics.errors.get(),
+                latency_sum_micros: metrics.latency.sum_micros(),
+                latency_buckets: metrics.latency.cumulative_buckets(),
+            })
+            .collect();
+
+        let notifications = self
+            .notifiers
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|(name, notifier)| {
+                notifier.dispatcher_stats().into_iter().map(|stats| RpcNotificationMetrics {
+                    notifier: name.clone(),
+                    event: format!("{:?}", stats.event),
+                    notifications: stats.notifications,
+                    queue_depth: stats.queue_depth as u64,
+                })
+            })
+            .collect();
+
+        GetMetricsResponse {
+            server_time: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64,
+            active_connections: self.active_connections.get().max(0) as u64,
+            pending_calls: self.pending_calls.get().max(0) as u64,
+            ops,
+            notifications,
+        }
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram() {
+        let histogram = Histogram::default();
+        histogram.observe(Duration::from_micros(50));
+        histogram.observe(Duration::from_micros(700));
+        histogram.observe(Duration::from_secs(10));
+        assert_eq!(histogram.cumulative_buckets(), vec![1, 1, 2, 2, 2, 2, 2, 2, 2, 2]);
+        assert_eq!(histogram.sum_micros(), 10_000_750);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let metrics = Metrics::new();
+        metrics.record_call(RpcApiOps::GetBlock, Duration::from_millis(2), false);
+        metrics.record_call(RpcApiOps::GetBlock, Duration::from_millis(3), true);
+        metrics.record_call(RpcApiOps::GetInfo, Duration::from_millis(1), false);
+        metrics.active_connections().inc();
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.active_connections, 1);
+        assert_eq!(snapshot.ops.len(), 2);
+        let get_block = snapshot.ops.iter().find(|x| x.op == "GetBlock").unwrap();
+        assert_eq!((get_block.calls, get_block.errors, get_block.latency_sum_micros), (2, 1, 5_000));
+    }
+}
@@ -0,0 +1,92 @@
+//! Encoding of RPC metrics in the Prometheus text exposition format
+
+use crate::{GetMetricsResponse, LATENCY_BUCKETS_MICROS};
+use std::fmt::Write;
+
+/// Content type of the Prometheus text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Encodes a metrics snapshot in the Prometheus text exposition format.
+pub fn encode(metrics: &GetMetricsResponse) -> String {
+    let mut out = String::new();
+    // Writing into a String cannot fail
+    encode_into(&mut out, metrics).unwrap();
+    out
+}
+
+fn encode_into(out: &mut String, metrics: &GetMetricsResponse) -> std::fmt::Result {
+    header(out, "kaspa_rpc_calls_total", "counter", "Number of RPC calls by operation")?;
+    for op in metrics.ops.iter() {
+        writeln!(out, "kaspa_rpc_calls_total{{op=\"{}\"}} {}", op.op, op.calls)?;
+    }
+
+    header(out, "kaspa_rpc_errors_total", "counter", "Number of RPC calls ending with an error by operation")?;
+    for op in metrics.ops.iter() {
+        writeln!(out, "kaspa_rpc_errors_total{{op=\"{}\"}} {}", op.op, op.errors)?;
+    }
+
+    header(out, "kaspa_rpc_call_duration_seconds", "histogram", "Latency of RPC calls by operation")?;
+    for op in metrics.ops.iter() {
+        for (bound, count) in LATENCY_BUCKETS_MICROS.iter().zip(op.latency_buckets.iter()) {
+            writeln!(out, "kaspa_rpc_call_duration_seconds_bucket{{op=\"{}\",le=\"{}\"}} {}", op.op, seconds(*bound), count)?;
+        }
+        writeln!(out, "kaspa_rpc_call_duration_seconds_bucket{{op=\"{}\",le=\"+Inf\"}} {}", op.op, op.calls)?;
+        writeln!(out, "kaspa_rpc_call_duration_seconds_sum{{op=\"{}\"}} {}", op.op, seconds(op.latency_sum_micros))?;
+        writeln!(out, "kaspa_rpc_call_duration_seconds_count{{op=\"{}\"}} {}", op.op, op.calls)?;
+    }
+
+    header(out, "kaspa_rpc_active_connections", "gauge", "Number of active gRPC connections")?;
+    writeln!(out, "kaspa_rpc_active_connections {}", metrics.active_connections)?;
+
+    header(out, "kaspa_rpc_pending_calls", "gauge", "Number of client calls waiting for a response")?;
+    writeln!(out, "kaspa_rpc_pending_calls {}", metrics.pending_calls)?;
+
+    header(out, "kaspa_rpc_notifications_total", "counter", "Number of notifications received by notifier and event type")?;
+    for n in metrics.notifications.iter() {
+        writeln!(out, "kaspa_rpc_notifications_total{{notifier=\"{}\",event=\"{}\"}} {}", n.notifier, n.event, n.notifications)?;
+    }
+
+    header(out, "kaspa_rpc_notification_queue_depth", "gauge", "Number of messages waiting in a notifier dispatcher")?;
+    for n in metrics.notifications.iter() {
+        writeln!(out, "kaspa_rpc_notification_queue_depth{{notifier=\"{}\",event=\"{}\"}} {}", n.notifier, n.event, n.queue_depth)?;
+    }
+
+    Ok(())
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) -> std::fmt::Result {
+    writeln!(out, "# HELP {} {}", name, help)?;
+    writeln!(out, "# TYPE {} {}", name, kind)
+}
+
+fn seconds(micros: u64) -> f64 {
+    micros as f64 / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RpcOpMetrics;
+
+    #[test]
+    fn test_encode() {
+        let metrics = GetMetricsResponse {
+            active_connections: 3,
+            ops: vec![RpcOpMetrics {
+                op: "GetInfo".to_string(),
+                calls: 2,
+                errors: 1,
+                latency_sum_micros: 1_500,
+                latency_buckets: vec![0, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+            }],
+            ..Default::default()
+        };
+        let text = encode(&metrics);
+        assert!(text.contains("kaspa_rpc_calls_total{op=\"GetInfo\"} 2\n"));
+        assert!(text.contains("kaspa_rpc_errors_total{op=\"GetInfo\"} 1\n"));
+        assert!(text.contains("kaspa_rpc_call_duration_seconds_bucket{op=\"GetInfo\",le=\"0.0005\"} 1\n"));
+        assert!(text.contains("kaspa_rpc_call_duration_seconds_bucket{op=\"GetInfo\",le=\"+Inf\"} 2\n"));
+        assert!(text.contains("kaspa_rpc_call_duration_seconds_sum{op=\"GetInfo\"} 0.0015\n"));
+        assert!(text.contains("# TYPE kaspa_rpc_active_connections gauge\nkaspa_rpc_active_connections 3\n"));
+    }
+}
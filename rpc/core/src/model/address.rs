@@ -0,0 +1 @@
+pub type RpcAddress = addresses::Address;
@@ -1,7 +1,66 @@
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
-use crate::{api::ops::SubscribeCommand, RpcBlock, RpcHash};
+use crate::{api::ops::SubscribeCommand, stubs::NetworkType, RpcAddress, RpcBlock, RpcHash, RpcNotificationMetrics, RpcOpMetrics};
+
+/// PingRequest checks that the node is alive and responsive.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PingRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PingResponse {}
+
+/// GetCurrentNetworkRequest requests the network the node is currently running against.
+///
+/// Possible networks are: Mainnet, Testnet, Simnet, Devnet
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCurrentNetworkRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCurrentNetworkResponse {
+    pub current_network: NetworkType,
+}
+
+/// SubmitBlockRequest requests to submit a block into the DAG.
+/// Blocks are generally expected to have been generated using the getBlockTemplate call.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitBlockRequest {
+    pub block: RpcBlock,
+    #[serde(alias = "allowNonDAABlocks")]
+    pub allow_non_daa_blocks: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SubmitBlockRejectReason {
+    BlockInvalid = 1,
+    IsInIBD = 2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SubmitBlockReport {
+    Success,
+    // The BorshSchema derive copies the variant into a struct that never reads the field
+    Reject(#[allow(dead_code)] SubmitBlockRejectReason),
+}
+
+impl SubmitBlockReport {
+    pub fn is_success(&self) -> bool {
+        *self == SubmitBlockReport::Success
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitBlockResponse {
+    pub report: SubmitBlockReport,
+}
 
 /// GetBlockRequest requests information about a specific block
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
@@ -45,6 +104,21 @@ pub struct BlockAddedNotification {
     pub block: RpcBlock,
 }
 
+/// NotifyUtxosChangedRequest registers this connection for utxoChanged notifications
+/// for the given addresses.
+///
+/// An empty address list starts/stops all updates.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyUtxosChangedRequest {
+    pub addresses: Vec<RpcAddress>,
+    pub command: SubscribeCommand,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotifyUtxosChangedResponse {}
+
 /// GetInfoRequest returns info about the node.
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
@@ -60,3 +134,73 @@ pub struct GetInfoResponse {
     pub is_synced: bool,
     pub has_notify_command: bool,
 }
+
+/// GetSelectedTipHashRequest requests the hash of the current virtual's selected parent.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSelectedTipHashRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetSelectedTipHashResponse {
+    pub selected_tip_hash: RpcHash,
+}
+
+/// ShutdownRequest requests the node to shut down gracefully.
+///
+/// The response is sent before the node starts shutting down.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShutdownRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ShutdownResponse {}
+
+/// EstimateNetworkHashesPerSecondRequest estimates the hashrate of the network over the selected chain
+/// blocks ending at `start_hash`, or at the virtual selected parent if `None`.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimateNetworkHashesPerSecondRequest {
+    /// Number of selected chain blocks to estimate over
+    pub window_size: u32,
+    pub start_hash: Option<RpcHash>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EstimateNetworkHashesPerSecondResponse {
+    pub network_hashes_per_second: u64,
+}
+
+/// GetCoinSupplyRequest requests the circulating and the maximal supplies of the network, in sompi.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCoinSupplyRequest {}
+
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCoinSupplyResponse {
+    /// Total supply of the subsidy schedule. The actual maximal supply is expected to be up to 5% lower
+    /// but cannot be measured exactly.
+    pub max_sompi: u64,
+
+    /// Total amount of the virtual UTXO set
+    pub circulating_sompi: u64,
+}
+
+/// GetMetricsRequest returns the metrics of the RPC layer of the node.
+#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMetricsRequest {}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMetricsResponse {
+    /// Unix time in milliseconds at which the metrics were collected
+    pub server_time: u64,
+    pub active_connections: u64,
+    pub pending_calls: u64,
+    pub ops: Vec<RpcOpMetrics>,
+    pub notifications: Vec<RpcNotificationMetrics>,
+}
@@ -0,0 +1,32 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+/// Upper bounds, in microseconds, of the buckets of the RPC call latency histograms
+pub const LATENCY_BUCKETS_MICROS: [u64; 10] = [100, 500, 1_000, 5_000, 10_000, 50_000, 100_000, 500_000, 1_000_000, 5_000_000];
+
+/// Metrics of an RPC operation
+#[derive(Clone, Debug, Default, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcOpMetrics {
+    pub op: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub latency_sum_micros: u64,
+
+    /// Cumulative count of calls by latency bucket, as bounded by [`LATENCY_BUCKETS_MICROS`]
+    pub latency_buckets: Vec<u64>,
+}
+
+/// Metrics of a notifier for a single event type
+#[derive(Clone, Debug, Default, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcNotificationMetrics {
+    pub notifier: String,
+    pub event: String,
+
+    /// Number of notifications received by the notifier since it was created
+    pub notifications: u64,
+
+    /// Number of messages waiting in the dispatcher queue
+    pub queue_depth: u64,
+}
@@ -1,18 +1,22 @@
+pub mod address;
 pub mod block;
 pub mod blue_work;
 pub mod hash;
 pub mod header;
 pub mod hex_data;
 pub mod message;
+pub mod metrics;
 pub mod script_class;
 pub mod subnets;
 pub mod tx;
 
+pub use address::*;
 pub use block::*;
 pub use blue_work::*;
 pub use hash::*;
 pub use header::*;
 pub use hex_data::*;
 pub use message::*;
+pub use metrics::*;
 pub use subnets::*;
 pub use tx::*;
@@ -2,7 +2,7 @@ use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use consensus_core::tx::TransactionId;
 use serde::{Deserialize, Serialize};
 
-use crate::prelude::{RpcHash, RpcHexData, RpcScriptClass, RpcSubnetworkId};
+use crate::prelude::{RpcAddress, RpcHash, RpcHexData, RpcScriptClass, RpcSubnetworkId};
 
 pub type RpcTransactionId = TransactionId;
 
@@ -78,12 +78,7 @@ pub struct RpcTransactionInputVerboseData {}
 #[serde(rename_all = "camelCase")]
 pub struct RpcTransactionOutputVerboseData {
     pub script_public_key_type: RpcScriptClass,
-    pub script_public_key_address: String, // FIXME ? (investigate /crpyto/addresses/src/lib.rs)
-}
 
-// #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-// #[serde(rename_all = "camelCase")]
-// pub struct RpcAddress {
-//     pub prefix: u32,
-//     pub public_key: ScriptKey,
-// }
+    /// Address the output pays to, if its script is of a standard form
+    pub script_public_key_address: Option<RpcAddress>,
+}
@@ -3,10 +3,11 @@ use async_std::stream::StreamExt;
 use async_trait::async_trait;
 use core::fmt::Debug;
 use futures::{
-    future::FutureExt, // for `.fuse()`
+    future::{Fuse, FutureExt}, // for `.fuse()`
     pin_mut,
-    select,
+    select_biased,
 };
+use kaspa_core::{debug, error, trace};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 extern crate derive_more;
@@ -24,6 +25,7 @@ pub type CollectorNotificationReceiver<T> = Receiver<Arc<T>>;
 pub trait Collector: Send + Sync + Debug {
     fn start(self: Arc<Self>, notifier: Arc<Notifier>);
     async fn stop(self: Arc<Self>) -> Result<()>;
+    fn is_running(&self) -> bool;
 }
 
 pub type DynCollector = Arc<dyn Collector>;
@@ -42,12 +44,16 @@ impl<T> From<Arc<T>> for ArcConvert<T> {
 /// A notifications collector that receives [`T`] from a channel,
 /// converts it into a [Notification] and sends it to a its
 /// [Notifier].
+///
+/// An optional priority channel can be provided. Its notifications
+/// are always collected before those of the regular channel.
 #[derive(Debug)]
 pub struct CollectorFrom<T>
 where
     T: Send + Sync + 'static + Sized,
 {
     recv_channel: CollectorNotificationReceiver<T>,
+    priority_recv_channel: Option<CollectorNotificationReceiver<T>>,
     collect_shutdown: Arc<DuplexTrigger>,
     collect_is_running: Arc<AtomicBool>,
 }
@@ -58,7 +64,19 @@ where
     ArcConvert<T>: Into<Arc<Notification>>,
 {
     pub fn new(recv_channel: CollectorNotificationReceiver<T>) -> Self {
-        Self { recv_channel, collect_shutdown: Arc::new(DuplexTrigger::new()), collect_is_running: Arc::new(AtomicBool::new(false)) }
+        Self {
+            recv_channel,
+            priority_recv_channel: None,
+            collect_shutdown: Arc::new(DuplexTrigger::new()),
+            collect_is_running: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn with_priority(
+        recv_channel: CollectorNotificationReceiver<T>,
+        priority_recv_channel: CollectorNotificationReceiver<T>,
+    ) -> Self {
+        Self { priority_recv_channel: Some(priority_recv_channel), ..Self::new(recv_channel) }
     }
 
     fn start_collect(&self, notifier: Arc<Notifier>) {
@@ -71,10 +89,11 @@ where
         let collect_shutdown = self.collect_shutdown.clone();
         let collect_is_running = self.collect_is_running.clone();
         let recv_channel = self.recv_channel.clone();
+        let priority_recv_channel = self.priority_recv_channel.clone();
         collect_is_running.store(true, Ordering::SeqCst);
 
         workflow_core::task::spawn(async move {
-            println!("[Collector] collect_task start");
+            debug!("collect_task start");
 
             let shutdown = collect_shutdown.request.listener.clone().fuse();
             pin_mut!(shutdown);
@@ -82,32 +101,54 @@ where
             let notifications = recv_channel.fuse();
             pin_mut!(notifications);
 
+            let mut priority_notifications = priority_recv_channel.map(|x| x.fuse());
+
+            fn collect<T>(notifier: &Arc<Notifier>, msg: Arc<T>)
+            where
+                ArcConvert<T>: Into<Arc<Notification>>,
+            {
+                let rpc_notification: Arc<Notification> = ArcConvert::from(msg).into();
+                trace!("collect_task received {:?}", crate::NotificationType::from(&*rpc_notification));
+                match notifier.clone().notifiy(rpc_notification) {
+                    Ok(_) => (),
+                    Err(err) => {
+                        error!("notification sender error: {:?}", err);
+                    }
+                }
+            }
+
             loop {
-                select! {
+                let mut priority_is_closed = false;
+                let mut priority_notification = match priority_notifications {
+                    Some(ref mut priority_notifications) => priority_notifications.next().fuse(),
+                    None => Fuse::terminated(),
+                };
+                select_biased! {
                     _ = shutdown => { break; }
+                    notification = priority_notification => {
+                        match notification {
+                            Some(msg) => collect(&notifier, msg),
+                            None => priority_is_closed = true,
+                        }
+                    }
                     notification = notifications.next().fuse() => {
                         match notification {
                             Some(msg) => {
-                                let rpc_notification: Arc<Notification> = ArcConvert::from(msg.clone()).into();
-                                //let notification_type: crate::NotificationType = (&*rpc_notification).into();
-                                //println!("[Collector] collect_task received {:?}", notification_type);
-                                match notifier.clone().notifiy(rpc_notification) {
-                                    Ok(_) => (),
-                                    Err(err) => {
-                                        println!("[Collector] notification sender error: {:?}", err);
-                                    },
-                                }
+                                collect(&notifier, msg);
                             },
                             None => {
-                                println!("[Collector] notifications returned None. This should never happen");
+                                error!("notifications returned None. This should never happen");
                             }
                         }
                     }
                 }
+                if priority_is_closed {
+                    priority_notifications = None;
+                }
             }
             collect_is_running.store(false, Ordering::SeqCst);
             collect_shutdown.response.trigger.trigger();
-            println!("[Collector] collect_task end");
+            debug!("collect_task end");
         });
     }
 
@@ -133,6 +174,10 @@ where
     async fn stop(self: Arc<Self>) -> Result<()> {
         self.stop_collect().await
     }
+
+    fn is_running(&self) -> bool {
+        self.collect_is_running.load(Ordering::SeqCst)
+    }
 }
 
 /// A rpc_core notification collector providing a simple pass-through.
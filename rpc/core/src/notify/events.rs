@@ -16,6 +16,14 @@ pub enum EventType {
     NewBlockTemplate,
 }
 
+impl EventType {
+    /// Critical events are delivered through the priority path of the notification system,
+    /// bypassing any backlog of regular notifications.
+    pub fn is_critical(&self) -> bool {
+        matches!(self, EventType::FinalityConflicts | EventType::FinalityConflictResolved)
+    }
+}
+
 // TODO: write a macro or use an external crate to get this
 pub(crate) const EVENT_COUNT: usize = 9;
 
@@ -1,15 +1,123 @@
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Formatter};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::channel::NotificationChannel;
-use super::events::{EventArray, EventType};
+use super::events::{EventArray, EventType, EVENT_TYPE_ARRAY};
 use super::result::Result;
 use super::utxo_address_map::RpcUtxoAddressMap;
-use crate::stubs::RpcUtxoAddress;
+use crate::RpcAddress;
 use crate::{Notification, NotificationReceiver, NotificationSender, NotificationType};
+use futures::{future::FutureExt, select_biased};
 
-// TODO: consider the use of a newtype instead
-pub type ListenerID = u64;
+/// Identifier of a [`super::notifier::Notifier`] listener
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct ListenerID(u64);
+
+impl ListenerID {
+    pub fn new(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// Generates a random id
+    pub(crate) fn new_random() -> Self {
+        Self(u64::from_le_bytes(rand::random::<[u8; 8]>()))
+    }
+}
+
+impl From<u64> for ListenerID {
+    fn from(item: u64) -> Self {
+        Self(item)
+    }
+}
+
+impl From<ListenerID> for u64 {
+    fn from(item: ListenerID) -> Self {
+        item.0
+    }
+}
+
+impl Display for ListenerID {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Transport through which the notifications of a listener are delivered
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ListenerTransport {
+    /// In-process consumer (ie. another service or a client-side notifier)
+    Internal,
+
+    /// Remote gRPC connection
+    Grpc,
+}
+
+/// Descriptive data about who a [Listener] is
+#[derive(Clone, Debug)]
+pub struct ListenerMetadata {
+    /// Free label identifying the owner of the listener
+    pub owner: String,
+    pub transport: ListenerTransport,
+    pub remote_address: Option<SocketAddr>,
+
+    /// Creation time in milliseconds since the Unix epoch
+    pub created: u64,
+}
+
+impl ListenerMetadata {
+    pub fn new(owner: impl Into<String>, transport: ListenerTransport, remote_address: Option<SocketAddr>) -> Self {
+        let created = SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_millis() as u64).unwrap_or_default();
+        Self { owner: owner.into(), transport, remote_address, created }
+    }
+}
+
+impl Default for ListenerMetadata {
+    fn default() -> Self {
+        Self::new("anonymous", ListenerTransport::Internal, None)
+    }
+}
+
+/// Per-event counters of the notifications processed for a listener
+#[derive(Debug, Default)]
+pub(crate) struct ListenerCounters {
+    delivered: EventArray<AtomicU64>,
+    dropped: EventArray<AtomicU64>,
+}
+
+impl ListenerCounters {
+    fn delivered(&self, event: EventType) {
+        self.delivered[event].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn dropped(&self, event: EventType) {
+        self.dropped[event].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Notification statistics of a listener for a given [`EventType`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ListenerEventStats {
+    pub event: EventType,
+
+    /// Number of notifications successfully sent to the listener channel
+    pub delivered: u64,
+
+    /// Number of notifications that could not be sent to the listener channel
+    pub dropped: u64,
+}
+
+/// A snapshot of a listener registered in a [`super::notifier::Notifier`]
+#[derive(Clone, Debug)]
+pub struct ListenerInfo {
+    pub id: ListenerID,
+    pub metadata: ListenerMetadata,
+    pub active_events: Vec<EventType>,
+    pub utxo_address_count: usize,
+    pub stats: Vec<ListenerEventStats>,
+}
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum SendingChangedUtxo {
@@ -30,18 +138,37 @@ pub enum SendingChangedUtxo {
 ///
 /// Any ListenerSenderSide derived from a [Listener] should also be rebuilt
 /// upon relevant mutation by a call to toggle.
+///
+/// When the notifier creates the channels of the listener, notifications of critical
+/// events (see [`EventType::is_critical`]) are sent through a dedicated priority channel
+/// so they are not delayed by a backlog in the regular channel. A channel provided by
+/// the caller receives all notifications, critical ones included.
 #[derive(Debug)]
 pub(crate) struct Listener {
-    id: u64,
+    id: ListenerID,
     channel: NotificationChannel,
+    priority_channel: Option<NotificationChannel>,
     active_event: EventArray<bool>,
     utxo_addresses: RpcUtxoAddressMap,
+    metadata: ListenerMetadata,
+    counters: Arc<ListenerCounters>,
 }
 
 impl Listener {
-    pub(crate) fn new(id: ListenerID, channel: Option<NotificationChannel>) -> Listener {
-        let channel = channel.unwrap_or_default();
-        Self { id, channel, active_event: EventArray::default(), utxo_addresses: RpcUtxoAddressMap::new() }
+    pub(crate) fn new(id: ListenerID, channel: Option<NotificationChannel>, metadata: ListenerMetadata) -> Listener {
+        let (channel, priority_channel) = match channel {
+            Some(channel) => (channel, None),
+            None => (NotificationChannel::default(), Some(NotificationChannel::default())),
+        };
+        Self {
+            id,
+            channel,
+            priority_channel,
+            active_event: EventArray::default(),
+            utxo_addresses: RpcUtxoAddressMap::new(),
+            metadata,
+            counters: Arc::new(ListenerCounters::default()),
+        }
     }
 
     pub(crate) fn id(&self) -> ListenerID {
@@ -53,7 +180,7 @@ impl Listener {
         self.active_event[event]
     }
 
-    fn toggle_utxo_addresses(&mut self, utxo_addresses: &Vec<RpcUtxoAddress>) -> bool {
+    fn toggle_utxo_addresses(&mut self, utxo_addresses: &Vec<RpcAddress>) -> bool {
         let utxo_addresses: RpcUtxoAddressMap = utxo_addresses.into();
         if utxo_addresses != self.utxo_addresses {
             self.utxo_addresses = utxo_addresses;
@@ -83,23 +210,81 @@ impl Listener {
         if !self.is_closed() {
             self.channel.close();
         }
+        if let Some(ref priority_channel) = self.priority_channel {
+            if !priority_channel.is_closed() {
+                priority_channel.close();
+            }
+        }
     }
 
     pub(crate) fn is_closed(&self) -> bool {
         self.channel.is_closed()
     }
+
+    pub(crate) fn info(&self) -> ListenerInfo {
+        ListenerInfo {
+            id: self.id,
+            metadata: self.metadata.clone(),
+            active_events: EVENT_TYPE_ARRAY.into_iter().filter(|event| self.has(*event)).collect(),
+            utxo_address_count: self.utxo_addresses.len(),
+            stats: EVENT_TYPE_ARRAY
+                .into_iter()
+                .map(|event| ListenerEventStats {
+                    event,
+                    delivered: self.counters.delivered[event].load(Ordering::Relaxed),
+                    dropped: self.counters.dropped[event].load(Ordering::Relaxed),
+                })
+                .collect(),
+        }
+    }
 }
 
 /// Contains the receiver side of a listener
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct ListenerReceiverSide {
     pub id: ListenerID,
     pub recv_channel: NotificationReceiver,
+
+    /// Receives notifications of critical events, unless the listener was registered
+    /// with a channel of its own, in which case they go through `recv_channel`
+    pub priority_recv_channel: Option<NotificationReceiver>,
+}
+
+impl ListenerReceiverSide {
+    /// Receive the next notification, giving precedence to the priority channel.
+    pub async fn recv(&self) -> Result<Arc<Notification>> {
+        let priority_recv_channel = match self.priority_recv_channel {
+            Some(ref priority_recv_channel) if !priority_recv_channel.is_closed() => priority_recv_channel,
+            _ => return Ok(self.recv_channel.recv().await?),
+        };
+        if let Ok(notification) = priority_recv_channel.try_recv() {
+            return Ok(notification);
+        }
+        select_biased! {
+            notification = priority_recv_channel.recv().fuse() => Ok(notification?),
+            notification = self.recv_channel.recv().fuse() => Ok(notification?),
+        }
+    }
+
+    pub fn close(&self) {
+        self.recv_channel.close();
+        if let Some(ref priority_recv_channel) = self.priority_recv_channel {
+            priority_recv_channel.close();
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.recv_channel.is_closed()
+    }
 }
 
 impl From<&Listener> for ListenerReceiverSide {
     fn from(item: &Listener) -> Self {
-        Self { id: item.id(), recv_channel: item.channel.receiver() }
+        Self {
+            id: item.id(),
+            recv_channel: item.channel.receiver(),
+            priority_recv_channel: item.priority_channel.as_ref().map(|x| x.receiver()),
+        }
     }
 }
 
@@ -108,17 +293,23 @@ impl From<&Listener> for ListenerReceiverSide {
 pub(crate) struct ListenerSenderSide {
     send_channel: NotificationSender,
     filter: Box<dyn Filter + Send + Sync>,
+    event: EventType,
+    counters: Arc<ListenerCounters>,
 }
 
 impl ListenerSenderSide {
     pub(crate) fn new(listener: &Listener, sending_changed_utxos: SendingChangedUtxo, event: EventType) -> Self {
-        match event {
-            EventType::UtxosChanged if sending_changed_utxos == SendingChangedUtxo::FilteredByAddress => Self {
-                send_channel: listener.channel.sender(),
-                filter: Box::new(FilterUtxoAddress { utxos_addresses: listener.utxo_addresses.clone() }),
-            },
-            _ => Self { send_channel: listener.channel.sender(), filter: Box::new(Unfiltered {}) },
-        }
+        let filter: Box<dyn Filter + Send + Sync> = match event {
+            EventType::UtxosChanged if sending_changed_utxos == SendingChangedUtxo::FilteredByAddress => {
+                Box::new(FilterUtxoAddress { utxos_addresses: listener.utxo_addresses.clone() })
+            }
+            _ => Box::new(Unfiltered {}),
+        };
+        let send_channel = match listener.priority_channel {
+            Some(ref priority_channel) if event.is_critical() => priority_channel.sender(),
+            _ => listener.channel.sender(),
+        };
+        Self { send_channel, filter, event, counters: listener.counters.clone() }
     }
 
     /// Try to send a notification.
@@ -129,9 +320,11 @@ impl ListenerSenderSide {
         if self.filter.filter(notification.clone()) {
             match self.send_channel.try_send(notification) {
                 Ok(_) => {
+                    self.counters.delivered(self.event);
                     return Ok(true);
                 }
                 Err(err) => {
+                    self.counters.dropped(self.event);
                     return Err(err.into());
                 }
             }
@@ -173,3 +366,79 @@ impl InnerFilter for FilterUtxoAddress {
     }
 }
 impl Filter for FilterUtxoAddress {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stubs::{FinalityConflictNotification, UtxosChangedNotification, VirtualDaaScoreChangedNotification};
+
+    #[test]
+    fn test_listener_info() {
+        let metadata = ListenerMetadata::new("test", ListenerTransport::Grpc, Some("127.0.0.1:16110".parse().unwrap()));
+        let mut listener = Listener::new(ListenerID::new(1), None, metadata);
+        assert!(listener.toggle(NotificationType::VirtualDaaScoreChanged, true));
+        assert!(!listener.toggle(NotificationType::VirtualDaaScoreChanged, true));
+
+        let sender_side = ListenerSenderSide::new(&listener, SendingChangedUtxo::All, EventType::VirtualDaaScoreChanged);
+        let notification = Arc::new(Notification::VirtualDaaScoreChanged(VirtualDaaScoreChangedNotification));
+        assert!(sender_side.try_send(notification.clone()).unwrap());
+        listener.close();
+        assert!(sender_side.try_send(notification).is_err());
+
+        let info = listener.info();
+        assert_eq!(info.id, ListenerID::new(1));
+        assert_eq!(info.metadata.owner, "test");
+        assert_eq!(info.metadata.transport, ListenerTransport::Grpc);
+        assert_eq!(info.active_events, vec![EventType::VirtualDaaScoreChanged]);
+        assert_eq!(info.utxo_address_count, 0);
+        let stats = info.stats.iter().find(|x| x.event == EventType::VirtualDaaScoreChanged).unwrap();
+        assert_eq!((stats.delivered, stats.dropped), (1, 1));
+        assert!(info
+            .stats
+            .iter()
+            .filter(|x| x.event != EventType::VirtualDaaScoreChanged)
+            .all(|x| x.delivered == 0 && x.dropped == 0));
+    }
+
+    #[async_std::test]
+    async fn test_listener_priority_channel() {
+        let mut listener = Listener::new(ListenerID::new(1), None, ListenerMetadata::default());
+        listener.toggle(NotificationType::VirtualDaaScoreChanged, true);
+        listener.toggle(NotificationType::FinalityConflicts, true);
+        let regular_sender_side = ListenerSenderSide::new(&listener, SendingChangedUtxo::All, EventType::VirtualDaaScoreChanged);
+        let critical_sender_side = ListenerSenderSide::new(&listener, SendingChangedUtxo::All, EventType::FinalityConflicts);
+
+        // The critical notification is sent last but must be received first
+        let receiver_side: ListenerReceiverSide = (&listener).into();
+        regular_sender_side.try_send(Arc::new(Notification::VirtualDaaScoreChanged(VirtualDaaScoreChangedNotification))).unwrap();
+        critical_sender_side.try_send(Arc::new(Notification::FinalityConflict(FinalityConflictNotification))).unwrap();
+        assert!(matches!(*receiver_side.recv().await.unwrap(), Notification::FinalityConflict(_)));
+        assert!(matches!(*receiver_side.recv().await.unwrap(), Notification::VirtualDaaScoreChanged(_)));
+    }
+
+    #[async_std::test]
+    async fn test_listener_own_channel() {
+        // A listener registered with its own channel receives critical notifications through it
+        let channel = NotificationChannel::default();
+        let mut listener = Listener::new(ListenerID::new(1), Some(channel.clone()), ListenerMetadata::default());
+        listener.toggle(NotificationType::FinalityConflicts, true);
+        let critical_sender_side = ListenerSenderSide::new(&listener, SendingChangedUtxo::All, EventType::FinalityConflicts);
+        critical_sender_side.try_send(Arc::new(Notification::FinalityConflict(FinalityConflictNotification))).unwrap();
+        assert!(matches!(*channel.receiver().recv().await.unwrap(), Notification::FinalityConflict(_)));
+
+        let receiver_side: ListenerReceiverSide = (&listener).into();
+        assert!(receiver_side.priority_recv_channel.is_none());
+    }
+
+    #[test]
+    fn test_utxo_address_filter() {
+        let address = |byte: u8| RpcAddress::new(addresses::Prefix::Mainnet, addresses::Version::PubKey, &[byte; 32]).unwrap();
+        let mut listener = Listener::new(ListenerID::new(1), None, ListenerMetadata::default());
+        listener.toggle(NotificationType::UtxosChanged(vec![address(1), address(2)]), true);
+
+        let sender_side = ListenerSenderSide::new(&listener, SendingChangedUtxo::FilteredByAddress, EventType::UtxosChanged);
+        let notification = |byte| Arc::new(Notification::UtxosChanged(UtxosChangedNotification { utxo_address: address(byte) }));
+        assert!(sender_side.try_send(notification(2)).unwrap());
+        assert!(!sender_side.try_send(notification(3)).unwrap());
+    }
+}
@@ -1,4 +1,7 @@
-use super::listener::{ListenerID, ListenerSenderSide};
+use super::{
+    events::EventType,
+    listener::{ListenerID, ListenerSenderSide},
+};
 use crate::{Notification, NotificationType};
 use std::sync::Arc;
 
@@ -10,6 +13,17 @@ pub(crate) enum DispatchMessage {
     Shutdown,
 }
 
+impl DispatchMessage {
+    /// Control messages and notifications of critical events take
+    /// precedence over regular notifications in a dispatcher.
+    pub(crate) fn is_priority(&self) -> bool {
+        match self {
+            DispatchMessage::Send(ref notification) => EventType::from(notification.as_ref()).is_critical(),
+            _ => true,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) enum SubscribeMessage {
     StartEvent(NotificationType),
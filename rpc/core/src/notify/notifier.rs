@@ -2,7 +2,7 @@ use super::{
     channel::NotificationChannel,
     collector::DynCollector,
     events::{EventArray, EventType, EVENT_TYPE_ARRAY},
-    listener::{Listener, ListenerID, ListenerReceiverSide, ListenerSenderSide, SendingChangedUtxo},
+    listener::{Listener, ListenerID, ListenerInfo, ListenerMetadata, ListenerReceiverSide, ListenerSenderSide, SendingChangedUtxo},
     message::{DispatchMessage, SubscribeMessage},
     result::Result,
     subscriber::{Subscriber, SubscriptionManager},
@@ -11,12 +11,26 @@ use crate::{api::ops::SubscribeCommand, Notification, NotificationType, RpcResul
 use ahash::AHashMap;
 use async_std::channel::{Receiver, Sender};
 use async_trait::async_trait;
+use futures::{future::FutureExt, select_biased};
+use kaspa_core::{debug, error, trace};
 use kaspa_utils::channel::Channel;
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicU64, Ordering},
     Arc, Mutex,
 };
 
+/// Dispatching statistics of a [`Notifier`] for an event type
+#[derive(Clone, Debug)]
+pub struct DispatcherStats {
+    pub event: EventType,
+
+    /// Number of notifications received since the notifier was created
+    pub notifications: u64,
+
+    /// Number of messages waiting in the dispatcher lanes
+    pub queue_depth: usize,
+}
+
 /// A notification sender
 ///
 /// Manage a collection of [Listener] and, for each one, a set of events to be notified.
@@ -36,13 +50,48 @@ impl Notifier {
     }
 
     pub fn register_new_listener(&self, channel: Option<NotificationChannel>) -> ListenerReceiverSide {
-        self.inner.clone().register_new_listener(channel)
+        self.inner.clone().register_new_listener(channel, ListenerMetadata::default())
+    }
+
+    /// Register a new listener described by some metadata.
+    pub fn register_new_listener_with_metadata(
+        &self,
+        channel: Option<NotificationChannel>,
+        metadata: ListenerMetadata,
+    ) -> ListenerReceiverSide {
+        self.inner.clone().register_new_listener(channel, metadata)
+    }
+
+    /// Returns a snapshot of all registered listeners, including their per-event
+    /// delivered and dropped notification counters.
+    pub fn listeners(&self) -> Vec<ListenerInfo> {
+        self.inner.clone().listeners()
+    }
+
+    /// Returns a snapshot of a registered listener.
+    pub fn listener(&self, id: ListenerID) -> Option<ListenerInfo> {
+        self.inner.clone().listener(id)
     }
 
     pub fn unregister_listener(&self, id: ListenerID) -> Result<()> {
         self.inner.clone().unregister_listener(id)
     }
 
+    /// Returns the dispatching statistics of every event type.
+    pub fn dispatcher_stats(&self) -> Vec<DispatcherStats> {
+        self.inner.dispatcher_stats()
+    }
+
+    /// Returns true if all dispatchers are running.
+    pub fn is_running(&self) -> bool {
+        EVENT_TYPE_ARRAY.into_iter().all(|event| self.inner.dispatcher_is_running[event].load(Ordering::SeqCst))
+    }
+
+    /// Returns true if the collector is running, `None` if the notifier has no collector.
+    pub fn collector_is_running(&self) -> Option<bool> {
+        self.inner.collector.as_ref().as_ref().map(|collector| collector.is_running())
+    }
+
     pub fn execute_notify_command(
         self: Arc<Self>,
         id: ListenerID,
@@ -53,7 +102,7 @@ impl Notifier {
     }
 
     pub fn start_notify(&self, id: ListenerID, notification_type: NotificationType) -> Result<()> {
-        println!("[Notifier] start sending to listener {0} notifications of type {1:?}", id, notification_type);
+        debug!("start sending to listener {0} notifications of type {1:?}", id, notification_type);
         self.inner.clone().start_notify(id, notification_type)
     }
 
@@ -62,7 +111,7 @@ impl Notifier {
     }
 
     pub fn stop_notify(&self, id: ListenerID, notification_type: NotificationType) -> Result<()> {
-        println!("[Notifier] stop sending to listener {0} notifications of type {1:?}", id, notification_type);
+        debug!("stop sending to listener {0} notifications of type {1:?}", id, notification_type);
         self.inner.clone().stop_notify(id, notification_type)
     }
 
@@ -74,31 +123,51 @@ impl Notifier {
 #[async_trait]
 impl SubscriptionManager for Notifier {
     async fn start_notify(self: Arc<Self>, id: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
-        println!(
-            "[Notifier] as subscription manager start sending to listener {0} notifications of type {1:?}",
-            id, notification_type
-        );
+        debug!("as subscription manager start sending to listener {0} notifications of type {1:?}", id, notification_type);
         self.inner.clone().start_notify(id, notification_type)?;
         Ok(())
     }
 
     async fn stop_notify(self: Arc<Self>, id: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
-        println!("[Notifier] as subscription manager stop sending to listener {0} notifications of type {1:?}", id, notification_type);
+        debug!("as subscription manager stop sending to listener {0} notifications of type {1:?}", id, notification_type);
         self.inner.clone().stop_notify(id, notification_type)?;
         Ok(())
     }
 }
 
+/// The lanes of a dispatcher
+///
+/// Messages sent through the priority lane are always processed before any pending
+/// message of the data lane. See [`DispatchMessage::is_priority`].
+#[derive(Clone, Debug, Default)]
+struct DispatchChannel {
+    priority: Channel<DispatchMessage>,
+    data: Channel<DispatchMessage>,
+}
+
+impl DispatchChannel {
+    fn lane(&self, msg: &DispatchMessage) -> &Channel<DispatchMessage> {
+        if msg.is_priority() {
+            &self.priority
+        } else {
+            &self.data
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Inner {
     /// Map of registered listeners
     listeners: Arc<Mutex<AHashMap<ListenerID, Listener>>>,
 
     /// Dispatcher channels by event type
-    dispatcher_channel: EventArray<Channel<DispatchMessage>>,
+    dispatcher_channel: EventArray<DispatchChannel>,
     dispatcher_shutdown_listener: Arc<Mutex<EventArray<Option<triggered::Listener>>>>,
     dispatcher_is_running: EventArray<Arc<AtomicBool>>,
 
+    /// Count of received notifications by event type
+    notification_counts: EventArray<AtomicU64>,
+
     /// Collector & Subscriber
     collector: Arc<Option<DynCollector>>,
     subscriber: Arc<Option<Arc<Subscriber>>>,
@@ -115,6 +184,7 @@ impl Inner {
             dispatcher_channel: EventArray::default(),
             dispatcher_shutdown_listener: Arc::new(Mutex::new(EventArray::default())),
             dispatcher_is_running: EventArray::default(),
+            notification_counts: EventArray::default(),
             collector: Arc::new(collector),
             subscriber: Arc::new(subscriber),
             sending_changed_utxos,
@@ -130,7 +200,13 @@ impl Inner {
                 let (shutdown_trigger, shutdown_listener) = triggered::trigger();
                 let mut dispatcher_shutdown_listener = self.dispatcher_shutdown_listener.lock().unwrap();
                 dispatcher_shutdown_listener[event] = Some(shutdown_listener);
-                self.dispatch_task(event, shutdown_trigger, self.dispatcher_channel[event].receiver());
+                let dispatcher_channel = &self.dispatcher_channel[event];
+                self.dispatch_task(
+                    event,
+                    shutdown_trigger,
+                    dispatcher_channel.priority.receiver(),
+                    dispatcher_channel.data.receiver(),
+                );
             }
         }
         if let Some(ref collector) = self.collector.clone().as_ref() {
@@ -146,7 +222,17 @@ impl Inner {
     /// corresponding type. The dispatcher receives and execute messages
     /// instructing to modify the map. This happens without blocking
     /// the whole notifier.
-    fn dispatch_task(&self, event: EventType, shutdown_trigger: triggered::Trigger, dispatch_rx: Receiver<DispatchMessage>) {
+    ///
+    /// The dispatcher always processes the messages of the priority lane first,
+    /// so a burst of notifications cannot delay a change in the listeners map
+    /// nor the delivery of a critical event.
+    fn dispatch_task(
+        &self,
+        event: EventType,
+        shutdown_trigger: triggered::Trigger,
+        priority_rx: Receiver<DispatchMessage>,
+        data_rx: Receiver<DispatchMessage>,
+    ) {
         let dispatcher_is_running = self.dispatcher_is_running[event].clone();
         dispatcher_is_running.store(true, Ordering::SeqCst);
 
@@ -163,14 +249,14 @@ impl Inner {
         // This is necessary for the correct handling of repeating start/stop cycles.
 
         workflow_core::task::spawn(async move {
-            println!("[Notifier] dispatch_task spawned");
+            trace!("dispatch_task spawned");
 
             fn send_subscribe_message(send_subscriber: Sender<SubscribeMessage>, message: SubscribeMessage) {
-                println!("[Notifier] dispatch_task send subscribe message: {:?}", message);
+                trace!("dispatch_task send subscribe message: {:?}", message);
                 match send_subscriber.try_send(message) {
                     Ok(_) => {}
                     Err(err) => {
-                        println!("[Notifier] sending subscribe message error: {:?}", err);
+                        error!("sending subscribe message error: {:?}", err);
                     }
                 }
             }
@@ -192,7 +278,11 @@ impl Inner {
                         send_subscribe_message(send_subscriber.as_ref().unwrap().clone(), SubscribeMessage::StopEvent(event.into()));
                     }
                 }
-                let dispatch = dispatch_rx.recv().await.unwrap();
+                let dispatch = select_biased! {
+                    dispatch = priority_rx.recv().fuse() => dispatch,
+                    dispatch = data_rx.recv().fuse() => dispatch,
+                }
+                .unwrap();
 
                 match dispatch {
                     DispatchMessage::Send(ref notification) => {
@@ -245,14 +335,18 @@ impl Inner {
         });
     }
 
-    fn register_new_listener(self: Arc<Self>, channel: Option<NotificationChannel>) -> ListenerReceiverSide {
+    fn register_new_listener(
+        self: Arc<Self>,
+        channel: Option<NotificationChannel>,
+        metadata: ListenerMetadata,
+    ) -> ListenerReceiverSide {
         let mut listeners = self.listeners.lock().unwrap();
         loop {
-            let id = u64::from_le_bytes(rand::random::<[u8; 8]>());
+            let id = ListenerID::new_random();
 
             // This is very unlikely to happen but still, check for duplicates
             if !listeners.contains_key(&id) {
-                let listener = Listener::new(id, channel);
+                let listener = Listener::new(id, channel, metadata);
                 let registration: ListenerReceiverSide = (&listener).into();
                 listeners.insert(id, listener);
                 return registration;
@@ -260,6 +354,31 @@ impl Inner {
         }
     }
 
+    fn listeners(self: Arc<Self>) -> Vec<ListenerInfo> {
+        let listeners = self.listeners.lock().unwrap();
+        let mut infos: Vec<ListenerInfo> = listeners.values().map(|x| x.info()).collect();
+        infos.sort_by_key(|x| x.metadata.created);
+        infos
+    }
+
+    fn listener(self: Arc<Self>, id: ListenerID) -> Option<ListenerInfo> {
+        self.listeners.lock().unwrap().get(&id).map(|x| x.info())
+    }
+
+    fn dispatcher_stats(&self) -> Vec<DispatcherStats> {
+        EVENT_TYPE_ARRAY
+            .into_iter()
+            .map(|event| {
+                let channel = &self.dispatcher_channel[event];
+                DispatcherStats {
+                    event,
+                    notifications: self.notification_counts[event].load(Ordering::Relaxed),
+                    queue_depth: channel.priority.len() + channel.data.len(),
+                }
+            })
+            .collect()
+    }
+
     fn unregister_listener(self: Arc<Self>, id: ListenerID) -> Result<()> {
         let mut listeners = self.listeners.lock().unwrap();
         if let Some(mut listener) = listeners.remove(&id) {
@@ -289,7 +408,7 @@ impl Inner {
         let event: EventType = (&notification_type).into();
         let mut listeners = self.listeners.lock().unwrap();
         if let Some(listener) = listeners.get_mut(&id) {
-            println!("[Notifier] start notify to {0} about {1:?}", id, notification_type);
+            debug!("start notify to {0} about {1:?}", id, notification_type);
 
             // Any mutation in the listener will trigger a dispatch of a brand new ListenerSenderSide
             // eventually creating or replacing this listener in the matching dispatcher.
@@ -305,6 +424,7 @@ impl Inner {
 
     fn notifiy(self: Arc<Self>, notification: Arc<Notification>) -> Result<()> {
         let event: EventType = notification.as_ref().into();
+        self.notification_counts[event].fetch_add(1, Ordering::Relaxed);
         let msg = DispatchMessage::Send(notification);
         self.try_send_dispatch(event, msg)?;
         Ok(())
@@ -314,7 +434,7 @@ impl Inner {
         let event: EventType = (&notification_type).into();
         let mut listeners = self.listeners.lock().unwrap();
         if let Some(listener) = listeners.get_mut(&id) {
-            println!("[Notifier] stop notify to {0} about {1:?}", id, notification_type);
+            debug!("stop notify to {0} about {1:?}", id, notification_type);
 
             if listener.toggle(notification_type, false) {
                 let msg = DispatchMessage::RemoveListener(listener.id());
@@ -325,7 +445,7 @@ impl Inner {
     }
 
     fn try_send_dispatch(self: Arc<Self>, event: EventType, msg: DispatchMessage) -> Result<()> {
-        self.dispatcher_channel[event].sender().try_send(msg)?;
+        self.dispatcher_channel[event].lane(&msg).sender().try_send(msg)?;
         Ok(())
     }
 
@@ -360,3 +480,41 @@ impl Inner {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stubs::VirtualDaaScoreChangedNotification;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_control_messages_priority() {
+        let notifier = Arc::new(Notifier::new(None, None, SendingChangedUtxo::All));
+        let notification = Arc::new(Notification::VirtualDaaScoreChanged(VirtualDaaScoreChangedNotification));
+
+        // Fill the data lane while the dispatchers are not running yet, then register a listener
+        for _ in 0..3 {
+            notifier.clone().notifiy(notification.clone()).unwrap();
+        }
+        let listener = notifier.register_new_listener(None);
+        Notifier::start_notify(&notifier, listener.id, NotificationType::VirtualDaaScoreChanged).unwrap();
+        let queue_depth = |notifier: &Notifier| {
+            notifier.dispatcher_stats().iter().find(|x| x.event == EventType::VirtualDaaScoreChanged).unwrap().queue_depth
+        };
+        assert_eq!(queue_depth(&notifier), 4);
+
+        // The registration is handled first so the listener receives the queued notifications
+        notifier.clone().start();
+        for _ in 0..3 {
+            let received = tokio::time::timeout(Duration::from_secs(1), listener.recv()).await;
+            assert!(matches!(*received.unwrap().unwrap(), Notification::VirtualDaaScoreChanged(_)));
+        }
+
+        // So is a shutdown
+        assert!(DispatchMessage::Shutdown.is_priority());
+        assert!(DispatchMessage::RemoveListener(listener.id).is_priority());
+        assert!(!DispatchMessage::Send(notification).is_priority());
+        notifier.stop().await.unwrap();
+        assert!(!notifier.is_running());
+    }
+}
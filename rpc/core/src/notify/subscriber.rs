@@ -1,6 +1,7 @@
 use async_std::channel::{Receiver, Sender};
 use async_trait::async_trait;
 use core::fmt::Debug;
+use kaspa_core::error;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc, Mutex,
@@ -85,7 +86,7 @@ impl Subscriber {
                         match subscription_manager.clone().start_notify(listener_id, notification_type.clone()).await {
                             Ok(_) => (),
                             Err(err) => {
-                                println!("[Reporter] start notify error: {:?}", err);
+                                error!("start notify error: {:?}", err);
                             }
                         }
                     }
@@ -94,7 +95,7 @@ impl Subscriber {
                         match subscription_manager.clone().stop_notify(listener_id, notification_type.clone()).await {
                             Ok(_) => (),
                             Err(err) => {
-                                println!("[Reporter] start notify error: {:?}", err);
+                                error!("start notify error: {:?}", err);
                             }
                         }
                     }
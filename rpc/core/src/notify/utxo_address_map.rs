@@ -2,11 +2,11 @@ extern crate derive_more;
 use ahash::AHashMap;
 use derive_more::Deref;
 
-use crate::stubs::RpcUtxoAddress;
+use crate::RpcAddress;
 
-/// A newtype allowing conversion Vec<RpcUtxoAddress> to AHashMap<RpcUtxoAddress, ()>.
+/// A newtype allowing conversion Vec<RpcAddress> to AHashMap<RpcAddress, ()>.
 #[derive(Clone, Debug, Deref, Default)]
-pub struct RpcUtxoAddressMap(AHashMap<RpcUtxoAddress, ()>);
+pub struct RpcUtxoAddressMap(AHashMap<RpcAddress, ()>);
 
 impl RpcUtxoAddressMap {
     pub fn new() -> Self {
@@ -14,8 +14,8 @@ impl RpcUtxoAddressMap {
     }
 }
 
-impl From<&Vec<RpcUtxoAddress>> for RpcUtxoAddressMap {
-    fn from(item: &Vec<RpcUtxoAddress>) -> Self {
+impl From<&Vec<RpcAddress>> for RpcUtxoAddressMap {
+    fn from(item: &Vec<RpcAddress>) -> Self {
         Self(item.iter().map(|x| (x.clone(), ())).collect())
     }
 }
@@ -1,8 +1,6 @@
-use serde::{Deserialize, Serialize};
-use borsh::{BorshSerialize, BorshDeserialize, BorshSchema};
 use super::stubs::*;
-
-
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
 
 //   ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // ^ ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -10,62 +8,6 @@ use super::stubs::*;
 // ^ ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 //   ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-/// GetCurrentNetworkRequest requests the network kaspad is currently running against.
-///
-/// Possible networks are: Mainnet, Testnet, Simnet, Devnet
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-#[serde(rename_all = "camelCase")]
-pub struct GetCurrentNetworkRequest;
-
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-#[serde(rename_all = "camelCase")]
-pub struct GetCurrentNetworkResponse {
-    pub current_network: NetworkType, // FIXME
-    // pub current_network: String, // FIXME
-    // RpcError error = 1000;
-    // error : RpcError,
-}
-
-/// SubmitBlockRequest requests to submit a block into the DAG.
-/// Blocks are generally expected to have been generated using the getBlockTemplate call.
-///
-/// See: [`GetBlockTemplateRequest`]
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-#[serde(rename_all = "camelCase")]
-pub struct SubmitBlockRequest {
-    pub block: RpcBlock,
-    // allowNonDAABlocks
-    #[serde(alias = "allowNonDAABlocks")]
-    pub allow_non_daa_blocks: bool,
-}
-
-
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-#[serde(rename_all = "camelCase")]
-pub enum SubmitBlockRejectReason  {
-    // None = 0,
-    BlockInvalid = 1,
-    IsInIBD = 2
-}
-
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-#[serde(rename_all = "camelCase")]
-pub enum SubmitBlockResponse  {
-    Success,
-    Reject(SubmitBlockRejectReason),
-    // RpcError(RpcError)
-}
-
-// pub struct SubmitBlockResponse {
-//     enum RejectReason  {
-//         NONE = 0;
-//         BLOCK_INVALID = 1;
-//         IS_IN_IBD = 2;
-//     }
-//     RejectReason rejectReason = 1;
-//     RpcError error = 1000;
-// }
-
 /// GetBlockTemplateRequest requests a current block template.
 /// Callers are expected to solve the block template and submit it using the submitBlock call
 ///
@@ -74,7 +16,7 @@ pub enum SubmitBlockResponse  {
 #[serde(rename_all = "camelCase")]
 pub struct GetBlockTemplateRequest {
     /// Which kaspa address should the coinbase block reward transaction pay into
-    pub pay_address: Address,
+    pub pay_address: RpcAddress,
     pub extra_data: String, // FIXME
 }
 
@@ -88,7 +30,6 @@ pub struct GetBlockTemplateResponse {
     /// That is because when kaspad isn't in sync with the rest of the network there's a high
     /// chance the block will never be accepted, thus the solving effort would have been wasted.
     pub is_synced: bool,
-
     // RpcError error = 1000;
 }
 
@@ -98,39 +39,25 @@ pub struct GetBlockTemplateResponse {
 #[serde(rename_all = "camelCase")]
 pub struct GetPeerAddressesRequest;
 
-
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetPeerAddressesResponse {
     pub addresses: Vec<String>, // FIXME GetPeerAddressesKnownAddress>,
     pub banned_addresses: Vec<String>, // FIXME GetPeerAddressesKnownAddress,
-    // RpcError error = 1000;
+                                // RpcError error = 1000;
 }
 
 // pub struct GetPeerAddressesKnownAddress  {
 //     string Addr = 1;
 // }
 
-/// GetSelectedTipHashRequest requests the hash of the current virtual's
-/// selected parent.
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-#[serde(rename_all = "camelCase")]
-pub struct GetSelectedTipHashRequest;
-
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-#[serde(rename_all = "camelCase")]
-pub struct GetSelectedTipHashResponse {
-    pub selected_tip_hash: String, // FIXME
-    // RpcError error = 1000;
-}
-
 /// GetMempoolEntryRequest requests information about a specific transaction
 /// in the mempool.
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetMempoolEntryRequest {
     /// The transaction's TransactionID.
-    pub tx_id: String, 
+    pub tx_id: String,
     pub include_orphan_pool: bool,
     pub filter_transaction_pool: bool,
 }
@@ -138,8 +65,7 @@ pub struct GetMempoolEntryRequest {
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetMempoolEntryResponse {
-    pub entry: MempoolEntry
-    // RpcError error = 1000;
+    pub entry: MempoolEntry, // RpcError error = 1000;
 }
 
 /// GetMempoolEntriesRequest requests information about all the transactions
@@ -174,13 +100,13 @@ pub struct GetConnectedPeerInfoRequest;
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetConnectedPeerInfoResponse {
-    pub infos: Vec<GetConnectedPeerInfo>
+    pub infos: Vec<GetConnectedPeerInfo>,
 }
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetConnectedPeerInfo {
-    pub id: String, // FIXME
+    pub id: String,      // FIXME
     pub address: String, // FIXME
 
     /// How long did the last ping/pong exchange take
@@ -229,7 +155,7 @@ pub struct SubmitTransactionRequest {
 pub struct SubmitTransactionResponse {
     /// The transaction ID of the submitted transaction
     pub transaction_id: String, // FIXME
-    // RpcError error = 1000;
+                                // RpcError error = 1000;
 }
 
 /// NotifyVirtualSelectedParentChainChangedRequest registers this connection for virtualSelectedParentChainChanged notifications.
@@ -286,13 +212,13 @@ pub struct GetSubnetworkResponse {
 #[serde(rename_all = "camelCase")]
 pub struct GetVirtualSelectedParentChainFromBlockRequest {
     pub start_hash: String, // FIXME
-    pub include_accepted_transaction_ids: bool
+    pub include_accepted_transaction_ids: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AcceptedTransactionIds {
-    pub accepting_block_hash: String, // FIXME
+    pub accepting_block_hash: String,          // FIXME
     pub accepted_transaction_ids: Vec<String>, // FIXME
 }
 
@@ -308,7 +234,6 @@ pub struct GetVirtualSelectedParentChainFromBlockResponse {
     /// The transactions accepted by each block in addedChainBlockHashes.
     /// Will be filled only if `includeAcceptedTransactionIds = true` in the request.
     pub accepted_transaction_ids: Vec<AcceptedTransactionIds>,
-
     // RpcError error = 1000;
 }
 
@@ -360,7 +285,7 @@ pub struct GetBlockDagInfoResponse {
     pub difficulty: f64,
     pub past_median_time: i64,
     pub virtual_parent_hashes: Vec<String>, // FIXME
-    pub pruning_point_hash: String, // FIXME
+    pub pruning_point_hash: String,         // FIXME
     pub virtual_daa_score: u64,
     // RpcError error = 1000;
 }
@@ -399,19 +324,6 @@ pub struct FinalityConflictResolvedNotification {
     pub finality_block_hash: String, // FIXME
 }
 
-/// ShutDownRequest shuts down this kaspad.
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-#[serde(rename_all = "camelCase")]
-// pub struct ShutDownRequest;
-pub struct ShutdownRequest;
-
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-#[serde(rename_all = "camelCase")]
-// pub struct ShutDownResponse {
-pub struct ShutdownResponse {
-    // RpcError error = 1000;
-}
-
 /// GetHeadersRequest requests headers between the given startHash and the
 /// current virtual, up to the given limit.
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
@@ -426,7 +338,7 @@ pub struct GetHeadersRequest {
 #[serde(rename_all = "camelCase")]
 pub struct GetHeadersResponse {
     pub headers: Vec<String>, // FIXME
-    // RpcError error = 1000;
+                              // RpcError error = 1000;
 }
 
 /// NotifyUtxosChangedRequest registers this connection for utxoChanged notifications
@@ -437,14 +349,14 @@ pub struct GetHeadersResponse {
 /// See: [`UtxosChangedNotification`]
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct NotifyUtxosChangedRequest  {
+pub struct NotifyUtxosChangedRequest {
     /// Leave empty to get all updates
-    pub addresses: Vec<Address>, // FIXME
+    pub addresses: Vec<RpcAddress>,
 }
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct NotifyUtxosChangedResponse  {
+pub struct NotifyUtxosChangedResponse {
     // RpcError error = 1000;
 }
 
@@ -453,15 +365,15 @@ pub struct NotifyUtxosChangedResponse  {
 /// See: [`NotifyUtxosChangedRequest`]
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct UtxosChangedNotification  {
+pub struct UtxosChangedNotification {
     pub added: Vec<UtxosByAddressesEntry>,
-    pub removed: Vec<UtxosByAddressesEntry>
+    pub removed: Vec<UtxosByAddressesEntry>,
 }
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct UtxosByAddressesEntry  {
-    pub address: Address, // FIXME
+pub struct UtxosByAddressesEntry {
+    pub address: RpcAddress,
     pub outpoint: RpcOutpoint,
     pub utxo_entry: RpcUtxoEntry,
 }
@@ -474,13 +386,13 @@ pub struct UtxosByAddressesEntry  {
 /// See: [`UtxosChangedNotification`]
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct StopNotifyingUtxosChangedRequest  {
-    pub addresses: Vec<Address>, // FIXME
+pub struct StopNotifyingUtxosChangedRequest {
+    pub addresses: Vec<RpcAddress>,
 }
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct StopNotifyingUtxosChangedResponse  {
+pub struct StopNotifyingUtxosChangedResponse {
     // RpcError error = 1000;
 }
 
@@ -489,55 +401,51 @@ pub struct StopNotifyingUtxosChangedResponse  {
 /// This call is only available when this kaspad was started with `--utxoindex`
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct GetUtxosByAddressesRequest  {
-    pub addresses: Vec<Address>, // FIXME
+pub struct GetUtxosByAddressesRequest {
+    pub addresses: Vec<RpcAddress>,
 }
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct GetUtxosByAddressesResponse  {
+pub struct GetUtxosByAddressesResponse {
     pub entries: Vec<UtxosByAddressesEntry>,
-
     // RpcError error = 1000;
 }
 
 /// GetBalanceByAddressRequest returns the total balance in unspent transactions towards a given address
-/// 
+///
 /// This call is only available when this kaspad was started with `--utxoindex`
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct GetBalanceByAddressRequest  {
-    pub address: Address, // FIXME
+pub struct GetBalanceByAddressRequest {
+    pub address: RpcAddress,
 }
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct GetBalanceByAddressResponse  {
+pub struct GetBalanceByAddressResponse {
     pub balance: u64,
-
     // RpcError error = 1000;
 }
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct GetBalancesByAddressesRequest  {
-    pub addresses: Vec<Address>, // FIXME
+pub struct GetBalancesByAddressesRequest {
+    pub addresses: Vec<RpcAddress>,
 }
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BalancesByAddressEntry {
-    pub address: Address, // FIXME
+    pub address: RpcAddress,
     pub balance: u64,
-
     // RpcError error = 1000;
 }
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct GetBalancesByAddressesResponse  {
+pub struct GetBalancesByAddressesResponse {
     pub entries: Vec<BalancesByAddressEntry>,
-
     // RpcError error = 1000;
 }
 
@@ -545,14 +453,12 @@ pub struct GetBalancesByAddressesResponse  {
 /// of the virtual block.
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct GetVirtualSelectedParentBlueScoreRequest  {
-}
+pub struct GetVirtualSelectedParentBlueScoreRequest {}
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct GetVirtualSelectedParentBlueScoreResponse  {
+pub struct GetVirtualSelectedParentBlueScoreResponse {
     pub blue_score: u64,
-
     // RpcError error = 1000;
 }
 
@@ -562,12 +468,11 @@ pub struct GetVirtualSelectedParentBlueScoreResponse  {
 /// See: [`VirtualSelectedParentBlueScoreChangedNotification`]
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct NotifyVirtualSelectedParentBlueScoreChangedRequest  {
-}
+pub struct NotifyVirtualSelectedParentBlueScoreChangedRequest {}
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct NotifyVirtualSelectedParentBlueScoreChangedResponse  {
+pub struct NotifyVirtualSelectedParentBlueScoreChangedResponse {
     // RpcError error = 1000;
 }
 
@@ -577,7 +482,7 @@ pub struct NotifyVirtualSelectedParentBlueScoreChangedResponse  {
 /// See [`NotifyVirtualSelectedParentBlueScoreChangedRequest`]
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct VirtualSelectedParentBlueScoreChangedNotification  {
+pub struct VirtualSelectedParentBlueScoreChangedNotification {
     pub virtual_selected_parent_blue_score: u64,
 }
 
@@ -587,12 +492,11 @@ pub struct VirtualSelectedParentBlueScoreChangedNotification  {
 /// See: [`VirtualDaaScoreChangedNotification`]
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct NotifyVirtualDaaScoreChangedRequest  {
-}
+pub struct NotifyVirtualDaaScoreChangedRequest {}
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct NotifyVirtualDaaScoreChangedResponse  {
+pub struct NotifyVirtualDaaScoreChangedResponse {
     // RpcError error = 1000;
 }
 
@@ -602,7 +506,7 @@ pub struct NotifyVirtualDaaScoreChangedResponse  {
 /// See [`NotifyVirtualDaaScoreChangedRequest`]
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct VirtualDaaScoreChangedNotification  {
+pub struct VirtualDaaScoreChangedNotification {
     pub virtual_daa_score: u64,
 }
 
@@ -614,13 +518,11 @@ pub struct VirtualDaaScoreChangedNotification  {
 /// See: [`NotifyPruningPointUTXOSetOverrideResponse`]
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct NotifyPruningPointUTXOSetOverrideRequest  {
-}
-
+pub struct NotifyPruningPointUTXOSetOverrideRequest {}
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct NotifyPruningPointUTXOSetOverrideResponse  {
+pub struct NotifyPruningPointUTXOSetOverrideResponse {
     // RpcError error = 1000;
 }
 
@@ -630,8 +532,7 @@ pub struct NotifyPruningPointUTXOSetOverrideResponse  {
 /// See [`NotifyPruningPointUTXOSetOverrideRequest`]
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct PruningPointUTXOSetOverrideNotification  {
-}
+pub struct PruningPointUTXOSetOverrideNotification {}
 
 /// StopNotifyingPruningPointUTXOSetOverrideRequest unregisters this connection for
 /// pruning point UTXO set override notifications.
@@ -641,12 +542,11 @@ pub struct PruningPointUTXOSetOverrideNotification  {
 /// See: [`PruningPointUTXOSetOverrideNotification`]
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct StopNotifyingPruningPointUTXOSetOverrideRequest  {
-}
+pub struct StopNotifyingPruningPointUTXOSetOverrideRequest {}
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct StopNotifyingPruningPointUTXOSetOverrideResponse  {
+pub struct StopNotifyingPruningPointUTXOSetOverrideResponse {
     // RpcError error = 1000;
 }
 
@@ -676,32 +576,17 @@ pub struct UnbanResponse {
     // RpcError error = 1000;
 }
 
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-#[serde(rename_all = "camelCase")]
-pub struct EstimateNetworkHashesPerSecondRequest {
-    pub window_size: u32,
-    pub start_hash: String, // FIXME
-}
-
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-#[serde(rename_all = "camelCase")]
-pub struct EstimateNetworkHashesPerSecondResponse {
-    pub network_hashes_per_second: u64,
-    // RpcError error = 1000;
-}
-
 /// NotifyNewBlockTemplateRequest registers this connection for
 /// NewBlockTemplate notifications.
 ///
 /// See: [`NewBlockTemplateNotification`]
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct NotifyNewBlockTemplateRequest  {
-}
+pub struct NotifyNewBlockTemplateRequest {}
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct NotifyNewBlockTemplateResponse  {
+pub struct NotifyNewBlockTemplateResponse {
     // RpcError error = 1000;
 }
 
@@ -711,8 +596,7 @@ pub struct NotifyNewBlockTemplateResponse  {
 /// See [`NotifyNewBlockTemplateRequest`]
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct NewBlockTemplateNotification  {
-}
+pub struct NewBlockTemplateNotification {}
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
@@ -732,23 +616,7 @@ pub struct GetMempoolEntriesByAddressesRequest {
 
 #[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 #[serde(rename_all = "camelCase")]
-pub struct  GetMempoolEntriesByAddressesResponse {
-    pub entries : Vec<MempoolEntryByAddress>,
-
+pub struct GetMempoolEntriesByAddressesResponse {
+    pub entries: Vec<MempoolEntryByAddress>,
     // RpcError error = 1000;
 }
-
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-#[serde(rename_all = "camelCase")]
-pub struct GetCoinSupplyRequest {
-}
-
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-#[serde(rename_all = "camelCase")]
-pub struct GetCoinSupplyResponse {
-    /// note: this is a hard coded maxSupply, actual maxSupply is expected to deviate by upto -5%, but cannot be measured exactly.
-    pub max_sompi: u64,
-    pub circulating_sompi: u64,
-
-    // RpcError error = 1000;
-}
\ No newline at end of file
@@ -3,22 +3,20 @@
 use super::collector::{ConsensusCollector, ConsensusNotificationReceiver};
 use crate::{
     api::rpc,
+    metrics::Metrics,
     model::*,
     notify::{
         channel::NotificationChannel,
         listener::{ListenerID, ListenerReceiverSide, SendingChangedUtxo},
         notifier::Notifier,
     },
-    NotificationType, RpcError, RpcResult,
+    NotificationType, RpcResult,
 };
 use async_trait::async_trait;
-use hashes::Hash;
-use std::{
-    str::FromStr,
-    sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
-    vec,
-};
+use consensus_core::{api::DynConsensus, block::Block, subsidy};
+use kaspa_core::{info, warn};
+use kaspa_utils::triggers::SingleTrigger;
+use std::sync::Arc;
 
 /// A service implementing the Rpc API at rpc_core level.
 ///
@@ -39,11 +37,15 @@ use std::{
 /// Subscriber.
 #[derive(Debug)]
 pub struct RpcApi {
+    consensus: DynConsensus,
     notifier: Arc<Notifier>,
+    metrics: Arc<Metrics>,
+    shutdown: SingleTrigger,
 }
 
 impl RpcApi {
-    pub fn new(consensus_recv: ConsensusNotificationReceiver) -> Arc<Self> {
+    /// Builds the service, a [`rpc::RpcApi::shutdown`] call requesting the shutdown of the node by triggering `shutdown`.
+    pub fn new(consensus: DynConsensus, consensus_recv: ConsensusNotificationReceiver, shutdown: SingleTrigger) -> Arc<Self> {
         // // FIXME: the channel receiver should be obtained by registering to a consensus notification service
         // let consensus_notifications: ConsensusNotificationChannel = Channel::default();
 
@@ -52,7 +54,10 @@ impl RpcApi {
         // FIXME: Some consensus-compatible subscriber could be provided here
         let notifier = Arc::new(Notifier::new(Some(collector), None, SendingChangedUtxo::All));
 
-        Arc::new(Self { notifier })
+        let metrics = Arc::new(Metrics::new());
+        metrics.register_notifier("core", notifier.clone());
+
+        Arc::new(Self { consensus, notifier, metrics, shutdown })
     }
 
     pub fn start(&self) {
@@ -64,35 +69,95 @@ impl RpcApi {
         Ok(())
     }
 
+    pub fn consensus(&self) -> DynConsensus {
+        self.consensus.clone()
+    }
+
     pub fn notifier(&self) -> Arc<Notifier> {
         self.notifier.clone()
     }
+
+    /// The metrics registry shared by all the RPC services built on top of this instance.
+    pub fn metrics(&self) -> Arc<Metrics> {
+        self.metrics.clone()
+    }
 }
 
 #[async_trait]
 impl rpc::RpcApi for RpcApi {
-    async fn get_block(&self, req: GetBlockRequest) -> RpcResult<GetBlockResponse> {
-        // This is a test to simulate a consensus error
-        if req.hash.as_bytes()[0] == 0 {
-            return Err(RpcError::General(format!("Block {0} not found", req.hash)));
+    async fn ping(&self, _req: PingRequest) -> RpcResult<PingResponse> {
+        Ok(PingResponse {})
+    }
+
+    async fn get_current_network(&self, _req: GetCurrentNetworkRequest) -> RpcResult<GetCurrentNetworkResponse> {
+        Ok(GetCurrentNetworkResponse { current_network: self.consensus.network_type() })
+    }
+
+    async fn submit_block(&self, req: SubmitBlockRequest) -> RpcResult<SubmitBlockResponse> {
+        if !self.consensus.is_synced() {
+            return Ok(SubmitBlockResponse { report: SubmitBlockReport::Reject(SubmitBlockRejectReason::IsInIBD) });
         }
+        let block: Block = (&req.block).try_into()?;
+        let hash = block.hash();
+        let report = match self.consensus.validate_and_insert_block(block) {
+            Ok(()) => SubmitBlockReport::Success,
+            Err(err) => {
+                warn!("The RPC submitted block {0} triggered an error: {1}", hash, err);
+                SubmitBlockReport::Reject(SubmitBlockRejectReason::BlockInvalid)
+            }
+        };
+        Ok(SubmitBlockResponse { report })
+    }
 
-        // This is a test to simulate a respons containing a block
-        Ok(GetBlockResponse { block: create_dummy_rpc_block() })
+    async fn get_block(&self, req: GetBlockRequest) -> RpcResult<GetBlockResponse> {
+        let block = self.consensus.get_block(req.hash)?;
+        let context = self.consensus.get_block_dag_context(req.hash)?;
+        Ok(GetBlockResponse {
+            block: RpcBlock::from_block(&block, &context, req.include_transactions, self.consensus.network_type().into()),
+        })
     }
 
     async fn get_info(&self, _req: GetInfoRequest) -> RpcResult<GetInfoResponse> {
-        // Info should be queried from consensus
         Ok(GetInfoResponse {
             p2p_id: "test".to_string(),
-            mempool_size: 1,
+            mempool_size: 0,
             server_version: "0.12.8".to_string(),
             is_utxo_indexed: false,
-            is_synced: false,
+            is_synced: self.consensus.is_synced(),
             has_notify_command: true,
         })
     }
 
+    async fn get_selected_tip_hash(&self, _req: GetSelectedTipHashRequest) -> RpcResult<GetSelectedTipHashResponse> {
+        Ok(GetSelectedTipHashResponse { selected_tip_hash: self.consensus.get_virtual_state().selected_parent })
+    }
+
+    async fn shutdown(&self, _req: ShutdownRequest) -> RpcResult<ShutdownResponse> {
+        info!("Shutdown requested through RPC");
+        self.shutdown.trigger.trigger();
+        Ok(ShutdownResponse {})
+    }
+
+    async fn estimate_network_hashes_per_second(
+        &self,
+        req: EstimateNetworkHashesPerSecondRequest,
+    ) -> RpcResult<EstimateNetworkHashesPerSecondResponse> {
+        // A window spanning no time can't tell any hashrate
+        if req.window_size < 2 {
+            return Err(format!("window size {0} is too small, at least 2 blocks are needed", req.window_size).into());
+        }
+        let network_hashes_per_second = self.consensus.estimate_network_hashes_per_second(req.start_hash, req.window_size as usize)?;
+        Ok(EstimateNetworkHashesPerSecondResponse { network_hashes_per_second })
+    }
+
+    async fn get_coin_supply(&self, _req: GetCoinSupplyRequest) -> RpcResult<GetCoinSupplyResponse> {
+        Ok(GetCoinSupplyResponse { max_sompi: subsidy::MAX_SOMPI, circulating_sompi: self.consensus.get_circulating_supply() })
+    }
+
+    async fn get_metrics(&self, _req: GetMetricsRequest) -> RpcResult<GetMetricsResponse> {
+        Ok(self.metrics.snapshot())
+    }
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
 
@@ -121,36 +186,3 @@ impl rpc::RpcApi for RpcApi {
         Ok(())
     }
 }
-
-fn create_dummy_rpc_block() -> RpcBlock {
-    let sel_parent_hash = Hash::from_str("5963be67f12da63004ce1baceebd7733c4fb601b07e9b0cfb447a3c5f4f3c4f0").unwrap();
-    RpcBlock {
-        header: RpcBlockHeader {
-            version: 1,
-            parents: vec![],
-            hash_merkle_root: Hash::from_str("4b5a041951c4668ecc190c6961f66e54c1ce10866bef1cf1308e46d66adab270").unwrap(),
-            accepted_id_merkle_root: Hash::from_str("1a1310d49d20eab15bf62c106714bdc81e946d761701e81fabf7f35e8c47b479").unwrap(),
-            utxo_commitment: Hash::from_str("e7cdeaa3a8966f3fff04e967ed2481615c76b7240917c5d372ee4ed353a5cc15").unwrap(),
-            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as i64,
-            bits: 1,
-            nonce: 1234,
-            daa_score: 123456,
-            blue_work: 1234567890.into(),
-            pruning_point: Hash::from_str("7190c08d42a0f7994b183b52e7ef2f99bac0b91ef9023511cadf4da3a2184b16").unwrap(),
-            blue_score: 12345678901,
-        },
-        transactions: vec![],
-        verbose_data: RpcBlockVerboseData {
-            hash: Hash::from_str("8270e63a0295d7257785b9c9b76c9a2efb7fb8d6ac0473a1bff1571c5030e995").unwrap(),
-            difficulty: 5678.0,
-            selected_parent_hash: sel_parent_hash,
-            transaction_ids: vec![],
-            is_header_only: true,
-            blue_score: 98765,
-            children_hashes: vec![],
-            merge_set_blues_hashes: vec![],
-            merge_set_reds_hashes: vec![],
-            is_chain_block: true,
-        },
-    }
-}
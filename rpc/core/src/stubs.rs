@@ -1,24 +1,13 @@
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
-use crate::RpcHexData;
-
-#[derive(Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-pub enum NetworkType {
-    Mainnet,
-    Testnet,
-    Simnet,
-    Devnet,
-}
-
-#[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
-pub struct Address;
+use crate::RpcAddress;
 
-pub type RpcUtxoAddress = RpcHexData;
+pub use consensus_core::networktype::NetworkType;
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
 pub struct UtxosChangedNotification {
-    pub utxo_address: RpcUtxoAddress,
+    pub utxo_address: RpcAddress,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, BorshSerialize, BorshDeserialize, BorshSchema)]
@@ -4,8 +4,12 @@ fn main() {
 
     println!("cargo:rerun-if-changed={}, {}", proto_file1, proto_file2);
 
+    // The descriptor set is served by the reflection service
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+
     tonic_build::configure()
         .build_server(true)
+        .file_descriptor_set_path(out_dir.join("protowire_descriptor.bin"))
         .compile(&[proto_file1], &["./proto/", "."])
         .unwrap_or_else(|e| panic!("protobuf compile error: {}", e));
 
@@ -6,6 +6,7 @@ use self::result::Result;
 use rpc_core::{
     api::ops::RpcApiOps,
     api::rpc::RpcApi,
+    metrics::Metrics,
     notify::{
         channel::NotificationChannel,
         collector::RpcCoreCollector,
@@ -13,7 +14,10 @@ use rpc_core::{
         notifier::Notifier,
         subscriber::Subscriber,
     },
-    GetBlockRequest, GetBlockResponse, GetInfoRequest, GetInfoResponse, NotificationType, RpcError, RpcResult,
+    EstimateNetworkHashesPerSecondRequest, EstimateNetworkHashesPerSecondResponse, GetBlockRequest, GetBlockResponse,
+    GetCoinSupplyRequest, GetCoinSupplyResponse, GetCurrentNetworkRequest, GetCurrentNetworkResponse, GetInfoRequest, GetInfoResponse,
+    GetMetricsRequest, GetMetricsResponse, GetSelectedTipHashRequest, GetSelectedTipHashResponse, NotificationType, PingRequest,
+    PingResponse, RpcError, RpcResult, ShutdownRequest, ShutdownResponse, SubmitBlockRequest, SubmitBlockResponse,
 };
 
 mod errors;
@@ -23,18 +27,21 @@ mod result;
 pub struct RpcApiGrpc {
     inner: Arc<Resolver>,
     notifier: Arc<Notifier>,
+    metrics: Arc<Metrics>,
 }
 
 impl RpcApiGrpc {
     pub async fn connect(address: String) -> Result<RpcApiGrpc> {
         let notify_channel = NotificationChannel::default();
-        let inner = Resolver::connect(address, notify_channel.sender()).await?;
+        let metrics = Arc::new(Metrics::new());
+        let inner = Resolver::connect(address, notify_channel.sender(), metrics.clone()).await?;
         let collector = Arc::new(RpcCoreCollector::new(notify_channel.receiver()));
-        let subscriber = Subscriber::new(inner.clone(), 0);
+        let subscriber = Subscriber::new(inner.clone(), ListenerID::default());
 
         let notifier = Arc::new(Notifier::new(Some(collector), Some(subscriber), SendingChangedUtxo::FilteredByAddress));
+        metrics.register_notifier("client", notifier.clone());
 
-        Ok(Self { inner, notifier })
+        Ok(Self { inner, notifier, metrics })
     }
 
     pub async fn start(&self) {
@@ -46,11 +53,17 @@ impl RpcApiGrpc {
         Ok(())
     }
 
+    /// The metrics of this client, as opposed to [`RpcApi::get_metrics`] which queries the server.
+    pub fn metrics(&self) -> Arc<Metrics> {
+        self.metrics.clone()
+    }
+
     pub fn handle_stop_notify(&self) -> bool {
         self.inner.handle_stop_notify()
     }
 
-    pub async fn shutdown(&mut self) -> Result<()> {
+    /// Closes the connection to the server.
+    pub async fn disconnect(&mut self) -> Result<()> {
         self.inner.clone().shutdown().await?;
         Ok(())
     }
@@ -58,6 +71,22 @@ impl RpcApiGrpc {
 
 #[async_trait]
 impl RpcApi for RpcApiGrpc {
+    async fn ping(&self, request: PingRequest) -> RpcResult<PingResponse> {
+        self.inner.clone().call(RpcApiOps::Ping, request).await?.as_ref().try_into()
+    }
+
+    async fn get_current_network(&self, request: GetCurrentNetworkRequest) -> RpcResult<GetCurrentNetworkResponse> {
+        self.inner.clone().call(RpcApiOps::GetCurrentNetwork, request).await?.as_ref().try_into()
+    }
+
+    async fn submit_block(&self, request: SubmitBlockRequest) -> RpcResult<SubmitBlockResponse> {
+        self.inner.clone().call(RpcApiOps::SubmitBlock, request).await?.as_ref().try_into()
+    }
+
+    async fn get_selected_tip_hash(&self, request: GetSelectedTipHashRequest) -> RpcResult<GetSelectedTipHashResponse> {
+        self.inner.clone().call(RpcApiOps::GetSelectedTipHash, request).await?.as_ref().try_into()
+    }
+
     async fn get_block(&self, request: GetBlockRequest) -> RpcResult<GetBlockResponse> {
         self.inner.clone().call(RpcApiOps::GetBlock, request).await?.as_ref().try_into()
     }
@@ -66,6 +95,25 @@ impl RpcApi for RpcApiGrpc {
         self.inner.clone().call(RpcApiOps::GetInfo, request).await?.as_ref().try_into()
     }
 
+    async fn estimate_network_hashes_per_second(
+        &self,
+        request: EstimateNetworkHashesPerSecondRequest,
+    ) -> RpcResult<EstimateNetworkHashesPerSecondResponse> {
+        self.inner.clone().call(RpcApiOps::EstimateNetworkHashesPerSecond, request).await?.as_ref().try_into()
+    }
+
+    async fn get_coin_supply(&self, request: GetCoinSupplyRequest) -> RpcResult<GetCoinSupplyResponse> {
+        self.inner.clone().call(RpcApiOps::GetCoinSupply, request).await?.as_ref().try_into()
+    }
+
+    async fn get_metrics(&self, request: GetMetricsRequest) -> RpcResult<GetMetricsResponse> {
+        self.inner.clone().call(RpcApiOps::GetMetrics, request).await?.as_ref().try_into()
+    }
+
+    async fn shutdown(&self, request: ShutdownRequest) -> RpcResult<ShutdownResponse> {
+        self.inner.clone().call(RpcApiOps::Shutdown, request).await?.as_ref().try_into()
+    }
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Notification API
 
@@ -33,9 +33,16 @@ impl Matcher<&kaspad_response::Payload> for kaspad_request::Payload {
     fn is_matching(&self, response: &kaspad_response::Payload) -> bool {
         match self {
             kaspad_request::Payload::GetBlockRequest(ref request) => request.is_matching(response),
+            kaspad_request::Payload::PingRequest(_) => true,
             kaspad_request::Payload::GetCurrentNetworkRequest(_) => true,
+            kaspad_request::Payload::GetSelectedTipHashRequest(_) => true,
+            kaspad_request::Payload::SubmitBlockRequest(_) => true,
             kaspad_request::Payload::NotifyBlockAddedRequest(_) => true,
             kaspad_request::Payload::GetInfoRequest(_) => true,
+            kaspad_request::Payload::EstimateNetworkHashesPerSecondRequest(_) => true,
+            kaspad_request::Payload::GetCoinSupplyRequest(_) => true,
+            kaspad_request::Payload::GetMetricsRequest(_) => true,
+            kaspad_request::Payload::ShutDownRequest(_) => true,
         }
     }
 }
@@ -6,9 +6,11 @@ use futures::{
     pin_mut,
     select,
 };
+use kaspa_core::{debug, error, trace, warn};
 use kaspa_utils::triggers::DuplexTrigger;
 use rpc_core::{
     api::ops::{RpcApiOps, SubscribeCommand},
+    metrics::Metrics,
     notify::{events::EventType, listener::ListenerID, subscriber::SubscriptionManager},
     GetInfoResponse, Notification, NotificationSender, NotificationType, RpcResult,
 };
@@ -36,8 +38,6 @@ mod matcher;
 
 pub type SenderResponse = tokio::sync::oneshot::Sender<Result<KaspadResponse>>;
 
-//pub(crate) type RequestID = u64;
-
 #[derive(Debug)]
 struct Pending {
     timestamp: Instant,
@@ -52,13 +52,18 @@ impl Pending {
     }
 
     fn is_matching(&self, response: &KaspadResponse, response_op: RpcApiOps) -> bool {
+        // A server supporting request ids echoes them in its responses
+        if response.id != 0 {
+            return self.request.id == response.id;
+        }
         self.op == response_op && self.request.is_matching(response)
     }
 }
 
 /// A struct to handle messages flowing to (requestes) and from (responses) a protowire server.
-/// Incoming responses are associated to pending requests based on their matching operation
-/// type and, for some operations like [`ClientApiOps::GetBlock`], on their properties.
+/// Every request gets a unique id. Incoming responses are associated to pending requests by this id
+/// if the server echoes it, or else based on their matching operation type and, for some operations
+/// like [`ClientApiOps::GetBlock`], on their properties.
 ///
 /// Data flow:
 /// ```
@@ -97,6 +102,7 @@ pub struct Resolver {
     // Sending to server
     request_send: Sender<KaspadRequest>,
     pending_calls: Arc<Mutex<VecDeque<Pending>>>,
+    next_request_id: AtomicU64,
     sender_is_running: AtomicBool,
     sender_shutdown: DuplexTrigger,
 
@@ -109,6 +115,8 @@ pub struct Resolver {
     timeout_shutdown: DuplexTrigger,
     timeout_timer_interval: AtomicU64,
     timeout_duration: AtomicU64,
+
+    metrics: Arc<Metrics>,
 }
 
 impl Resolver {
@@ -117,6 +125,7 @@ impl Resolver {
         handle_stop_notify: bool,
         notify_send: NotificationSender,
         request_send: Sender<KaspadRequest>,
+        metrics: Arc<Metrics>,
     ) -> Self {
         Self {
             _inner: client,
@@ -124,6 +133,7 @@ impl Resolver {
             notify_send,
             request_send,
             pending_calls: Arc::new(Mutex::new(VecDeque::new())),
+            next_request_id: AtomicU64::new(1),
             sender_is_running: AtomicBool::new(false),
             sender_shutdown: DuplexTrigger::new(),
             receiver_is_running: AtomicBool::new(false),
@@ -132,10 +142,11 @@ impl Resolver {
             timeout_shutdown: DuplexTrigger::new(),
             timeout_duration: AtomicU64::new(5_000),
             timeout_timer_interval: AtomicU64::new(1_000),
+            metrics,
         }
     }
 
-    pub(crate) async fn connect(address: String, notify_send: NotificationSender) -> Result<Arc<Self>> {
+    pub(crate) async fn connect(address: String, notify_send: NotificationSender, metrics: Arc<Metrics>) -> Result<Arc<Self>> {
         let channel = Endpoint::from_shared(address.clone())?
             .timeout(tokio::time::Duration::from_secs(5))
             .connect_timeout(tokio::time::Duration::from_secs(20))
@@ -164,7 +175,7 @@ impl Resolver {
 
         match stream.message().await? {
             Some(ref msg) => {
-                println!("GetInfo got response {:?}", msg);
+                trace!("GetInfo got response {:?}", msg);
                 let response: RpcResult<GetInfoResponse> = msg.try_into();
                 if let Ok(response) = response {
                     handle_stop_notify = response.has_notify_command;
@@ -175,7 +186,7 @@ impl Resolver {
             }
         }
 
-        let resolver = Arc::new(Resolver::new(client, handle_stop_notify, notify_send, request_send));
+        let resolver = Arc::new(Resolver::new(client, handle_stop_notify, notify_send, request_send, metrics));
 
         // KaspadRequest timeout cleaner
         resolver.clone().timeout_task();
@@ -194,22 +205,33 @@ impl Resolver {
     }
 
     pub(crate) async fn call(&self, op: RpcApiOps, request: impl Into<KaspadRequest>) -> Result<KaspadResponse> {
-        let request: KaspadRequest = request.into();
-        println!("resolver call: {:?}", request);
+        let mut request: KaspadRequest = request.into();
+        request.id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        trace!("resolver call: {:?}", request);
         if request.payload.is_some() {
+            let started = Instant::now();
             let (sender, receiver) = oneshot::channel::<Result<KaspadResponse>>();
 
             {
-                let pending = Pending::new(op, request.clone(), sender);
+                let pending = Pending::new(op.clone(), request.clone(), sender);
 
                 let mut pending_calls = self.pending_calls.lock().unwrap();
                 pending_calls.push_back(pending);
+                self.metrics.pending_calls().set(pending_calls.len() as i64);
                 drop(pending_calls);
             }
 
-            self.request_send.send(request).await.map_err(|_| Error::ChannelRecvError)?;
-
-            receiver.await?
+            // Failing to send the request or to receive its response also counts as an error
+            let response = match self.request_send.send(request).await {
+                Ok(_) => match receiver.await {
+                    Ok(response) => response,
+                    Err(err) => Err(err.into()),
+                },
+                Err(_) => Err(Error::ChannelRecvError),
+            };
+            let is_error = !matches!(response, Ok(ref response) if response.error().is_none());
+            self.metrics.record_call(op, started.elapsed(), is_error);
+            response
         } else {
             Err(Error::MissingRequestPayload)
         }
@@ -231,7 +253,7 @@ impl Resolver {
                 select! {
                     _ = shutdown => { break; },
                     _ = delay => {
-                        println!("[Resolver] running timeout task");
+                        debug!("running timeout task");
                         let mut pending_calls = self.pending_calls.lock().unwrap();
                         let mut purge = Vec::<usize>::new();
                         let timeout = Duration::from_millis(self.timeout_duration.load(Ordering::Relaxed));
@@ -249,7 +271,7 @@ impl Resolver {
                             let pending = pending_calls.remove(*index);
                             if let Some(pending) = pending {
 
-                                println!("[Resolver] timeout task purged request emmited {:?}", pending.timestamp);
+                                warn!("timeout task purged request emmited {:?}", pending.timestamp);
 
                                 // This attribute doesn't seem to work at expression level
                                 // So it is duplicated at fn level
@@ -257,11 +279,12 @@ impl Resolver {
                                 pending.sender.send(Err(Error::Timeout));
                             }
                         }
+                        self.metrics.pending_calls().set(pending_calls.len() as i64);
                     },
                 }
             }
 
-            println!("[Resolver] terminating timeout task");
+            debug!("terminating timeout task");
             self.timeout_is_running.store(false, Ordering::SeqCst);
             self.timeout_shutdown.response.trigger.trigger();
         });
@@ -272,10 +295,10 @@ impl Resolver {
 
         tokio::spawn(async move {
             loop {
-                println!("[Resolver] sender task loop");
+                trace!("sender task loop");
 
                 if send.is_closed() {
-                    println!("[Resolver] sender_task sender is closed");
+                    debug!("sender_task sender is closed");
                     break;
                 }
 
@@ -290,24 +313,24 @@ impl Resolver {
                                 match msg {
                                     Some(response) => {
                                         if let Err(err) = send.send(response).await {
-                                            println!("[Resolver] sender_task sender error: {:?}", err);
+                                            error!("sender_task sender error: {:?}", err);
                                         }
                                     },
                                     None =>{
-                                        println!("[Resolver] sender_task sender error: no payload");
+                                        error!("sender_task sender error: no payload");
                                         break;
                                     }
                                 }
                             },
                             Err(err) => {
-                                println!("[Resolver] sender_task sender error: {:?}", err);
+                                error!("sender_task sender error: {:?}", err);
                             }
                         }
                     }
                 }
             }
 
-            println!("[Resolver] terminating sender task");
+            debug!("terminating sender task");
             self.sender_is_running.store(false, Ordering::SeqCst);
             self.sender_shutdown.response.trigger.trigger();
         });
@@ -318,7 +341,7 @@ impl Resolver {
 
         tokio::spawn(async move {
             loop {
-                println!("[Resolver] receiver task loop");
+                trace!("receiver task loop");
 
                 let shutdown = self.receiver_shutdown.request.listener.clone();
                 pin_mut!(shutdown);
@@ -329,7 +352,7 @@ impl Resolver {
                 }
             }
 
-            println!("[Resolver] terminating receiver task");
+            debug!("terminating receiver task");
             self.receiver_is_running.store(false, Ordering::SeqCst);
             self.receiver_shutdown.response.trigger.trigger();
         });
@@ -338,22 +361,22 @@ impl Resolver {
     #[allow(unused_must_use)]
     fn handle_response(&self, response: KaspadResponse) {
         if response.is_notification() {
-            println!("[Resolver] handle_response received a notification");
+            trace!("handle_response received a notification");
             match Notification::try_from(&response) {
                 Ok(notification) => {
                     let event: EventType = (&notification).into();
-                    println!("[Resolver] handle_response received notification: {:?}", event);
+                    trace!("handle_response received notification: {:?}", event);
 
                     // Here we ignore any returned error
                     self.notify_send.try_send(Arc::new(notification));
                 }
                 Err(err) => {
-                    println!("[Resolver] handle_response error converting reponse into notification: {:?}", err);
+                    error!("handle_response error converting reponse into notification: {:?}", err);
                 }
             }
         } else if response.payload.is_some() {
             let response_op: RpcApiOps = response.payload.as_ref().unwrap().into();
-            println!("[Resolver] handle_response type: {:?}", response_op);
+            trace!("handle_response type: {:?}", response_op);
             let mut pending_calls = self.pending_calls.lock().unwrap();
             let mut pending: Option<Pending> = None;
             if pending_calls.front().is_some() {
@@ -370,9 +393,10 @@ impl Resolver {
                     }
                 }
             }
+            self.metrics.pending_calls().set(pending_calls.len() as i64);
             drop(pending_calls);
             if let Some(pending) = pending {
-                println!("[Resolver] handle_response matching request found: {:?}", pending.request);
+                trace!("handle_response matching request found: {:?}", pending.request);
 
                 // This attribute doesn't seem to work at expression level
                 // So it is duplicated at fn level
@@ -417,7 +441,7 @@ impl Resolver {
 #[async_trait]
 impl SubscriptionManager for Resolver {
     async fn start_notify(self: Arc<Self>, _: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
-        println!("[Resolver] start_notify: {:?}", notification_type);
+        debug!("start_notify: {:?}", notification_type);
         // FIXME: Enhance protowire with Subscribe Commands (handle explicit Start)
         let request = kaspad_request::Payload::from_notification_type(&notification_type, SubscribeCommand::Start);
         self.clone().call((&request).into(), request).await?;
@@ -426,11 +450,11 @@ impl SubscriptionManager for Resolver {
 
     async fn stop_notify(self: Arc<Self>, _: ListenerID, notification_type: NotificationType) -> RpcResult<()> {
         if self.handle_stop_notify {
-            println!("[Resolver] stop_notify: {:?}", notification_type);
+            debug!("stop_notify: {:?}", notification_type);
             let request = kaspad_request::Payload::from_notification_type(&notification_type, SubscribeCommand::Stop);
             self.clone().call((&request).into(), request).await?;
         } else {
-            println!("[Resolver] stop_notify ignored because not supported by server: {:?}", notification_type);
+            warn!("stop_notify ignored because not supported by server: {:?}", notification_type);
         }
         Ok(())
     }
@@ -4,9 +4,16 @@ use rpc_core::api::ops::RpcApiOps;
 impl From<&kaspad_request::Payload> for RpcApiOps {
     fn from(item: &kaspad_request::Payload) -> Self {
         match item {
+            kaspad_request::Payload::PingRequest(_) => RpcApiOps::Ping,
             kaspad_request::Payload::GetCurrentNetworkRequest(_) => RpcApiOps::GetCurrentNetwork,
+            kaspad_request::Payload::SubmitBlockRequest(_) => RpcApiOps::SubmitBlock,
+            kaspad_request::Payload::GetSelectedTipHashRequest(_) => RpcApiOps::GetSelectedTipHash,
             kaspad_request::Payload::GetBlockRequest(_) => RpcApiOps::GetBlock,
+            kaspad_request::Payload::ShutDownRequest(_) => RpcApiOps::Shutdown,
             kaspad_request::Payload::GetInfoRequest(_) => RpcApiOps::GetInfo,
+            kaspad_request::Payload::EstimateNetworkHashesPerSecondRequest(_) => RpcApiOps::EstimateNetworkHashesPerSecond,
+            kaspad_request::Payload::GetCoinSupplyRequest(_) => RpcApiOps::GetCoinSupply,
+            kaspad_request::Payload::GetMetricsRequest(_) => RpcApiOps::GetMetrics,
 
             // Subscription commands for starting/stopping notifications
             kaspad_request::Payload::NotifyBlockAddedRequest(_) => RpcApiOps::NotifyBlockAdded,
@@ -17,9 +24,16 @@ impl From<&kaspad_request::Payload> for RpcApiOps {
 impl From<&kaspad_response::Payload> for RpcApiOps {
     fn from(item: &kaspad_response::Payload) -> Self {
         match item {
+            kaspad_response::Payload::PingResponse(_) => RpcApiOps::Ping,
             kaspad_response::Payload::GetCurrentNetworkResponse(_) => RpcApiOps::GetCurrentNetwork,
+            kaspad_response::Payload::SubmitBlockResponse(_) => RpcApiOps::SubmitBlock,
+            kaspad_response::Payload::GetSelectedTipHashResponse(_) => RpcApiOps::GetSelectedTipHash,
             kaspad_response::Payload::GetBlockResponse(_) => RpcApiOps::GetBlock,
+            kaspad_response::Payload::ShutDownResponse(_) => RpcApiOps::Shutdown,
             kaspad_response::Payload::GetInfoResponse(_) => RpcApiOps::GetInfo,
+            kaspad_response::Payload::EstimateNetworkHashesPerSecondResponse(_) => RpcApiOps::EstimateNetworkHashesPerSecond,
+            kaspad_response::Payload::GetCoinSupplyResponse(_) => RpcApiOps::GetCoinSupply,
+            kaspad_response::Payload::GetMetricsResponse(_) => RpcApiOps::GetMetrics,
 
             // Subscription commands for starting/stopping notifications
             kaspad_response::Payload::NotifyBlockAddedResponse(_) => RpcApiOps::NotifyBlockAdded,
@@ -32,7 +46,7 @@ impl From<&kaspad_response::Payload> for RpcApiOps {
 
 impl From<kaspad_request::Payload> for KaspadRequest {
     fn from(item: kaspad_request::Payload) -> Self {
-        KaspadRequest { payload: Some(item) }
+        KaspadRequest { id: 0, payload: Some(item) }
     }
 }
 
@@ -52,9 +66,21 @@ pub mod kaspad_request_convert {
     use crate::protowire::*;
     use rpc_core::{RpcError, RpcResult};
 
+    impl_into_kaspad_request!(rpc_core::PingRequest, PingRequestMessage, PingRequest);
+    impl_into_kaspad_request!(rpc_core::GetCurrentNetworkRequest, GetCurrentNetworkRequestMessage, GetCurrentNetworkRequest);
+    impl_into_kaspad_request!(rpc_core::SubmitBlockRequest, SubmitBlockRequestMessage, SubmitBlockRequest);
+    impl_into_kaspad_request!(rpc_core::GetSelectedTipHashRequest, GetSelectedTipHashRequestMessage, GetSelectedTipHashRequest);
     impl_into_kaspad_request!(rpc_core::GetBlockRequest, GetBlockRequestMessage, GetBlockRequest);
+    impl_into_kaspad_request!(rpc_core::ShutdownRequest, ShutDownRequestMessage, ShutDownRequest);
     impl_into_kaspad_request!(rpc_core::NotifyBlockAddedRequest, NotifyBlockAddedRequestMessage, NotifyBlockAddedRequest);
     impl_into_kaspad_request!(rpc_core::GetInfoRequest, GetInfoRequestMessage, GetInfoRequest);
+    impl_into_kaspad_request!(
+        rpc_core::EstimateNetworkHashesPerSecondRequest,
+        EstimateNetworkHashesPerSecondRequestMessage,
+        EstimateNetworkHashesPerSecondRequest
+    );
+    impl_into_kaspad_request!(rpc_core::GetCoinSupplyRequest, GetCoinSupplyRequestMessage, GetCoinSupplyRequest);
+    impl_into_kaspad_request!(rpc_core::GetMetricsRequest, GetMetricsRequestMessage, GetMetricsRequest);
 
     macro_rules! impl_into_kaspad_request {
         ($($core_struct:ident)::+, $($protowire_struct:ident)::+, $($variant:ident)::+) => {
@@ -72,6 +98,7 @@ pub mod kaspad_request_convert {
             impl From<&$($core_struct)::+> for KaspadRequest {
                 fn from(item: &$($core_struct)::+) -> Self {
                     Self {
+                        id: 0,
                         payload: Some(item.into())
                     }
                 }
@@ -86,6 +113,7 @@ pub mod kaspad_request_convert {
             impl From<$($core_struct)::+> for KaspadRequest {
                 fn from(item: $($core_struct)::+) -> Self {
                     Self {
+                        id: 0,
                         payload: Some((&item).into())
                     }
                 }
@@ -118,7 +146,7 @@ pub mod kaspad_request_convert {
 
             impl From<$($protowire_struct)::+> for KaspadRequest {
                 fn from(item: $($protowire_struct)::+) -> Self {
-                    Self { payload: Some(kaspad_request::Payload::$($variant)::+(item)) }
+                    Self { id: 0, payload: Some(kaspad_request::Payload::$($variant)::+(item)) }
                 }
             }
 
@@ -137,8 +165,20 @@ pub mod kaspad_response_convert {
     use crate::protowire::*;
     use rpc_core::{RpcError, RpcResult};
 
+    impl_into_kaspad_response!(rpc_core::PingResponse, PingResponseMessage, PingResponse);
+    impl_into_kaspad_response!(rpc_core::GetCurrentNetworkResponse, GetCurrentNetworkResponseMessage, GetCurrentNetworkResponse);
+    impl_into_kaspad_response!(rpc_core::SubmitBlockResponse, SubmitBlockResponseMessage, SubmitBlockResponse);
+    impl_into_kaspad_response!(rpc_core::GetSelectedTipHashResponse, GetSelectedTipHashResponseMessage, GetSelectedTipHashResponse);
     impl_into_kaspad_response!(rpc_core::GetBlockResponse, GetBlockResponseMessage, GetBlockResponse);
+    impl_into_kaspad_response!(rpc_core::ShutdownResponse, ShutDownResponseMessage, ShutDownResponse);
     impl_into_kaspad_response!(rpc_core::GetInfoResponse, GetInfoResponseMessage, GetInfoResponse);
+    impl_into_kaspad_response!(
+        rpc_core::EstimateNetworkHashesPerSecondResponse,
+        EstimateNetworkHashesPerSecondResponseMessage,
+        EstimateNetworkHashesPerSecondResponse
+    );
+    impl_into_kaspad_response!(rpc_core::GetCoinSupplyResponse, GetCoinSupplyResponseMessage, GetCoinSupplyResponse);
+    impl_into_kaspad_response!(rpc_core::GetMetricsResponse, GetMetricsResponseMessage, GetMetricsResponse);
 
     impl_into_kaspad_response!(rpc_core::NotifyBlockAddedResponse, NotifyBlockAddedResponseMessage, NotifyBlockAddedResponse);
     impl_into_kaspad_notify_response!(rpc_core::NotifyBlockAddedResponse, NotifyBlockAddedResponseMessage, NotifyBlockAddedResponse);
@@ -159,6 +199,7 @@ pub mod kaspad_response_convert {
             impl From<RpcResult<&$($core_struct)::+>> for KaspadResponse {
                 fn from(item: RpcResult<&$($core_struct)::+>) -> Self {
                     Self {
+                        id: 0,
                         payload: Some(item.into())
                     }
                 }
@@ -173,6 +214,7 @@ pub mod kaspad_response_convert {
             impl From<RpcResult<$($core_struct)::+>> for KaspadResponse {
                 fn from(item: RpcResult<$($core_struct)::+>) -> Self {
                     Self {
+                        id: 0,
                         payload: Some(item.into())
                     }
                 }
@@ -200,6 +242,7 @@ pub mod kaspad_response_convert {
             impl From<$($protowire_struct)::+> for KaspadResponse {
                 fn from(item: $($protowire_struct)::+) -> Self {
                     Self {
+                        id: 0,
                         payload: Some(kaspad_response::Payload::$($variant)::+(item))
                     }
                 }
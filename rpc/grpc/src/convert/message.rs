@@ -1,11 +1,63 @@
-use crate::protowire;
-use rpc_core::{RpcError, RpcHash, RpcResult};
+use crate::protowire::{self, submit_block_response_message::RejectReason};
+use rpc_core::{stubs::NetworkType, RpcError, RpcHash, RpcResult};
 use std::str::FromStr;
 
 // ----------------------------------------------------------------------------
 // rpc_core to protowire
 // ----------------------------------------------------------------------------
 
+impl From<&rpc_core::PingRequest> for protowire::PingRequestMessage {
+    fn from(_item: &rpc_core::PingRequest) -> Self {
+        Self {}
+    }
+}
+
+impl From<RpcResult<&rpc_core::PingResponse>> for protowire::PingResponseMessage {
+    fn from(item: RpcResult<&rpc_core::PingResponse>) -> Self {
+        Self { error: item.err().map(|x| x.into()) }
+    }
+}
+
+impl From<&rpc_core::GetCurrentNetworkRequest> for protowire::GetCurrentNetworkRequestMessage {
+    fn from(_item: &rpc_core::GetCurrentNetworkRequest) -> Self {
+        Self {}
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetCurrentNetworkResponse>> for protowire::GetCurrentNetworkResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetCurrentNetworkResponse>) -> Self {
+        match item {
+            Ok(response) => Self { current_network: response.current_network.to_string(), error: None },
+            Err(err) => Self { current_network: String::default(), error: Some(err.into()) },
+        }
+    }
+}
+
+impl From<&rpc_core::SubmitBlockRequest> for protowire::SubmitBlockRequestMessage {
+    fn from(item: &rpc_core::SubmitBlockRequest) -> Self {
+        Self { block: Some((&item.block).into()), allow_non_daa_blocks: item.allow_non_daa_blocks }
+    }
+}
+
+impl From<&rpc_core::SubmitBlockReport> for RejectReason {
+    fn from(item: &rpc_core::SubmitBlockReport) -> Self {
+        match item {
+            rpc_core::SubmitBlockReport::Success => RejectReason::None,
+            rpc_core::SubmitBlockReport::Reject(rpc_core::SubmitBlockRejectReason::BlockInvalid) => RejectReason::BlockInvalid,
+            rpc_core::SubmitBlockReport::Reject(rpc_core::SubmitBlockRejectReason::IsInIBD) => RejectReason::IsInIbd,
+        }
+    }
+}
+
+impl From<RpcResult<&rpc_core::SubmitBlockResponse>> for protowire::SubmitBlockResponseMessage {
+    fn from(item: RpcResult<&rpc_core::SubmitBlockResponse>) -> Self {
+        Self {
+            reject_reason: item.as_ref().map(|x| RejectReason::from(&x.report)).unwrap_or(RejectReason::None) as i32,
+            error: item.map_err(protowire::RpcError::from).err(),
+        }
+    }
+}
+
 impl From<&rpc_core::GetBlockRequest> for protowire::GetBlockRequestMessage {
     fn from(item: &rpc_core::GetBlockRequest) -> Self {
         Self { hash: item.hash.to_string(), include_transactions: item.include_transactions }
@@ -47,6 +99,18 @@ impl From<RpcResult<&rpc_core::NotifyBlockAddedResponse>> for protowire::NotifyB
     }
 }
 
+impl From<&rpc_core::NotifyUtxosChangedRequest> for protowire::NotifyUtxosChangedRequestMessage {
+    fn from(item: &rpc_core::NotifyUtxosChangedRequest) -> Self {
+        Self { addresses: item.addresses.iter().map(|x| x.to_string()).collect(), command: item.command.into() }
+    }
+}
+
+impl From<RpcResult<&rpc_core::NotifyUtxosChangedResponse>> for protowire::NotifyUtxosChangedResponseMessage {
+    fn from(item: RpcResult<&rpc_core::NotifyUtxosChangedResponse>) -> Self {
+        Self { error: item.map_err(protowire::RpcError::from).err() }
+    }
+}
+
 impl From<&rpc_core::GetInfoRequest> for protowire::GetInfoRequestMessage {
     fn from(_item: &rpc_core::GetInfoRequest) -> Self {
         Self {}
@@ -78,10 +142,154 @@ impl From<RpcResult<&rpc_core::GetInfoResponse>> for protowire::GetInfoResponseM
     }
 }
 
+impl From<&rpc_core::GetSelectedTipHashRequest> for protowire::GetSelectedTipHashRequestMessage {
+    fn from(_item: &rpc_core::GetSelectedTipHashRequest) -> Self {
+        Self {}
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetSelectedTipHashResponse>> for protowire::GetSelectedTipHashResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetSelectedTipHashResponse>) -> Self {
+        match item {
+            Ok(response) => Self { selected_tip_hash: response.selected_tip_hash.to_string(), error: None },
+            Err(err) => Self { selected_tip_hash: String::default(), error: Some(err.into()) },
+        }
+    }
+}
+
+impl From<&rpc_core::ShutdownRequest> for protowire::ShutDownRequestMessage {
+    fn from(_item: &rpc_core::ShutdownRequest) -> Self {
+        Self {}
+    }
+}
+
+impl From<RpcResult<&rpc_core::ShutdownResponse>> for protowire::ShutDownResponseMessage {
+    fn from(item: RpcResult<&rpc_core::ShutdownResponse>) -> Self {
+        Self { error: item.err().map(|x| x.into()) }
+    }
+}
+
+impl From<&rpc_core::EstimateNetworkHashesPerSecondRequest> for protowire::EstimateNetworkHashesPerSecondRequestMessage {
+    fn from(item: &rpc_core::EstimateNetworkHashesPerSecondRequest) -> Self {
+        Self { window_size: item.window_size, start_hash: item.start_hash.map_or(String::default(), |hash| hash.to_string()) }
+    }
+}
+
+impl From<RpcResult<&rpc_core::EstimateNetworkHashesPerSecondResponse>> for protowire::EstimateNetworkHashesPerSecondResponseMessage {
+    fn from(item: RpcResult<&rpc_core::EstimateNetworkHashesPerSecondResponse>) -> Self {
+        match item {
+            Ok(response) => Self { network_hashes_per_second: response.network_hashes_per_second, error: None },
+            Err(err) => Self { network_hashes_per_second: 0, error: Some(err.into()) },
+        }
+    }
+}
+
+impl From<&rpc_core::GetCoinSupplyRequest> for protowire::GetCoinSupplyRequestMessage {
+    fn from(_item: &rpc_core::GetCoinSupplyRequest) -> Self {
+        Self {}
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetCoinSupplyResponse>> for protowire::GetCoinSupplyResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetCoinSupplyResponse>) -> Self {
+        match item {
+            Ok(response) => Self { max_sompi: response.max_sompi, circulating_sompi: response.circulating_sompi, error: None },
+            Err(err) => Self { max_sompi: 0, circulating_sompi: 0, error: Some(err.into()) },
+        }
+    }
+}
+
+impl From<&rpc_core::GetMetricsRequest> for protowire::GetMetricsRequestMessage {
+    fn from(_item: &rpc_core::GetMetricsRequest) -> Self {
+        Self {}
+    }
+}
+
+impl From<RpcResult<&rpc_core::GetMetricsResponse>> for protowire::GetMetricsResponseMessage {
+    fn from(item: RpcResult<&rpc_core::GetMetricsResponse>) -> Self {
+        match item {
+            Ok(response) => Self {
+                server_time: response.server_time,
+                active_connections: response.active_connections,
+                pending_calls: response.pending_calls,
+                ops: response.ops.iter().map(protowire::RpcOpMetrics::from).collect(),
+                notifications: response.notifications.iter().map(protowire::RpcNotificationMetrics::from).collect(),
+                error: None,
+            },
+            Err(err) => Self { error: Some(err.into()), ..Default::default() },
+        }
+    }
+}
+
 // ----------------------------------------------------------------------------
 // protowire to rpc_core
 // ----------------------------------------------------------------------------
 
+impl TryFrom<&protowire::PingRequestMessage> for rpc_core::PingRequest {
+    type Error = RpcError;
+    fn try_from(_item: &protowire::PingRequestMessage) -> RpcResult<Self> {
+        Ok(Self {})
+    }
+}
+
+impl TryFrom<&protowire::PingResponseMessage> for rpc_core::PingResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::PingResponseMessage) -> RpcResult<Self> {
+        item.error.as_ref().map_or(Ok(Self {}), |x| Err(x.into()))
+    }
+}
+
+impl TryFrom<&protowire::GetCurrentNetworkRequestMessage> for rpc_core::GetCurrentNetworkRequest {
+    type Error = RpcError;
+    fn try_from(_item: &protowire::GetCurrentNetworkRequestMessage) -> RpcResult<Self> {
+        Ok(Self {})
+    }
+}
+
+impl TryFrom<&protowire::GetCurrentNetworkResponseMessage> for rpc_core::GetCurrentNetworkResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetCurrentNetworkResponseMessage) -> RpcResult<Self> {
+        match item.error {
+            Some(ref err) => Err(err.into()),
+            None => Ok(Self { current_network: NetworkType::from_str(&item.current_network)? }),
+        }
+    }
+}
+
+impl TryFrom<&protowire::SubmitBlockRequestMessage> for rpc_core::SubmitBlockRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::SubmitBlockRequestMessage) -> RpcResult<Self> {
+        let block = item
+            .block
+            .as_ref()
+            .ok_or_else(|| RpcError::MissingRpcFieldError("SubmitBlockRequestMessage".to_string(), "block".to_string()))?;
+        Ok(Self { block: block.try_into()?, allow_non_daa_blocks: item.allow_non_daa_blocks })
+    }
+}
+
+impl From<RejectReason> for rpc_core::SubmitBlockReport {
+    fn from(item: RejectReason) -> Self {
+        match item {
+            RejectReason::None => rpc_core::SubmitBlockReport::Success,
+            RejectReason::BlockInvalid => rpc_core::SubmitBlockReport::Reject(rpc_core::SubmitBlockRejectReason::BlockInvalid),
+            RejectReason::IsInIbd => rpc_core::SubmitBlockReport::Reject(rpc_core::SubmitBlockRejectReason::IsInIBD),
+        }
+    }
+}
+
+impl TryFrom<&protowire::SubmitBlockResponseMessage> for rpc_core::SubmitBlockResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::SubmitBlockResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            let reject_reason = RejectReason::from_i32(item.reject_reason)
+                .ok_or_else(|| RpcError::General(format!("Invalid reject reason {0}", item.reject_reason)))?;
+            Ok(Self { report: reject_reason.into() })
+        }
+    }
+}
+
 impl TryFrom<&protowire::GetBlockRequestMessage> for rpc_core::GetBlockRequest {
     type Error = RpcError;
     fn try_from(item: &protowire::GetBlockRequestMessage) -> RpcResult<Self> {
@@ -133,6 +341,23 @@ impl TryFrom<&protowire::NotifyBlockAddedResponseMessage> for rpc_core::NotifyBl
     }
 }
 
+impl TryFrom<&protowire::NotifyUtxosChangedRequestMessage> for rpc_core::NotifyUtxosChangedRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::NotifyUtxosChangedRequestMessage) -> RpcResult<Self> {
+        Ok(Self {
+            addresses: item.addresses.iter().map(|x| x.as_str().try_into()).collect::<Result<Vec<_>, _>>()?,
+            command: item.command.into(),
+        })
+    }
+}
+
+impl TryFrom<&protowire::NotifyUtxosChangedResponseMessage> for rpc_core::NotifyUtxosChangedResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::NotifyUtxosChangedResponseMessage) -> RpcResult<Self> {
+        item.error.as_ref().map_or(Ok(rpc_core::NotifyUtxosChangedResponse {}), |x| Err(x.into()))
+    }
+}
+
 impl TryFrom<&protowire::GetInfoRequestMessage> for rpc_core::GetInfoRequest {
     type Error = RpcError;
     fn try_from(_item: &protowire::GetInfoRequestMessage) -> RpcResult<Self> {
@@ -157,3 +382,93 @@ impl TryFrom<&protowire::GetInfoResponseMessage> for rpc_core::GetInfoResponse {
         }
     }
 }
+
+impl TryFrom<&protowire::GetSelectedTipHashRequestMessage> for rpc_core::GetSelectedTipHashRequest {
+    type Error = RpcError;
+    fn try_from(_item: &protowire::GetSelectedTipHashRequestMessage) -> RpcResult<Self> {
+        Ok(Self {})
+    }
+}
+
+impl TryFrom<&protowire::GetSelectedTipHashResponseMessage> for rpc_core::GetSelectedTipHashResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetSelectedTipHashResponseMessage) -> RpcResult<Self> {
+        match item.error {
+            Some(ref err) => Err(err.into()),
+            None => Ok(Self { selected_tip_hash: RpcHash::from_str(&item.selected_tip_hash)? }),
+        }
+    }
+}
+
+impl TryFrom<&protowire::ShutDownRequestMessage> for rpc_core::ShutdownRequest {
+    type Error = RpcError;
+    fn try_from(_item: &protowire::ShutDownRequestMessage) -> RpcResult<Self> {
+        Ok(Self {})
+    }
+}
+
+impl TryFrom<&protowire::ShutDownResponseMessage> for rpc_core::ShutdownResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::ShutDownResponseMessage) -> RpcResult<Self> {
+        item.error.as_ref().map_or(Ok(Self {}), |x| Err(x.into()))
+    }
+}
+
+impl TryFrom<&protowire::EstimateNetworkHashesPerSecondRequestMessage> for rpc_core::EstimateNetworkHashesPerSecondRequest {
+    type Error = RpcError;
+    fn try_from(item: &protowire::EstimateNetworkHashesPerSecondRequestMessage) -> RpcResult<Self> {
+        // An empty start hash stands for the virtual selected parent
+        let start_hash = match item.start_hash.is_empty() {
+            true => None,
+            false => Some(RpcHash::from_str(&item.start_hash)?),
+        };
+        Ok(Self { window_size: item.window_size, start_hash })
+    }
+}
+
+impl TryFrom<&protowire::EstimateNetworkHashesPerSecondResponseMessage> for rpc_core::EstimateNetworkHashesPerSecondResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::EstimateNetworkHashesPerSecondResponseMessage) -> RpcResult<Self> {
+        item.error.as_ref().map_or(Ok(Self { network_hashes_per_second: item.network_hashes_per_second }), |x| Err(x.into()))
+    }
+}
+
+impl TryFrom<&protowire::GetCoinSupplyRequestMessage> for rpc_core::GetCoinSupplyRequest {
+    type Error = RpcError;
+    fn try_from(_item: &protowire::GetCoinSupplyRequestMessage) -> RpcResult<Self> {
+        Ok(Self {})
+    }
+}
+
+impl TryFrom<&protowire::GetCoinSupplyResponseMessage> for rpc_core::GetCoinSupplyResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetCoinSupplyResponseMessage) -> RpcResult<Self> {
+        item.error
+            .as_ref()
+            .map_or(Ok(Self { max_sompi: item.max_sompi, circulating_sompi: item.circulating_sompi }), |x| Err(x.into()))
+    }
+}
+
+impl TryFrom<&protowire::GetMetricsRequestMessage> for rpc_core::GetMetricsRequest {
+    type Error = RpcError;
+    fn try_from(_item: &protowire::GetMetricsRequestMessage) -> RpcResult<Self> {
+        Ok(Self {})
+    }
+}
+
+impl TryFrom<&protowire::GetMetricsResponseMessage> for rpc_core::GetMetricsResponse {
+    type Error = RpcError;
+    fn try_from(item: &protowire::GetMetricsResponseMessage) -> RpcResult<Self> {
+        if let Some(err) = item.error.as_ref() {
+            Err(err.into())
+        } else {
+            Ok(Self {
+                server_time: item.server_time,
+                active_connections: item.active_connections,
+                pending_calls: item.pending_calls,
+                ops: item.ops.iter().map(rpc_core::RpcOpMetrics::from).collect(),
+                notifications: item.notifications.iter().map(rpc_core::RpcNotificationMetrics::from).collect(),
+            })
+        }
+    }
+}
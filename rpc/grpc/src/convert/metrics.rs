@@ -0,0 +1,55 @@
+use crate::protowire;
+
+// ----------------------------------------------------------------------------
+// rpc_core to protowire
+// ----------------------------------------------------------------------------
+
+impl From<&rpc_core::RpcOpMetrics> for protowire::RpcOpMetrics {
+    fn from(item: &rpc_core::RpcOpMetrics) -> Self {
+        Self {
+            op: item.op.clone(),
+            calls: item.calls,
+            errors: item.errors,
+            latency_sum_micros: item.latency_sum_micros,
+            latency_buckets: item.latency_buckets.clone(),
+        }
+    }
+}
+
+impl From<&rpc_core::RpcNotificationMetrics> for protowire::RpcNotificationMetrics {
+    fn from(item: &rpc_core::RpcNotificationMetrics) -> Self {
+        Self {
+            notifier: item.notifier.clone(),
+            event: item.event.clone(),
+            notifications: item.notifications,
+            queue_depth: item.queue_depth,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// protowire to rpc_core
+// ----------------------------------------------------------------------------
+
+impl From<&protowire::RpcOpMetrics> for rpc_core::RpcOpMetrics {
+    fn from(item: &protowire::RpcOpMetrics) -> Self {
+        Self {
+            op: item.op.clone(),
+            calls: item.calls,
+            errors: item.errors,
+            latency_sum_micros: item.latency_sum_micros,
+            latency_buckets: item.latency_buckets.clone(),
+        }
+    }
+}
+
+impl From<&protowire::RpcNotificationMetrics> for rpc_core::RpcNotificationMetrics {
+    fn from(item: &protowire::RpcNotificationMetrics) -> Self {
+        Self {
+            notifier: item.notifier.clone(),
+            event: item.event.clone(),
+            notifications: item.notifications,
+            queue_depth: item.queue_depth,
+        }
+    }
+}
@@ -4,5 +4,6 @@ pub mod error;
 pub mod header;
 pub mod kaspad;
 pub mod message;
+pub mod metrics;
 pub mod notification;
 pub mod tx;
@@ -8,7 +8,7 @@ use crate::protowire::{kaspad_response::Payload, BlockAddedNotificationMessage,
 
 impl From<&rpc_core::Notification> for KaspadResponse {
     fn from(item: &rpc_core::Notification) -> Self {
-        Self { payload: Some(item.into()) }
+        Self { id: 0, payload: Some(item.into()) }
     }
 }
 
@@ -88,7 +88,7 @@ impl From<&rpc_core::RpcTransactionOutputVerboseData> for protowire::RpcTransact
     fn from(item: &rpc_core::RpcTransactionOutputVerboseData) -> Self {
         Self {
             script_public_key_type: item.script_public_key_type.to_string(),
-            script_public_key_address: item.script_public_key_address.clone(),
+            script_public_key_address: item.script_public_key_address.as_ref().map(|x| x.to_string()).unwrap_or_default(),
         }
     }
 }
@@ -216,7 +216,10 @@ impl TryFrom<&protowire::RpcTransactionOutputVerboseData> for rpc_core::RpcTrans
     fn try_from(item: &protowire::RpcTransactionOutputVerboseData) -> RpcResult<Self> {
         Ok(Self {
             script_public_key_type: item.script_public_key_type.as_str().try_into()?,
-            script_public_key_address: item.script_public_key_address.clone(),
+            script_public_key_address: match item.script_public_key_address.is_empty() {
+                true => None,
+                false => Some(item.script_public_key_address.as_str().try_into()?),
+            },
         })
     }
 }
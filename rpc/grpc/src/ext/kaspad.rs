@@ -1,10 +1,15 @@
 use rpc_core::{api::ops::SubscribeCommand, NotificationType};
 
-use crate::protowire::{kaspad_request, kaspad_response, KaspadRequest, KaspadResponse, NotifyBlockAddedRequestMessage};
+use crate::protowire::{
+    kaspad_request, kaspad_response, EstimateNetworkHashesPerSecondResponseMessage, GetBlockResponseMessage,
+    GetCoinSupplyResponseMessage, GetCurrentNetworkResponseMessage, GetInfoResponseMessage, GetMetricsResponseMessage,
+    GetSelectedTipHashResponseMessage, KaspadRequest, KaspadResponse, NotifyBlockAddedRequestMessage, NotifyBlockAddedResponseMessage,
+    PingResponseMessage, RpcError, ShutDownResponseMessage, SubmitBlockResponseMessage,
+};
 
 impl KaspadRequest {
     pub fn from_notification_type(notification_type: &NotificationType, command: SubscribeCommand) -> Self {
-        KaspadRequest { payload: Some(kaspad_request::Payload::from_notification_type(notification_type, command)) }
+        KaspadRequest { id: 0, payload: Some(kaspad_request::Payload::from_notification_type(notification_type, command)) }
     }
 }
 
@@ -30,6 +35,25 @@ impl kaspad_request::Payload {
             // NotificationType::NewBlockTemplate => todo!(),
         }
     }
+
+    /// Builds the response payload matching this request and carrying an error.
+    pub fn error_response(&self, err: rpc_core::RpcError) -> kaspad_response::Payload {
+        match self {
+            kaspad_request::Payload::PingRequest(_) => PingResponseMessage::from(err).into(),
+            kaspad_request::Payload::GetCurrentNetworkRequest(_) => GetCurrentNetworkResponseMessage::from(err).into(),
+            kaspad_request::Payload::SubmitBlockRequest(_) => SubmitBlockResponseMessage::from(err).into(),
+            kaspad_request::Payload::NotifyBlockAddedRequest(_) => NotifyBlockAddedResponseMessage::from(err).into(),
+            kaspad_request::Payload::GetSelectedTipHashRequest(_) => GetSelectedTipHashResponseMessage::from(err).into(),
+            kaspad_request::Payload::GetBlockRequest(_) => GetBlockResponseMessage::from(err).into(),
+            kaspad_request::Payload::GetInfoRequest(_) => GetInfoResponseMessage::from(err).into(),
+            kaspad_request::Payload::EstimateNetworkHashesPerSecondRequest(_) => {
+                EstimateNetworkHashesPerSecondResponseMessage::from(err).into()
+            }
+            kaspad_request::Payload::GetCoinSupplyRequest(_) => GetCoinSupplyResponseMessage::from(err).into(),
+            kaspad_request::Payload::GetMetricsRequest(_) => GetMetricsResponseMessage::from(err).into(),
+            kaspad_request::Payload::ShutDownRequest(_) => ShutDownResponseMessage::from(err).into(),
+        }
+    }
 }
 
 impl KaspadResponse {
@@ -39,6 +63,11 @@ impl KaspadResponse {
             None => false,
         }
     }
+
+    /// Returns the error carried by the response payload, if any.
+    pub fn error(&self) -> Option<&RpcError> {
+        self.payload.as_ref().and_then(|payload| payload.error())
+    }
 }
 
 #[allow(clippy::match_like_matches_macro)]
@@ -49,4 +78,21 @@ impl kaspad_response::Payload {
             _ => false,
         }
     }
+
+    pub fn error(&self) -> Option<&RpcError> {
+        match self {
+            kaspad_response::Payload::PingResponse(response) => response.error.as_ref(),
+            kaspad_response::Payload::GetCurrentNetworkResponse(response) => response.error.as_ref(),
+            kaspad_response::Payload::SubmitBlockResponse(response) => response.error.as_ref(),
+            kaspad_response::Payload::NotifyBlockAddedResponse(response) => response.error.as_ref(),
+            kaspad_response::Payload::GetSelectedTipHashResponse(response) => response.error.as_ref(),
+            kaspad_response::Payload::GetBlockResponse(response) => response.error.as_ref(),
+            kaspad_response::Payload::GetInfoResponse(response) => response.error.as_ref(),
+            kaspad_response::Payload::EstimateNetworkHashesPerSecondResponse(response) => response.error.as_ref(),
+            kaspad_response::Payload::GetCoinSupplyResponse(response) => response.error.as_ref(),
+            kaspad_response::Payload::GetMetricsResponse(response) => response.error.as_ref(),
+            kaspad_response::Payload::ShutDownResponse(response) => response.error.as_ref(),
+            kaspad_response::Payload::BlockAddedNotification(_) => None,
+        }
+    }
 }
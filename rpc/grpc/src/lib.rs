@@ -1,8 +1,10 @@
 #![recursion_limit = "256"]
 
-#[allow(clippy::derive_partial_eq_without_eq)]
+#[allow(clippy::derive_partial_eq_without_eq, clippy::large_enum_variant)]
 pub mod protowire {
     tonic::include_proto!("protowire");
+
+    pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("protowire_descriptor");
 }
 
 pub mod client;
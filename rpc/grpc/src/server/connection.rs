@@ -1,41 +1,112 @@
+use super::errors::Error;
 use crate::{protowire::KaspadResponse, server::StatusResult};
 use futures::pin_mut;
-use kaspa_utils::triggers::DuplexTrigger;
-use rpc_core::notify::{
-    listener::{ListenerID, ListenerReceiverSide},
-    notifier::Notifier,
+use kaspa_core::{debug, error, info, trace, warn};
+use kaspa_utils::triggers::{DuplexTrigger, SingleTrigger};
+use rpc_core::{
+    metrics::Metrics,
+    notify::{
+        listener::{ListenerID, ListenerMetadata, ListenerReceiverSide, ListenerTransport},
+        notifier::Notifier,
+    },
 };
 use std::{
-    collections::HashMap,
-    net::SocketAddr,
+    collections::{HashMap, HashSet},
+    fmt::{Display, Formatter},
+    net::{IpAddr, SocketAddr},
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc,
     },
+    time::{SystemTime, UNIX_EPOCH},
 };
 use tokio::sync::mpsc;
 
 pub type GrpcSender = mpsc::Sender<StatusResult<KaspadResponse>>;
 
+/// Unique identifier of a gRPC connection, several connections possibly sharing the same remote address
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct ConnectionId(u64);
+
+impl ConnectionId {
+    pub fn new(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+impl From<u64> for ConnectionId {
+    fn from(item: u64) -> Self {
+        Self(item)
+    }
+}
+
+impl From<ConnectionId> for u64 {
+    fn from(item: ConnectionId) -> Self {
+        item.0
+    }
+}
+
+impl Display for ConnectionId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Descriptive data about a gRPC connection
+#[derive(Clone, Debug)]
+pub struct ConnectionInfo {
+    pub id: ConnectionId,
+    pub address: SocketAddr,
+    pub listener_id: ListenerID,
+
+    /// Connection time in milliseconds since the Unix epoch
+    pub connected: u64,
+}
+
 pub(crate) struct GrpcConnection {
-    _address: SocketAddr,
+    id: ConnectionId,
+    address: SocketAddr,
+    connected: u64,
     sender: GrpcSender,
     notifiy_listener: ListenerReceiverSide,
     collect_shutdown: Arc<DuplexTrigger>,
     collect_is_running: Arc<AtomicBool>,
+
+    /// Triggered when the connection gets closed by the server
+    close: SingleTrigger,
 }
 
 impl GrpcConnection {
-    pub(crate) fn new(address: SocketAddr, sender: GrpcSender, notifiy_listener: ListenerReceiverSide) -> Self {
+    pub(crate) fn new(id: ConnectionId, address: SocketAddr, sender: GrpcSender, notifiy_listener: ListenerReceiverSide) -> Self {
         Self {
-            _address: address,
+            id,
+            address,
+            connected: SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_millis() as u64).unwrap_or_default(),
             sender,
             notifiy_listener,
             collect_shutdown: Arc::new(DuplexTrigger::new()),
             collect_is_running: Arc::new(AtomicBool::new(false)),
+            close: SingleTrigger::new(),
         }
     }
 
+    pub(crate) fn id(&self) -> ConnectionId {
+        self.id
+    }
+
+    pub(crate) fn listener_id(&self) -> ListenerID {
+        self.notifiy_listener.id
+    }
+
+    /// Returns a listener resolving once the connection is closed by the server.
+    pub(crate) fn closed(&self) -> triggered::Listener {
+        self.close.listener.clone()
+    }
+
+    pub(crate) fn info(&self) -> ConnectionInfo {
+        ConnectionInfo { id: self.id, address: self.address, listener_id: self.notifiy_listener.id, connected: self.connected }
+    }
+
     pub(crate) fn start(self: Arc<Self>) {
         self.collect_task();
     }
@@ -44,13 +115,14 @@ impl GrpcConnection {
         match self.sender.send(message).await {
             Ok(_) => {}
             Err(err) => {
-                println!("[send] SendError: to {}, {:?}", self._address, err);
+                warn!("SendError: to {}, {:?}", self.address, err);
                 // TODO: drop this connection
             }
         }
     }
 
     async fn stop(self: Arc<Self>) {
+        self.close.trigger.trigger();
         self.stop_collect().await
     }
 
@@ -59,30 +131,30 @@ impl GrpcConnection {
         let sender = self.sender.clone();
         let collect_shutdown = self.collect_shutdown.clone();
         let collect_is_running = self.collect_is_running.clone();
-        let recv_channel = self.notifiy_listener.recv_channel.clone();
+        let notifiy_listener = self.notifiy_listener.clone();
         collect_is_running.store(true, Ordering::SeqCst);
 
         tokio::task::spawn(async move {
-            println!("[GrpcConnection] collect_task listener id {0}: start", listener_id);
+            debug!("collect_task listener id {0}: start", listener_id);
             loop {
                 let shutdown = collect_shutdown.request.listener.clone();
                 pin_mut!(shutdown);
 
                 tokio::select! {
                     _ = shutdown => { break; }
-                    notification = recv_channel.recv() => {
+                    notification = notifiy_listener.recv() => {
                         match notification {
                             Ok(notification) => {
-                                println!("[GrpcConnection] collect_task listener id {0}: notification", listener_id);
+                                trace!("collect_task listener id {0}: notification", listener_id);
                                 match sender.send(Ok((&*notification).into())).await {
                                     Ok(_) => (),
                                     Err(err) => {
-                                        println!("[Connection] notification sender error: {:?}", err);
+                                        warn!("notification sender error: {:?}", err);
                                     },
                                 }
                             },
                             Err(err) => {
-                                println!("[Connection] notification receiver error: {:?}", err);
+                                error!("notification receiver error: {:?}", err);
                             }
                         }
                     }
@@ -90,7 +162,7 @@ impl GrpcConnection {
             }
             collect_is_running.store(false, Ordering::SeqCst);
             collect_shutdown.response.trigger.trigger();
-            println!("[GrpcConnection] collect_task listener id {0}: stop", listener_id);
+            debug!("collect_task listener id {0}: stop", listener_id);
         });
     }
 
@@ -103,32 +175,212 @@ impl GrpcConnection {
 }
 
 pub(crate) struct GrpcConnectionManager {
-    connections: HashMap<SocketAddr, Arc<GrpcConnection>>,
+    connections: HashMap<ConnectionId, Arc<GrpcConnection>>,
+    banned: HashSet<IpAddr>,
+    next_id: u64,
     notifier: Arc<Notifier>,
+    metrics: Arc<Metrics>,
+    max_connections: usize,
+
+    /// Number of unary calls in progress, each one counting as a connection
+    unary_calls: Arc<AtomicUsize>,
 }
 
 impl GrpcConnectionManager {
-    pub fn new(notifier: Arc<Notifier>) -> Self {
-        Self { connections: HashMap::new(), notifier }
+    pub fn new(notifier: Arc<Notifier>, metrics: Arc<Metrics>, max_connections: usize) -> Self {
+        Self {
+            connections: HashMap::new(),
+            banned: HashSet::new(),
+            next_id: 1,
+            notifier,
+            metrics,
+            max_connections,
+            unary_calls: Arc::new(AtomicUsize::new(0)),
+        }
     }
 
-    pub(crate) async fn register(&mut self, address: SocketAddr, sender: GrpcSender) -> ListenerID {
-        let notifiy_listener = self.notifier.clone().register_new_listener(None);
-        println!("register a new gRPC connection from: {0} with listener id {1}", address, notifiy_listener.id);
-        let connection = Arc::new(GrpcConnection::new(address, sender, notifiy_listener));
+    /// Checks that a client can be served, whatever the transport: the remote address must not be banned
+    /// and, counting the client in, the connections and unary calls in progress must not exceed the maximum.
+    ///
+    /// Returns the number of unary calls in progress the client would be added to.
+    fn admit(&self, ip: IpAddr) -> Result<usize, Error> {
+        if self.banned.contains(&ip) {
+            return Err(Error::AddressBanned(ip));
+        }
+        let unary_calls = self.unary_calls.load(Ordering::SeqCst);
+        if self.connections.len() + unary_calls >= self.max_connections {
+            return Err(Error::MaxConnectionsReached);
+        }
+        Ok(unary_calls)
+    }
 
-        // A pre-existing connection with same address is ignored here
-        // TODO: see if some close pattern can be applied to the replaced connection
-        self.connections.insert(address, connection.clone());
+    /// Registers a new connection under a fresh id.
+    ///
+    /// Fails if the client is not admitted, see [`GrpcConnectionManager::admit`].
+    pub(crate) async fn register(&mut self, address: SocketAddr, sender: GrpcSender) -> Result<Arc<GrpcConnection>, Error> {
+        self.admit(address.ip())?;
+
+        let id = ConnectionId::new(self.next_id);
+        self.next_id += 1;
+        let metadata = ListenerMetadata::new("gRPC connection", ListenerTransport::Grpc, Some(address));
+        let notifiy_listener = self.notifier.clone().register_new_listener_with_metadata(None, metadata);
+        info!("register a new gRPC connection {0} from: {1} with listener id {2}", id, address, notifiy_listener.id);
+        let connection = Arc::new(GrpcConnection::new(id, address, sender, notifiy_listener));
+
+        // Ids are never reused
+        debug_assert!(!self.connections.contains_key(&id));
+        self.connections.insert(id, connection.clone());
+        self.metrics.active_connections().set(self.connections.len() as i64);
         connection.clone().start();
-        connection.notifiy_listener.id
+        Ok(connection)
     }
 
-    pub(crate) async fn unregister(&mut self, address: SocketAddr) {
-        println!("dismiss a gRPC connection from: {}", address);
-        if let Some(connection) = self.connections.remove(&address) {
-            //connection.sender.closed().await;
-            connection.stop().await;
+    /// Starts a unary call, lasting until the returned guard gets dropped.
+    ///
+    /// Fails if the client is not admitted, see [`GrpcConnectionManager::admit`].
+    pub(crate) fn start_unary_call(&self, ip: IpAddr) -> Result<UnaryCallGuard, Error> {
+        // Concurrent unary calls only hold a read lock on the manager so the counter is incremented
+        // only if it did not change since the admission
+        loop {
+            let unary_calls = self.admit(ip)?;
+            if self.unary_calls.compare_exchange(unary_calls, unary_calls + 1, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+                return Ok(UnaryCallGuard { unary_calls: self.unary_calls.clone() });
+            }
         }
     }
+
+    pub(crate) async fn unregister(&mut self, id: ConnectionId) {
+        if let Some(connection) = self.connections.remove(&id) {
+            info!("dismiss a gRPC connection {0} from: {1}", id, connection.address);
+            self.metrics.active_connections().set(self.connections.len() as i64);
+            self.close(connection).await;
+        }
+    }
+
+    /// Stops all connections, eventually closing their streams.
+    pub(crate) async fn unregister_all(&mut self) {
+        let connections: Vec<Arc<GrpcConnection>> = self.connections.drain().map(|(_, connection)| connection).collect();
+        self.metrics.active_connections().set(0);
+        for connection in connections {
+            info!("dismiss a gRPC connection {0} from: {1}", connection.id, connection.address);
+            self.close(connection).await;
+        }
+    }
+
+    pub(crate) fn connections(&self) -> Vec<ConnectionInfo> {
+        let mut connections: Vec<ConnectionInfo> = self.connections.values().map(|connection| connection.info()).collect();
+        connections.sort_by_key(|info| info.id);
+        connections
+    }
+
+    /// Closes a connection, sending a status error to the client first. Returns false if the connection is unknown.
+    pub(crate) async fn kick(&mut self, id: ConnectionId) -> bool {
+        match self.connections.get(&id) {
+            Some(connection) => {
+                // Best effort, the client may already be gone or its stream saturated
+                let _ = connection.sender.try_send(Err(tonic::Status::aborted("Connection closed by the server")));
+                self.unregister(id).await;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Bans an IP address, closing all its connections and refusing any new one.
+    pub(crate) async fn ban(&mut self, ip: IpAddr) {
+        info!("ban gRPC address {0}", ip);
+        self.banned.insert(ip);
+        let ids: Vec<ConnectionId> = self.connections.values().filter(|x| x.address.ip() == ip).map(|x| x.id).collect();
+        for id in ids {
+            self.kick(id).await;
+        }
+    }
+
+    /// Lifts the ban of an IP address. Returns false if the address was not banned.
+    pub(crate) fn unban(&mut self, ip: IpAddr) -> bool {
+        self.banned.remove(&ip)
+    }
+
+    pub(crate) fn banned(&self) -> Vec<IpAddr> {
+        let mut banned: Vec<IpAddr> = self.banned.iter().copied().collect();
+        banned.sort();
+        banned
+    }
+
+    async fn close(&self, connection: Arc<GrpcConnection>) {
+        let listener_id = connection.notifiy_listener.id;
+        connection.stop().await;
+        if let Err(err) = self.notifier.unregister_listener(listener_id) {
+            warn!("error unregistering listener {0}: {1:?}", listener_id, err);
+        }
+    }
+}
+
+/// A unary call in progress, see [`GrpcConnectionManager::start_unary_call`]
+#[derive(Debug)]
+pub(crate) struct UnaryCallGuard {
+    unary_calls: Arc<AtomicUsize>,
+}
+
+impl Drop for UnaryCallGuard {
+    fn drop(&mut self) {
+        self.unary_calls.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpc_core::notify::listener::SendingChangedUtxo;
+
+    #[tokio::test]
+    async fn test_connection_manager() {
+        let notifier = Arc::new(Notifier::new(None, None, SendingChangedUtxo::All));
+        let metrics = Arc::new(Metrics::new());
+        let mut manager = GrpcConnectionManager::new(notifier, metrics.clone(), 3);
+        let address: SocketAddr = "10.0.0.1:16110".parse().unwrap();
+        let (sender, mut receiver) = mpsc::channel(8);
+
+        // Two streams from the same address get distinct connections
+        let a = manager.register(address, sender.clone()).await.unwrap();
+        let b = manager.register(address, sender.clone()).await.unwrap();
+        assert_ne!(a.id(), b.id());
+        assert_ne!(a.listener_id(), b.listener_id());
+        assert_eq!(manager.connections().len(), 2);
+        assert_eq!(metrics.active_connections().get(), 2);
+
+        // Kicking closes a single connection and notifies the client
+        assert!(manager.kick(a.id()).await);
+        assert!(!manager.kick(a.id()).await);
+        assert!(a.closed().is_triggered());
+        assert!(!b.closed().is_triggered());
+        assert_eq!(receiver.recv().await.unwrap().unwrap_err().code(), tonic::Code::Aborted);
+        assert_eq!(manager.connections().iter().map(|x| x.id).collect::<Vec<_>>(), vec![b.id()]);
+
+        // Banning closes all connections of the address and refuses new ones
+        let other = manager.register("10.0.0.2:16110".parse().unwrap(), sender.clone()).await.unwrap();
+        manager.ban(address.ip()).await;
+        assert!(b.closed().is_triggered());
+        assert!(matches!(manager.register(address, sender.clone()).await, Err(Error::AddressBanned(_))));
+        assert!(matches!(manager.start_unary_call(address.ip()), Err(Error::AddressBanned(_))));
+        assert_eq!(manager.connections().iter().map(|x| x.id).collect::<Vec<_>>(), vec![other.id()]);
+        assert_eq!(manager.banned(), vec![address.ip()]);
+        assert!(manager.unban(address.ip()));
+        assert!(manager.banned().is_empty());
+
+        // The connection cap applies whatever the address
+        manager.register(address, sender.clone()).await.unwrap();
+        manager.register(address, sender.clone()).await.unwrap();
+        assert!(matches!(manager.register(address, sender.clone()).await, Err(Error::MaxConnectionsReached)));
+        assert!(matches!(manager.start_unary_call(address.ip()), Err(Error::MaxConnectionsReached)));
+
+        // Unary calls in progress count as connections
+        manager.unregister_all().await;
+        let call = manager.start_unary_call(address.ip()).unwrap();
+        manager.register(address, sender.clone()).await.unwrap();
+        manager.register(address, sender.clone()).await.unwrap();
+        assert!(matches!(manager.register(address, sender.clone()).await, Err(Error::MaxConnectionsReached)));
+        drop(call);
+        manager.register(address, sender).await.unwrap();
+    }
 }
@@ -0,0 +1,40 @@
+use rpc_core::RpcError;
+use std::net::IpAddr;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("gRPC server transport error: {0}")]
+    TransportError(#[from] tonic::transport::Error),
+
+    #[error("RPC error: {0}")]
+    RpcError(#[from] RpcError),
+
+    #[error("Reflection service error: {0}")]
+    ReflectionError(#[from] tonic_reflection::server::Error),
+
+    #[error("Server task error: {0}")]
+    JoinError(#[from] tokio::task::JoinError),
+
+    #[error("Maximum number of connections reached")]
+    MaxConnectionsReached,
+
+    #[error("Address {0} is banned")]
+    AddressBanned(IpAddr),
+}
+
+impl From<Error> for RpcError {
+    fn from(value: Error) -> Self {
+        RpcError::General(value.to_string())
+    }
+}
+
+impl From<Error> for tonic::Status {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::AddressBanned(_) => tonic::Status::permission_denied(value.to_string()),
+            Error::MaxConnectionsReached => tonic::Status::resource_exhausted(value.to_string()),
+            _ => tonic::Status::internal(value.to_string()),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use super::{service::RpcService, unary::RpcUnaryService};
+use crate::protowire::{rpc_server::RpcServer, rpc_unary_server::RpcUnaryServer};
+use kaspa_core::{info, warn};
+use rpc_core::{api::rpc::RpcApi as RpcApiT, server::service::RpcApi, GetInfoRequest};
+use std::{sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+use tonic_health::{server::HealthReporter, ServingStatus};
+
+/// Delay between two evaluations of the server health
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Evaluates the health of the core service.
+///
+/// The server is serving if the core notifier and its consensus collector are running and,
+/// when `requires_sync` is set, if the node reports being synced.
+pub(crate) async fn health_status(core_service: &RpcApi, requires_sync: bool) -> ServingStatus {
+    let notifier = core_service.notifier();
+    if !notifier.is_running() || notifier.collector_is_running() == Some(false) {
+        return ServingStatus::NotServing;
+    }
+    if requires_sync {
+        match core_service.get_info(GetInfoRequest {}).await {
+            Ok(info) if info.is_synced => {}
+            Ok(_) => return ServingStatus::NotServing,
+            Err(err) => {
+                warn!("health check: get info error: {0}", err);
+                return ServingStatus::NotServing;
+            }
+        }
+    }
+    ServingStatus::Serving
+}
+
+/// Periodically reports the health of the server, both globally and for the `RPC` and `RPCUnary` services,
+/// until `shutdown` is triggered, after which both are reported as not serving.
+pub(crate) fn health_task(
+    mut reporter: HealthReporter,
+    core_service: Arc<RpcApi>,
+    requires_sync: bool,
+    shutdown: triggered::Listener,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut current = ServingStatus::Unknown;
+        loop {
+            let status = health_status(&core_service, requires_sync).await;
+            if status != current {
+                info!("gRPC server health: {:?}", status);
+                set_status(&mut reporter, status).await;
+                current = status;
+            }
+            tokio::select! {
+                _ = shutdown.clone() => { break; }
+                _ = tokio::time::sleep(HEALTH_CHECK_INTERVAL) => {}
+            }
+        }
+        set_status(&mut reporter, ServingStatus::NotServing).await;
+    })
+}
+
+async fn set_status(reporter: &mut HealthReporter, status: ServingStatus) {
+    reporter.set_service_status("", status).await;
+    match status {
+        ServingStatus::Serving => {
+            reporter.set_serving::<RpcServer<RpcService>>().await;
+            reporter.set_serving::<RpcUnaryServer<RpcUnaryService>>().await;
+        }
+        _ => {
+            reporter.set_not_serving::<RpcServer<RpcService>>().await;
+            reporter.set_not_serving::<RpcUnaryServer<RpcUnaryService>>().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus::simulator::DagSimulator;
+    use kaspa_utils::{channel::Channel, triggers::SingleTrigger};
+
+    #[tokio::test]
+    async fn test_health_status() {
+        let channel = Channel::default();
+        let core_service =
+            RpcApi::new(Arc::new(DagSimulator::new(Default::default()).unwrap()), channel.receiver(), SingleTrigger::new());
+        assert_eq!(health_status(&core_service, false).await, ServingStatus::NotServing);
+
+        core_service.start();
+        assert_eq!(health_status(&core_service, false).await, ServingStatus::Serving);
+        assert_eq!(health_status(&core_service, true).await, ServingStatus::Serving);
+
+        core_service.stop().await.unwrap();
+        assert_eq!(health_status(&core_service, false).await, ServingStatus::NotServing);
+    }
+}
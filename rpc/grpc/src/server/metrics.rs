@@ -0,0 +1,41 @@
+use hyper::{
+    header::CONTENT_TYPE,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use kaspa_core::info;
+use rpc_core::metrics::{prometheus, Metrics};
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+use tokio::task::JoinHandle;
+
+/// Path at which the metrics are served
+pub const METRICS_PATH: &str = "/metrics";
+
+/// Serves the metrics in Prometheus text format over HTTP at [`METRICS_PATH`].
+///
+/// The endpoint is meant to be bound to a local address and scraped by a Prometheus agent.
+/// The server shuts down gracefully once `shutdown` is triggered.
+pub fn run_metrics_server(
+    address: SocketAddr,
+    metrics: Arc<Metrics>,
+    shutdown: triggered::Listener,
+) -> JoinHandle<Result<(), hyper::Error>> {
+    info!("Metrics server listening on: http://{}{}", address, METRICS_PATH);
+
+    let make_service = make_service_fn(move |_| {
+        let metrics = metrics.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |request| handle(request, metrics.clone()))) }
+    });
+
+    tokio::spawn(async move { Server::try_bind(&address)?.serve(make_service).with_graceful_shutdown(shutdown).await })
+}
+
+async fn handle(request: Request<Body>, metrics: Arc<Metrics>) -> Result<Response<Body>, Infallible> {
+    let response = match (request.method(), request.uri().path()) {
+        (&Method::GET, METRICS_PATH) => Response::builder()
+            .header(CONTENT_TYPE, prometheus::CONTENT_TYPE)
+            .body(Body::from(prometheus::encode(&metrics.snapshot()))),
+        _ => Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty()),
+    };
+    Ok(response.unwrap())
+}
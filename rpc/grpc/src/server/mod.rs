@@ -1,29 +1,109 @@
-use crate::protowire::rpc_server::RpcServer;
+use crate::protowire::{rpc_server::RpcServer, rpc_unary_server::RpcUnaryServer, FILE_DESCRIPTOR_SET};
+use kaspa_core::{info, warn};
+use kaspa_utils::triggers::SingleTrigger;
+use result::Result;
 use rpc_core::server::service::RpcApi;
+use service::{RpcService, RpcServiceConfig};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::task::JoinHandle;
 use tonic::codec::CompressionEncoding;
-use tonic::transport::{Error, Server};
+use tonic::transport::Server;
+use unary::RpcUnaryService;
 
 pub mod connection;
+pub mod errors;
+pub mod health;
+pub mod metrics;
+pub mod rate_limit;
+pub mod result;
 pub mod service;
+#[cfg(test)]
+pub(crate) mod testing;
+pub mod unary;
+mod worker;
 
-pub type StatusResult<T> = Result<T, tonic::Status>;
+pub type StatusResult<T> = std::result::Result<T, tonic::Status>;
 
-// see https://hyper.rs/guides/server/graceful-shutdown/
-async fn shutdown_signal() {
-    // Wait for the CTRL+C signal
-    tokio::signal::ctrl_c().await.expect("failed to install CTRL+C signal handler");
+/// Handle of a running gRPC server
+pub struct ServerHandle {
+    service: Arc<RpcService>,
+    serve_shutdown: SingleTrigger,
+    serve_task: JoinHandle<std::result::Result<(), tonic::transport::Error>>,
+    health_task: JoinHandle<()>,
 }
 
-pub fn run_server(address: SocketAddr, core_service: Arc<RpcApi>) -> JoinHandle<Result<(), Error>> {
-    println!("KaspadRPCServer listening on: {}", address);
+impl ServerHandle {
+    pub fn service(&self) -> Arc<RpcService> {
+        self.service.clone()
+    }
 
-    let grpc_service = service::RpcService::new(core_service);
+    /// Gracefully shuts the server down.
+    ///
+    /// Stops accepting connections, lets the requests in flight complete within `deadline`,
+    /// then shuts the service down and closes all the connection streams.
+    pub async fn shutdown(self, deadline: Duration) -> Result<()> {
+        info!("KaspadRPCServer shutting down");
+        self.serve_shutdown.trigger.trigger();
+        self.service.shutdown(deadline).await?;
+
+        // With all streams closed, the transport terminates promptly
+        let mut serve_task = self.serve_task;
+        match tokio::time::timeout(deadline, &mut serve_task).await {
+            Ok(result) => result??,
+            Err(_) => {
+                warn!("KaspadRPCServer transport did not terminate within {:?}, aborting", deadline);
+                serve_task.abort();
+            }
+        }
+        let _ = self.health_task.await;
+        info!("KaspadRPCServer stopped");
+        Ok(())
+    }
+
+    /// Waits for the server to terminate by itself, ie. on a transport error.
+    pub async fn join(self) -> Result<()> {
+        Ok(self.serve_task.await??)
+    }
+}
+
+/// Runs the gRPC server along with the standard `grpc.health.v1.Health` and server reflection services.
+///
+/// Both the streaming `RPC` service and its unary counterpart `RPCUnary` are served.
+pub fn run_server(address: SocketAddr, core_service: Arc<RpcApi>, config: RpcServiceConfig) -> Result<ServerHandle> {
+    info!("KaspadRPCServer listening on: {}", address);
+
+    let reflection_service = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_health::proto::GRPC_HEALTH_V1_FILE_DESCRIPTOR_SET)
+        .build()?;
+
+    let health_requires_sync = config.health_requires_sync;
+    let grpc_service = Arc::new(RpcService::new(core_service.clone(), config));
     grpc_service.start();
 
-    let svc = RpcServer::new(grpc_service).send_compressed(CompressionEncoding::Gzip).accept_compressed(CompressionEncoding::Gzip);
+    let svc = RpcServer::from_arc(grpc_service.clone())
+        .send_compressed(CompressionEncoding::Gzip)
+        .accept_compressed(CompressionEncoding::Gzip);
+    let unary_svc = RpcUnaryServer::new(RpcUnaryService::new(grpc_service.clone()))
+        .send_compressed(CompressionEncoding::Gzip)
+        .accept_compressed(CompressionEncoding::Gzip);
+
+    let serve_shutdown = SingleTrigger::new();
+    let (health_reporter, health_service) = tonic_health::server::health_reporter();
+    let health_task = health::health_task(health_reporter, core_service, health_requires_sync, serve_shutdown.listener.clone());
+
+    let signal = serve_shutdown.listener.clone();
+    let serve_task = tokio::spawn(async move {
+        Server::builder()
+            .add_service(health_service)
+            .add_service(reflection_service)
+            .add_service(svc)
+            .add_service(unary_svc)
+            .serve_with_shutdown(address, signal)
+            .await
+    });
 
-    tokio::spawn(async move { Server::builder().add_service(svc).serve_with_shutdown(address, shutdown_signal()).await })
+    Ok(ServerHandle { service: grpc_service, serve_shutdown, serve_task, health_task })
 }
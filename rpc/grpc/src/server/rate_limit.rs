@@ -0,0 +1,305 @@
+use kaspa_core::warn;
+use rpc_core::api::ops::RpcApiOps;
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Cost class of an RPC operation, each class having its own rate limit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OpClass {
+    /// Queries answered from readily available data
+    Cheap,
+
+    /// Queries requiring significant work or returning large amounts of data
+    Heavy,
+
+    /// Subscription commands for starting/stopping notifications
+    Subscription,
+}
+
+impl From<&RpcApiOps> for OpClass {
+    fn from(op: &RpcApiOps) -> Self {
+        match op {
+            RpcApiOps::SubmitBlock
+            | RpcApiOps::GetBlockTemplate
+            | RpcApiOps::SubmitTransaction
+            | RpcApiOps::GetBlock
+            | RpcApiOps::GetVirtualSelectedParentChainFromBlock
+            | RpcApiOps::GetBlocks
+            | RpcApiOps::GetHeaders
+            | RpcApiOps::GetMempoolEntries
+            | RpcApiOps::GetUtxosByAddresses
+            | RpcApiOps::GetBalancesByAddresses
+            | RpcApiOps::EstimateNetworkHashesPerSecond
+            | RpcApiOps::GetMempoolEntriesByAddresses
+            | RpcApiOps::GetCoinSupply => OpClass::Heavy,
+
+            RpcApiOps::NotifyBlockAdded => OpClass::Subscription,
+
+            _ => OpClass::Cheap,
+        }
+    }
+}
+
+/// Settings of a token bucket
+#[derive(Clone, Copy, Debug)]
+pub struct TokenBucketConfig {
+    /// Maximum number of tokens, ie. the allowed burst of requests
+    pub capacity: u32,
+
+    /// Number of tokens added per second, ie. the sustained rate of requests
+    pub refill_per_second: f64,
+}
+
+impl TokenBucketConfig {
+    pub const fn new(capacity: u32, refill_per_second: f64) -> Self {
+        Self { capacity, refill_per_second }
+    }
+}
+
+/// What the server does when a request exceeds a rate limit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimitAction {
+    /// Reply to the request with an error payload and keep the stream open
+    Reject,
+
+    /// Send an error status and close the stream
+    CloseStream,
+}
+
+/// Rate limits applied to the requests of each remote address
+///
+/// A `None` limit leaves the matching class of operations unlimited.
+#[derive(Clone, Debug)]
+pub struct RateLimitConfig {
+    pub cheap: Option<TokenBucketConfig>,
+    pub heavy: Option<TokenBucketConfig>,
+    pub subscription: Option<TokenBucketConfig>,
+    pub action: RateLimitAction,
+}
+
+impl RateLimitConfig {
+    /// A config without any limit
+    pub fn unlimited() -> Self {
+        Self { cheap: None, heavy: None, subscription: None, action: RateLimitAction::Reject }
+    }
+
+    fn limit(&self, class: OpClass) -> Option<TokenBucketConfig> {
+        match class {
+            OpClass::Cheap => self.cheap,
+            OpClass::Heavy => self.heavy,
+            OpClass::Subscription => self.subscription,
+        }
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            cheap: Some(TokenBucketConfig::new(100, 50.0)),
+            heavy: Some(TokenBucketConfig::new(20, 10.0)),
+            subscription: Some(TokenBucketConfig::new(10, 1.0)),
+            action: RateLimitAction::Reject,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    config: TokenBucketConfig,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(config: TokenBucketConfig, now: Instant) -> Self {
+        Self { config, tokens: config.capacity as f64, last_refill: now }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.config.refill_per_second).min(self.config.capacity as f64);
+        self.last_refill = now;
+    }
+
+    fn try_take(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_full(&self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens + elapsed * self.config.refill_per_second >= self.config.capacity as f64
+    }
+}
+
+/// Number of buckets above which idle buckets get purged
+const PURGE_THRESHOLD: usize = 4096;
+
+/// Minimum delay between two purges
+const PURGE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Minimum delay between two warnings about the rejected requests of a same address
+const REJECTION_LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Token-bucket rate limiter keyed by remote address and operation class
+///
+/// All the connections of a same remote address share the same buckets.
+///
+/// Rejected requests are reported at most once per [`REJECTION_LOG_INTERVAL`] and per address.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<Buckets>,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    map: HashMap<(IpAddr, OpClass), TokenBucket>,
+    rejections: HashMap<IpAddr, Rejections>,
+    last_purge: Instant,
+}
+
+/// Requests of an address rejected since the last warning about it
+#[derive(Debug)]
+struct Rejections {
+    last_log: Instant,
+    count: u64,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: RateLimitConfig) -> Self {
+        Self { config, buckets: Mutex::new(Buckets { map: HashMap::new(), rejections: HashMap::new(), last_purge: Instant::now() }) }
+    }
+
+    pub(crate) fn action(&self) -> RateLimitAction {
+        self.config.action
+    }
+
+    /// Consumes a token for a request and returns false if the rate limit is exceeded.
+    pub(crate) fn check(&self, address: IpAddr, op: &RpcApiOps) -> bool {
+        let now = Instant::now();
+        let allowed = self.check_at(address, op, now);
+        if !allowed {
+            if let Some(count) = self.record_rejection(address, now) {
+                warn!("Rate limit exceeded by {0}: {1} request(s) rejected, last one being {2:?}", address, count, op);
+            }
+        }
+        allowed
+    }
+
+    /// Counts a rejected request and returns the number of requests to report if a warning is due.
+    fn record_rejection(&self, address: IpAddr, now: Instant) -> Option<u64> {
+        let mut buckets = self.buckets.lock().unwrap();
+        match buckets.rejections.get_mut(&address) {
+            Some(rejections) => {
+                rejections.count += 1;
+                if now.saturating_duration_since(rejections.last_log) < REJECTION_LOG_INTERVAL {
+                    return None;
+                }
+                let count = rejections.count;
+                *rejections = Rejections { last_log: now, count: 0 };
+                Some(count)
+            }
+            None => {
+                buckets.rejections.insert(address, Rejections { last_log: now, count: 0 });
+                Some(1)
+            }
+        }
+    }
+
+    fn check_at(&self, address: IpAddr, op: &RpcApiOps, now: Instant) -> bool {
+        let class = OpClass::from(op);
+        let limit = match self.config.limit(class) {
+            Some(limit) => limit,
+            None => return true,
+        };
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.map.len() > PURGE_THRESHOLD && now.saturating_duration_since(buckets.last_purge) > PURGE_INTERVAL {
+            // A full bucket behaves exactly like a brand new one so it can safely be dropped
+            buckets.map.retain(|_, bucket| !bucket.is_full(now));
+            buckets.rejections.retain(|_, rejections| now.saturating_duration_since(rejections.last_log) < REJECTION_LOG_INTERVAL);
+            buckets.last_purge = now;
+        }
+        buckets.map.entry((address, class)).or_insert_with(|| TokenBucket::new(limit, now)).try_take(now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    #[test]
+    fn test_token_bucket() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(TokenBucketConfig::new(2, 4.0), now);
+        assert!(bucket.try_take(now));
+        assert!(bucket.try_take(now));
+        assert!(!bucket.try_take(now));
+        assert!(!bucket.is_full(now));
+
+        // 4 tokens per second, so one token every 250ms
+        assert!(!bucket.try_take(now + Duration::from_millis(200)));
+        assert!(bucket.try_take(now + Duration::from_millis(260)));
+
+        // Refilling never exceeds capacity
+        assert!(bucket.is_full(now + Duration::from_secs(10)));
+        let later = now + Duration::from_secs(10);
+        assert!(bucket.try_take(later));
+        assert!(bucket.try_take(later));
+        assert!(!bucket.try_take(later));
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let config = RateLimitConfig {
+            cheap: None,
+            heavy: Some(TokenBucketConfig::new(1, 1.0)),
+            subscription: Some(TokenBucketConfig::new(1, 1.0)),
+            action: RateLimitAction::Reject,
+        };
+        let limiter = RateLimiter::new(config);
+        let now = Instant::now();
+        let a = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let b = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+        // Unlimited class
+        for _ in 0..100 {
+            assert!(limiter.check_at(a, &RpcApiOps::GetInfo, now));
+        }
+
+        // Classes and addresses have independent buckets
+        assert!(limiter.check_at(a, &RpcApiOps::GetBlock, now));
+        assert!(!limiter.check_at(a, &RpcApiOps::GetBlock, now));
+        assert!(limiter.check_at(a, &RpcApiOps::NotifyBlockAdded, now));
+        assert!(limiter.check_at(b, &RpcApiOps::GetBlock, now));
+        assert!(limiter.check_at(a, &RpcApiOps::GetBlock, now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_rejection_log() {
+        let limiter = RateLimiter::new(RateLimitConfig::default());
+        let now = Instant::now();
+        let a = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let b = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+        // The first rejection is reported right away, the next ones once per interval
+        assert_eq!(limiter.record_rejection(a, now), Some(1));
+        for _ in 0..5 {
+            assert_eq!(limiter.record_rejection(a, now + Duration::from_secs(1)), None);
+        }
+        assert_eq!(limiter.record_rejection(b, now), Some(1));
+        assert_eq!(limiter.record_rejection(a, now + REJECTION_LOG_INTERVAL), Some(6));
+        assert_eq!(limiter.record_rejection(a, now + REJECTION_LOG_INTERVAL), None);
+    }
+}
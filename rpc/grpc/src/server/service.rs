@@ -1,22 +1,31 @@
-use super::connection::{GrpcConnectionManager, GrpcSender};
-use crate::protowire::{
-    kaspad_request::Payload, rpc_server::Rpc, GetBlockResponseMessage, GetInfoResponseMessage, KaspadRequest, KaspadResponse,
-    NotifyBlockAddedResponseMessage,
+use super::{
+    connection::{ConnectionId, ConnectionInfo, GrpcConnection, GrpcConnectionManager, GrpcSender, UnaryCallGuard},
+    errors::Error,
+    rate_limit::{RateLimitAction, RateLimitConfig, RateLimiter},
+    worker::{handle_subscription, Job, WorkerPool},
 };
+use crate::protowire::{rpc_server::Rpc, KaspadRequest, KaspadResponse};
 use crate::server::StatusResult;
 use futures::Stream;
-use rpc_core::notify::channel::NotificationChannel;
-use rpc_core::notify::listener::{ListenerID, ListenerReceiverSide, SendingChangedUtxo};
+use kaspa_core::{debug, info, trace, warn};
+use kaspa_utils::triggers::SingleTrigger;
+use rpc_core::notify::listener::{ListenerMetadata, ListenerReceiverSide, ListenerTransport, SendingChangedUtxo};
 use rpc_core::notify::subscriber::DynSubscriptionManager;
 use rpc_core::notify::subscriber::Subscriber;
 use rpc_core::RpcResult;
 use rpc_core::{
-    api::rpc::RpcApi as RpcApiT,
+    api::{ops::RpcApiOps, rpc::RpcApi as RpcApiT},
     notify::{collector::RpcCoreCollector, events::EVENT_TYPE_ARRAY, notifier::Notifier},
     server::service::RpcApi,
 };
-use std::{io::ErrorKind, net::SocketAddr, pin::Pin, sync::Arc};
-use tokio::sync::{mpsc, RwLock};
+use std::{
+    io::ErrorKind,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::{mpsc, RwLock, Semaphore};
 use tonic::{Request, Response};
 
 /// A protowire RPC service.
@@ -45,47 +54,200 @@ use tonic::{Request, Response};
 ///
 /// _Object is ready for being dropped. Any further usage of it is undefined behaviour._
 ///
-/// #### Further development
+/// [`RpcService::shutdown`] runs the whole sequence from `stop` to `finalize`, letting the requests
+/// in flight complete within a deadline and closing all the connection streams.
+///
+/// #### Request processing
 ///
-/// TODO: implement a queue of requests and a pool of workers preparing and sending back the reponses.
+/// Incoming requests of all connections are queued and processed concurrently by a pool of workers,
+/// see [`RpcServiceConfig`]. A response is sent back as soon as it is ready and carries the id of the
+/// matching request. Subscription commands are the exception: they are executed by the connection
+/// itself, in the order they were sent.
 pub struct RpcService {
     core_service: Arc<RpcApi>,
-    core_channel: NotificationChannel,
     core_listener: Arc<ListenerReceiverSide>,
     connection_manager: Arc<RwLock<GrpcConnectionManager>>,
     notifier: Arc<Notifier>,
+    worker_pool: Arc<WorkerPool>,
+    rate_limiter: Arc<RateLimiter>,
+    config: RpcServiceConfig,
+    shutdown: SingleTrigger,
+}
+
+/// Request processing settings of a [`RpcService`]
+#[derive(Clone, Debug)]
+pub struct RpcServiceConfig {
+    /// Number of workers processing the requests of all connections
+    pub worker_count: usize,
+
+    /// Capacity of the request queue shared by all connections
+    pub request_queue_capacity: usize,
+
+    /// Maximum number of requests of a single connection being queued or processed at once
+    pub max_in_flight_per_connection: usize,
+
+    /// Maximum number of concurrent connections, unary calls in progress included, further attempts being refused
+    pub max_connections: usize,
+
+    /// Request rate limits by remote address
+    pub rate_limit: RateLimitConfig,
+
+    /// Whether the health service reports the server as serving only once the node is synced
+    pub health_requires_sync: bool,
+
+    /// Clients authorized to shut the node down
+    pub shutdown_policy: ShutdownPolicy,
+}
+
+/// Clients authorized to shut the node down through the `Shutdown` operation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShutdownPolicy {
+    /// No client at all
+    Disabled,
+
+    /// Clients connecting from a loopback address only
+    LoopbackOnly,
+
+    /// Any client
+    Any,
+}
+
+impl Default for RpcServiceConfig {
+    fn default() -> Self {
+        Self {
+            worker_count: std::thread::available_parallelism().map_or(4, |x| x.get()),
+            request_queue_capacity: 1024,
+            max_in_flight_per_connection: 16,
+            max_connections: 128,
+            rate_limit: RateLimitConfig::default(),
+            health_requires_sync: true,
+            shutdown_policy: ShutdownPolicy::LoopbackOnly,
+        }
+    }
+}
+
+impl RpcServiceConfig {
+    /// Whether a client at `ip` is authorized to run `op`.
+    pub(crate) fn is_authorized(&self, op: &RpcApiOps, ip: IpAddr) -> bool {
+        match op {
+            RpcApiOps::Shutdown => match self.shutdown_policy {
+                ShutdownPolicy::Disabled => false,
+                ShutdownPolicy::LoopbackOnly => ip.to_canonical().is_loopback(),
+                ShutdownPolicy::Any => true,
+            },
+            _ => true,
+        }
+    }
 }
 
 impl RpcService {
-    pub fn new(core_service: Arc<RpcApi>) -> Self {
+    pub fn new(core_service: Arc<RpcApi>, config: RpcServiceConfig) -> Self {
         // Prepare core objects
-        let core_channel = NotificationChannel::default();
-        let core_listener = Arc::new(core_service.register_new_listener(Some(core_channel.clone())));
+        let metadata = ListenerMetadata::new("gRPC service", ListenerTransport::Internal, None);
+        let core_listener = Arc::new(core_service.notifier().register_new_listener_with_metadata(None, metadata));
 
         // Prepare internals
-        let collector = Arc::new(RpcCoreCollector::new(core_channel.receiver()));
+        let collector = Arc::new(match core_listener.priority_recv_channel {
+            Some(ref priority_recv_channel) => {
+                RpcCoreCollector::with_priority(core_listener.recv_channel.clone(), priority_recv_channel.clone())
+            }
+            None => RpcCoreCollector::new(core_listener.recv_channel.clone()),
+        });
         let subscription_manager: DynSubscriptionManager = core_service.notifier();
         let subscriber = Subscriber::new(subscription_manager, core_listener.id);
         let notifier = Arc::new(Notifier::new(Some(collector), Some(subscriber), SendingChangedUtxo::FilteredByAddress));
-        let connection_manager = Arc::new(RwLock::new(GrpcConnectionManager::new(notifier.clone())));
+        let metrics = core_service.metrics();
+        metrics.register_notifier("grpc", notifier.clone());
+        let connection_manager = Arc::new(RwLock::new(GrpcConnectionManager::new(notifier.clone(), metrics, config.max_connections)));
+        let worker_pool =
+            Arc::new(WorkerPool::new(config.worker_count, config.request_queue_capacity, core_service.clone(), notifier.clone()));
+
+        let rate_limiter = Arc::new(RateLimiter::new(config.rate_limit.clone()));
 
-        Self { core_service, core_channel, core_listener, connection_manager, notifier }
+        Self {
+            core_service,
+            core_listener,
+            connection_manager,
+            notifier,
+            worker_pool,
+            rate_limiter,
+            config,
+            shutdown: SingleTrigger::new(),
+        }
     }
 
     pub fn start(&self) {
         // Start the internal notifier
         self.notifier.clone().start();
+
+        // Start processing requests
+        self.worker_pool.start();
     }
 
-    pub async fn register_connection(&self, address: SocketAddr, sender: GrpcSender) -> ListenerID {
+    /// Registers a new connection, failing if the address is banned or the maximum number of connections is reached.
+    pub(crate) async fn register_connection(&self, address: SocketAddr, sender: GrpcSender) -> Result<Arc<GrpcConnection>, Error> {
         self.connection_manager.write().await.register(address, sender).await
     }
 
-    pub async fn unregister_connection(&self, address: SocketAddr) {
-        self.connection_manager.write().await.unregister(address).await;
+    /// Starts a unary call, failing on the same conditions as [`RpcService::register_connection`].
+    pub(crate) async fn start_unary_call(&self, address: SocketAddr) -> Result<UnaryCallGuard, Error> {
+        self.connection_manager.read().await.start_unary_call(address.ip())
+    }
+
+    pub async fn unregister_connection(&self, id: ConnectionId) {
+        self.connection_manager.write().await.unregister(id).await;
+    }
+
+    /// Lists the active connections.
+    pub async fn connections(&self) -> Vec<ConnectionInfo> {
+        self.connection_manager.read().await.connections()
+    }
+
+    /// Closes a connection. Returns false if no connection has this id.
+    pub async fn kick(&self, id: ConnectionId) -> bool {
+        self.connection_manager.write().await.kick(id).await
+    }
+
+    /// Closes all connections of an IP address and refuses any new one.
+    pub async fn ban(&self, ip: IpAddr) {
+        self.connection_manager.write().await.ban(ip).await;
+    }
+
+    /// Lifts the ban of an IP address. Returns false if the address was not banned.
+    pub async fn unban(&self, ip: IpAddr) -> bool {
+        self.connection_manager.write().await.unban(ip)
+    }
+
+    /// Lists the banned IP addresses.
+    pub async fn banned(&self) -> Vec<IpAddr> {
+        self.connection_manager.read().await.banned()
+    }
+
+    pub fn core_service(&self) -> Arc<RpcApi> {
+        self.core_service.clone()
+    }
+
+    pub fn config(&self) -> &RpcServiceConfig {
+        &self.config
+    }
+
+    pub(crate) fn notifier(&self) -> Arc<Notifier> {
+        self.notifier.clone()
+    }
+
+    pub(crate) fn rate_limiter(&self) -> Arc<RateLimiter> {
+        self.rate_limiter.clone()
+    }
+
+    /// Returns a listener resolving once the service starts shutting down.
+    pub(crate) fn shutdown_listener(&self) -> triggered::Listener {
+        self.shutdown.listener.clone()
     }
 
     pub async fn stop(&self) -> RpcResult<()> {
+        // Process the requests still in queue
+        self.worker_pool.stop().await;
+
         // Unsubscribe from all notification types
         let listener_id = self.core_listener.id;
         for event in EVENT_TYPE_ARRAY.into_iter() {
@@ -100,9 +262,40 @@ impl RpcService {
 
     pub async fn finalize(&self) -> RpcResult<()> {
         self.core_service.unregister_listener(self.core_listener.id).await?;
-        self.core_channel.receiver().close();
+        self.core_listener.close();
         Ok(())
     }
+
+    /// Gracefully shuts the service down.
+    ///
+    /// New connections are refused and the connections stop reading requests. The requests in flight
+    /// are given `deadline` to complete, then the service unsubscribes from the core notifier, stops
+    /// the collectors of all connections and eventually closes their streams.
+    pub async fn shutdown(&self, deadline: Duration) -> RpcResult<()> {
+        if self.shutdown.listener.is_triggered() {
+            return Ok(());
+        }
+        info!("gRPC service: shutting down");
+        self.shutdown.trigger.trigger();
+
+        if !self.worker_pool.stop_with_deadline(deadline).await {
+            warn!("gRPC service: some requests were dropped after the shutdown deadline of {:?}", deadline);
+        }
+
+        // Unsubscribe from all notification types
+        let listener_id = self.core_listener.id;
+        for event in EVENT_TYPE_ARRAY.into_iter() {
+            self.core_service.stop_notify(listener_id, event.into()).await?;
+        }
+
+        // Stop the collectors and drop the senders of all connections, closing their streams
+        self.connection_manager.write().await.unregister_all().await;
+
+        // Stop the internal notifier
+        self.notifier.clone().stop().await?;
+
+        self.finalize().await
+    }
 }
 
 #[tonic::async_trait]
@@ -117,11 +310,20 @@ impl Rpc for RpcService {
             tonic::Status::new(tonic::Code::InvalidArgument, "Incoming connection opening request has no remote address".to_string())
         })?;
 
-        println!("MessageStream from {:?}", remote_addr);
+        debug!("MessageStream from {:?}", remote_addr);
+
+        if self.shutdown.listener.is_triggered() {
+            return Err(tonic::Status::unavailable("Server is shutting down"));
+        }
 
         // External sender and reciever
         let (send_channel, mut recv_channel) = mpsc::channel::<StatusResult<KaspadResponse>>(128);
-        let listener_id = self.register_connection(remote_addr, send_channel.clone()).await;
+        let connection = self.register_connection(remote_addr, send_channel.clone()).await.map_err(|err| {
+            warn!("Refusing connection from {0}: {1}", remote_addr, err);
+            tonic::Status::from(err)
+        })?;
+        let connection_id = connection.id();
+        let listener_id = connection.listener_id();
 
         // Internal related sender and reciever
         let (stream_tx, stream_rx) = mpsc::channel::<StatusResult<KaspadResponse>>(10);
@@ -134,60 +336,90 @@ impl Rpc for RpcService {
                     Ok(_) => {}
                     Err(_) => {
                         // If sending failed, then remove the connection from connection manager
-                        println!("[Remote] stream tx sending error. Remote {:?}", &remote_addr);
-                        connection_manager.write().await.unregister(remote_addr).await;
+                        warn!("stream tx sending error. Remote {:?}", &remote_addr);
+                        connection_manager.write().await.unregister(connection_id).await;
                     }
                 }
             }
         });
 
         // Request handler
+        let worker_pool = self.worker_pool.clone();
         let core_service = self.core_service.clone();
-        let connection_manager = self.connection_manager.clone();
         let notifier = self.notifier.clone();
+        let rate_limiter = self.rate_limiter.clone();
+        let config = self.config.clone();
+        let connection_manager = self.connection_manager.clone();
+        let in_flight = Arc::new(Semaphore::new(self.config.max_in_flight_per_connection.max(1)));
+        let shutdown = self.shutdown.listener.clone();
+        let closed = connection.closed();
+        drop(connection);
         let mut stream: tonic::Streaming<KaspadRequest> = request.into_inner();
         tokio::spawn(async move {
             loop {
-                match stream.message().await {
+                let message = tokio::select! {
+                    _ = shutdown.clone() => {
+                        debug!("Request handler stream {0}: server is shutting down", remote_addr);
+                        break;
+                    }
+                    _ = closed.clone() => {
+                        debug!("Request handler stream {0}: connection {1} closed by the server", remote_addr, connection_id);
+                        break;
+                    }
+                    message = stream.message() => message,
+                };
+                match message {
                     Ok(Some(request)) => {
-                        println!("Request is {:?}", request);
-                        let response: KaspadResponse = match request.payload {
-                            Some(Payload::GetBlockRequest(ref request)) => match request.try_into() {
-                                Ok(request) => core_service.get_block(request).await.into(),
-                                Err(err) => GetBlockResponseMessage::from(err).into(),
-                            },
-
-                            Some(Payload::GetInfoRequest(ref request)) => match request.try_into() {
-                                Ok(request) => core_service.get_info(request).await.into(),
-                                Err(err) => GetInfoResponseMessage::from(err).into(),
-                            },
-
-                            Some(Payload::NotifyBlockAddedRequest(ref request)) => NotifyBlockAddedResponseMessage::from({
-                                let request = rpc_core::NotifyBlockAddedRequest::try_from(request).unwrap();
-                                notifier.clone().execute_notify_command(
-                                    listener_id,
-                                    rpc_core::NotificationType::BlockAdded,
-                                    request.command,
-                                )
-                            })
-                            .into(),
-
-                            // TODO: This must be replaced by actual handling of all request variants
-                            _ => GetBlockResponseMessage::from(rpc_core::RpcError::General(
-                                "Server-side API Not implemented".to_string(),
-                            ))
-                            .into(),
-                        };
-
-                        match send_channel.send(Ok(response)).await {
-                            Ok(_) => {}
-                            Err(err) => {
-                                println!("tx send error: {:?}", err);
+                        trace!("Request is {:?}", request);
+
+                        if let Some(ref payload) = request.payload {
+                            let op = RpcApiOps::from(payload);
+                            if !config.is_authorized(&op, remote_addr.ip()) {
+                                warn!("Request handler stream {0}: unauthorized {1:?} request", remote_addr, op);
+                                let response = KaspadResponse {
+                                    id: request.id,
+                                    payload: Some(payload.error_response(rpc_core::RpcError::Unauthorized(format!("{:?}", op)))),
+                                };
+                                if send_channel.send(Ok(response)).await.is_err() {
+                                    break;
+                                }
+                                continue;
+                            }
+                            if !rate_limiter.check(remote_addr.ip(), &op) {
+                                let response = match rate_limiter.action() {
+                                    RateLimitAction::Reject => Ok(KaspadResponse {
+                                        id: request.id,
+                                        payload: Some(payload.error_response(rpc_core::RpcError::RateLimitExceeded)),
+                                    }),
+                                    RateLimitAction::CloseStream => Err(tonic::Status::resource_exhausted("Rate limit exceeded")),
+                                };
+                                let close = response.is_err();
+                                if send_channel.send(response).await.is_err() || close {
+                                    break;
+                                }
+                                continue;
+                            }
+                        }
+
+                        // Subscription commands are executed in stream order
+                        if let Some(response) = handle_subscription(&core_service, &notifier, listener_id, &request) {
+                            if send_channel.send(Ok(response)).await.is_err() {
+                                break;
                             }
+                            continue;
+                        }
+
+                        // Wait for a free slot in the in-flight limit of this connection.
+                        // The semaphore is never closed so acquiring cannot fail.
+                        let permit = in_flight.clone().acquire_owned().await.unwrap();
+                        let job = Job::new(request, listener_id, send_channel.clone(), permit);
+                        if worker_pool.enqueue(job).await.is_err() {
+                            debug!("Request handler stream {0}: worker pool is stopped", remote_addr);
+                            break;
                         }
                     }
                     Ok(None) => {
-                        println!("Request handler stream {0} got Ok(None). Connection terminated by the server", remote_addr);
+                        debug!("Request handler stream {0} got Ok(None). Connection terminated by the server", remote_addr);
                         break;
                     }
 
@@ -196,7 +428,7 @@ impl Rpc for RpcService {
                             if io_err.kind() == ErrorKind::BrokenPipe {
                                 // here you can handle special case when client
                                 // disconnected in unexpected way
-                                eprintln!("\tRequest handler stream {0} error: client disconnected, broken pipe", remote_addr);
+                                warn!("Request handler stream {0} error: client disconnected, broken pipe", remote_addr);
                                 break;
                             }
                         }
@@ -208,8 +440,8 @@ impl Rpc for RpcService {
                     }
                 }
             }
-            println!("Request handler {0} terminated", remote_addr);
-            connection_manager.write().await.unregister(remote_addr).await;
+            debug!("Request handler {0} terminated", remote_addr);
+            connection_manager.write().await.unregister(connection_id).await;
         });
 
         // Return connection stream
@@ -240,3 +472,132 @@ fn match_for_io_error(err_status: &tonic::Status) -> Option<&std::io::Error> {
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        protowire::{kaspad_request, GetSelectedTipHashRequestMessage, NotifyBlockAddedRequestMessage},
+        server::testing::{SlowConsensus, TestServer},
+    };
+    use consensus::simulator::DagSimulator;
+    use rpc_core::{api::ops::SubscribeCommand, notify::events::EventType};
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    fn config() -> RpcServiceConfig {
+        RpcServiceConfig { worker_count: 8, rate_limit: RateLimitConfig::unlimited(), ..Default::default() }
+    }
+
+    fn notify_block_added(id: u64, command: SubscribeCommand) -> KaspadRequest {
+        let payload = kaspad_request::Payload::NotifyBlockAddedRequest(NotifyBlockAddedRequestMessage { command: command.into() });
+        KaspadRequest { id, payload: Some(payload) }
+    }
+
+    fn get_selected_tip_hash(id: u64) -> KaspadRequest {
+        KaspadRequest { id, payload: Some(kaspad_request::Payload::GetSelectedTipHashRequest(GetSelectedTipHashRequestMessage {})) }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_subscription_ordering() {
+        let server = TestServer::start(Arc::new(DagSimulator::new(Default::default()).unwrap()), config()).await;
+        let service = server.handle.service();
+        let (sender, mut stream) = server.message_stream().await;
+
+        // Whatever the workers, the subscription state of the connection follows the last command sent
+        for last in [SubscribeCommand::Stop, SubscribeCommand::Start] {
+            let commands = (0..64).map(|i| if i % 2 == 0 { SubscribeCommand::Start } else { SubscribeCommand::Stop });
+            for (id, command) in commands.chain(std::iter::once(last)).enumerate() {
+                sender.send(notify_block_added(id as u64, command)).await.unwrap();
+            }
+            for id in 0..65 {
+                let response = stream.message().await.unwrap().unwrap();
+                assert_eq!(response.id, id);
+                assert!(response.error().is_none());
+            }
+            let listener_id = service.connections().await[0].listener_id;
+            let active_events = service.notifier().listener(listener_id).unwrap().active_events;
+            assert_eq!(active_events.contains(&EventType::BlockAdded), matches!(last, SubscribeCommand::Start));
+        }
+
+        drop(sender);
+        server.stop(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_in_flight_limit() {
+        let consensus = Arc::new(SlowConsensus::new(Duration::from_millis(50)));
+        let config = RpcServiceConfig { max_in_flight_per_connection: 2, ..config() };
+        let server = TestServer::start(consensus.clone(), config).await;
+        let (sender, mut stream) = server.message_stream().await;
+
+        // Plenty of idle workers but no more than 2 requests of the connection processed at once
+        for id in 0..8 {
+            sender.send(get_selected_tip_hash(id)).await.unwrap();
+        }
+        let mut ids = Vec::new();
+        for _ in 0..8 {
+            let response = stream.message().await.unwrap().unwrap();
+            assert!(response.error().is_none());
+            ids.push(response.id);
+        }
+        ids.sort();
+        assert_eq!(ids, (0..8).collect::<Vec<_>>());
+        assert_eq!(consensus.max_in_flight(), 2);
+
+        drop(sender);
+        server.stop(Duration::from_secs(1)).await;
+    }
+
+    /// Sends `count` slow requests to a server running `worker_count` workers, shuts it down with `deadline`
+    /// once all the workers are processing a request and returns the ids of the responses received.
+    async fn shutdown_with_requests_in_flight(count: u64, worker_count: usize, deadline: Duration) -> Vec<u64> {
+        let consensus = Arc::new(SlowConsensus::new(Duration::from_millis(200)));
+        let config = RpcServiceConfig { worker_count, ..config() };
+        let server = TestServer::start(consensus.clone(), config).await;
+        let (sender, mut stream) = server.message_stream().await;
+
+        for id in 0..count {
+            sender.send(get_selected_tip_hash(id)).await.unwrap();
+        }
+        while consensus.in_flight() < worker_count {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        server.stop(deadline).await;
+
+        let mut ids = Vec::new();
+        while let Ok(Some(response)) = stream.message().await {
+            assert!(response.error().is_none());
+            ids.push(response.id);
+        }
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_shutdown_deadline() {
+        // The requests in flight complete within the deadline
+        assert_eq!(shutdown_with_requests_in_flight(3, 3, Duration::from_secs(5)).await, vec![0, 1, 2]);
+
+        // Past the deadline the pending requests are dropped, only the consensus call already running may still get answered
+        let ids = shutdown_with_requests_in_flight(3, 1, Duration::from_millis(50)).await;
+        assert!(ids.iter().all(|&id| id == 0), "{ids:?}");
+    }
+
+    #[test]
+    fn test_shutdown_authorization() {
+        let remote = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10));
+        let mut config = RpcServiceConfig::default();
+        assert!(config.is_authorized(&RpcApiOps::Shutdown, IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        assert!(config.is_authorized(&RpcApiOps::Shutdown, IpAddr::V6(Ipv6Addr::LOCALHOST)));
+        assert!(config.is_authorized(&RpcApiOps::Shutdown, IpAddr::V6(Ipv4Addr::LOCALHOST.to_ipv6_mapped())));
+        assert!(!config.is_authorized(&RpcApiOps::Shutdown, remote));
+        assert!(config.is_authorized(&RpcApiOps::GetInfo, remote));
+
+        config.shutdown_policy = ShutdownPolicy::Any;
+        assert!(config.is_authorized(&RpcApiOps::Shutdown, remote));
+
+        config.shutdown_policy = ShutdownPolicy::Disabled;
+        assert!(!config.is_authorized(&RpcApiOps::Shutdown, IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        assert!(config.is_authorized(&RpcApiOps::GetInfo, remote));
+    }
+}
@@ -0,0 +1,195 @@
+//! Helpers running a gRPC server against a simulated consensus in tests
+
+use super::{run_server, service::RpcServiceConfig, ServerHandle};
+use crate::protowire::{
+    kaspad_request, kaspad_response, rpc_client::RpcClient, rpc_unary_client::RpcUnaryClient, KaspadRequest, KaspadResponse,
+};
+use consensus::{notifiy::ConsensusNotificationChannel, simulator::DagSimulator};
+use consensus_core::{
+    api::{ConsensusApi, VirtualState},
+    block::Block,
+    errors::ConsensusResult,
+    ghostdag::GhostdagData,
+    header::Header,
+    networktype::NetworkType,
+    tx::{ScriptPublicKey, TransactionOutpoint, UtxoEntry},
+};
+use hashes::Hash;
+use kaspa_utils::triggers::SingleTrigger;
+use rpc_core::server::service::RpcApi;
+use std::{
+    net::{SocketAddr, TcpListener},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{transport::Channel as TonicChannel, Streaming};
+
+/// A consensus taking `delay` to answer [`ConsensusApi::get_virtual_state`], so the `GetSelectedTipHash`
+/// requests it serves stay in flight for a while
+///
+/// It must run on a multi-threaded runtime.
+#[derive(Debug)]
+pub(crate) struct SlowConsensus {
+    inner: DagSimulator,
+    delay: Duration,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+impl SlowConsensus {
+    pub(crate) fn new(delay: Duration) -> Self {
+        Self {
+            inner: DagSimulator::new(Default::default()).unwrap(),
+            delay,
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    /// Current number of calls to [`ConsensusApi::get_virtual_state`]
+    pub(crate) fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Maximum number of concurrent calls to [`ConsensusApi::get_virtual_state`]
+    pub(crate) fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+impl ConsensusApi for SlowConsensus {
+    fn network_type(&self) -> NetworkType {
+        self.inner.network_type()
+    }
+
+    fn validate_and_insert_block(&self, block: Block) -> ConsensusResult<()> {
+        self.inner.validate_and_insert_block(block)
+    }
+
+    fn get_genesis_hash(&self) -> Hash {
+        self.inner.get_genesis_hash()
+    }
+
+    fn get_block(&self, hash: Hash) -> ConsensusResult<Block> {
+        self.inner.get_block(hash)
+    }
+
+    fn get_header(&self, hash: Hash) -> ConsensusResult<Header> {
+        self.inner.get_header(hash)
+    }
+
+    fn get_ghostdag_data(&self, hash: Hash) -> ConsensusResult<GhostdagData> {
+        self.inner.get_ghostdag_data(hash)
+    }
+
+    fn get_block_children(&self, hash: Hash) -> ConsensusResult<Vec<Hash>> {
+        self.inner.get_block_children(hash)
+    }
+
+    fn is_chain_block(&self, hash: Hash) -> ConsensusResult<bool> {
+        self.inner.is_chain_block(hash)
+    }
+
+    fn get_tips(&self) -> Vec<Hash> {
+        self.inner.get_tips()
+    }
+
+    fn get_virtual_state(&self) -> VirtualState {
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+        // Let the runtime move its other tasks to another thread while this one sleeps
+        tokio::task::block_in_place(|| std::thread::sleep(self.delay));
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.inner.get_virtual_state()
+    }
+
+    fn get_utxo(&self, outpoint: &TransactionOutpoint) -> Option<UtxoEntry> {
+        self.inner.get_utxo(outpoint)
+    }
+
+    fn get_utxos_by_script_public_keys(&self, script_public_keys: &[ScriptPublicKey]) -> Vec<(TransactionOutpoint, UtxoEntry)> {
+        self.inner.get_utxos_by_script_public_keys(script_public_keys)
+    }
+
+    fn get_circulating_supply(&self) -> u64 {
+        self.inner.get_circulating_supply()
+    }
+
+    fn is_synced(&self) -> bool {
+        self.inner.is_synced()
+    }
+}
+
+/// A gRPC server listening on a free loopback port
+pub(crate) struct TestServer {
+    pub(crate) handle: ServerHandle,
+    pub(crate) address: SocketAddr,
+    pub(crate) core_service: Arc<RpcApi>,
+    pub(crate) shutdown: SingleTrigger,
+
+    /// Feeds the core service with consensus notifications, kept open for the lifetime of the server
+    _consensus_channel: ConsensusNotificationChannel,
+}
+
+impl TestServer {
+    pub(crate) async fn start(consensus: Arc<dyn ConsensusApi>, config: RpcServiceConfig) -> Self {
+        let shutdown = SingleTrigger::new();
+        let consensus_channel = ConsensusNotificationChannel::default();
+        let core_service = RpcApi::new(consensus, consensus_channel.receiver(), shutdown.clone());
+        core_service.start();
+        let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let handle = run_server(address, core_service.clone(), config).unwrap();
+        Self { handle, address, core_service, shutdown, _consensus_channel: consensus_channel }
+    }
+
+    /// Shuts the server down then stops the core service, before the consensus channel gets closed.
+    pub(crate) async fn stop(self, deadline: Duration) {
+        self.handle.shutdown(deadline).await.unwrap();
+        self.core_service.stop().await.unwrap();
+    }
+
+    fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Opens a message stream, returning the request sender and the response stream.
+    pub(crate) async fn message_stream(&self) -> (mpsc::Sender<KaspadRequest>, Streaming<KaspadResponse>) {
+        let mut client = connect(|| RpcClient::connect(self.url())).await;
+        let (sender, receiver) = mpsc::channel(128);
+        let stream = client.message_stream(ReceiverStream::new(receiver)).await.unwrap().into_inner();
+        (sender, stream)
+    }
+
+    /// Sends a single request on a new message stream and returns the payload of its response.
+    pub(crate) async fn stream_call(&self, payload: kaspad_request::Payload) -> kaspad_response::Payload {
+        let (sender, mut stream) = self.message_stream().await;
+        sender.send(KaspadRequest { id: 1, payload: Some(payload) }).await.unwrap();
+        let response = stream.message().await.unwrap().unwrap();
+        assert_eq!(response.id, 1);
+        response.payload.unwrap()
+    }
+
+    pub(crate) async fn unary_client(&self) -> RpcUnaryClient<TonicChannel> {
+        connect(|| RpcUnaryClient::connect(self.url())).await
+    }
+}
+
+/// Connects a client, retrying while the server is starting up.
+async fn connect<C, F, Fut>(connect: F) -> C
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = Result<C, tonic::transport::Error>>,
+{
+    for _ in 0..50 {
+        if let Ok(client) = connect().await {
+            return client;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("unable to connect to the test server");
+}
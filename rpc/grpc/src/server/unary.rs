@@ -0,0 +1,457 @@
+use super::{connection::UnaryCallGuard, service::RpcService};
+use crate::protowire::{
+    rpc_unary_server::RpcUnary, BlockAddedNotificationMessage, EstimateNetworkHashesPerSecondRequestMessage,
+    EstimateNetworkHashesPerSecondResponseMessage, GetBlockRequestMessage, GetBlockResponseMessage, GetCoinSupplyRequestMessage,
+    GetCoinSupplyResponseMessage, GetCurrentNetworkRequestMessage, GetCurrentNetworkResponseMessage, GetInfoRequestMessage,
+    GetInfoResponseMessage, GetMetricsRequestMessage, GetMetricsResponseMessage, GetSelectedTipHashRequestMessage,
+    GetSelectedTipHashResponseMessage, PingRequestMessage, PingResponseMessage, ShutDownRequestMessage, ShutDownResponseMessage,
+    SubmitBlockRequestMessage, SubmitBlockResponseMessage, SubscribeBlockAddedRequestMessage,
+};
+use futures::Stream;
+use kaspa_core::{debug, warn};
+use rpc_core::{
+    api::{ops::RpcApiOps, rpc::RpcApi as RpcApiT},
+    notify::listener::{ListenerMetadata, ListenerTransport},
+    Notification, NotificationType, RpcResult,
+};
+use std::{pin::Pin, sync::Arc, time::Instant};
+use tokio::sync::mpsc;
+use tonic::{Request, Response, Status};
+
+/// A protowire RPC service exposing the operations of the core service as unary gRPC methods.
+///
+/// It shares the core service, notifier, admission rules, rate limits and shutdown of the [`RpcService`]
+/// processing the `MessageStream` requests. Each call counts as a connection while in progress.
+pub struct RpcUnaryService {
+    service: Arc<RpcService>,
+}
+
+impl RpcUnaryService {
+    pub fn new(service: Arc<RpcService>) -> Self {
+        Self { service }
+    }
+
+    /// Admits a call like a new connection then applies the authorization rules and the rate limits.
+    async fn admit<T>(&self, request: &Request<T>, op: RpcApiOps) -> Result<UnaryCallGuard, Status> {
+        let address = request.remote_addr().ok_or_else(|| Status::invalid_argument("Incoming request has no remote address"))?;
+        if !self.service.config().is_authorized(&op, address.ip()) {
+            warn!("Unary request from {0}: unauthorized {1:?} request", address, op);
+            return Err(Status::permission_denied(rpc_core::RpcError::Unauthorized(format!("{:?}", op)).to_string()));
+        }
+        let guard = self.service.start_unary_call(address).await.map_err(|err| {
+            debug!("Refusing unary request {0:?} from {1}: {2}", op, address, err);
+            Status::from(err)
+        })?;
+        if !self.service.rate_limiter().check(address.ip(), &op) {
+            return Err(Status::resource_exhausted("Rate limit exceeded"));
+        }
+        Ok(guard)
+    }
+
+    /// Runs a call on the core service, recording its metrics.
+    async fn call<T, F>(&self, op: RpcApiOps, call: F) -> RpcResult<T>
+    where
+        F: std::future::Future<Output = RpcResult<T>>,
+    {
+        let started = Instant::now();
+        let result = call.await;
+        self.service.core_service().metrics().record_call(op, started.elapsed(), result.is_err());
+        result
+    }
+}
+
+#[tonic::async_trait]
+impl RpcUnary for RpcUnaryService {
+    async fn submit_block(&self, request: Request<SubmitBlockRequestMessage>) -> Result<Response<SubmitBlockResponseMessage>, Status> {
+        let _guard = self.admit(&request, RpcApiOps::SubmitBlock).await?;
+        let response = match request.get_ref().try_into() {
+            Ok(request) => self.call(RpcApiOps::SubmitBlock, self.service.core_service().submit_block(request)).await.into(),
+            Err(err) => SubmitBlockResponseMessage::from(err),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn get_block(&self, request: Request<GetBlockRequestMessage>) -> Result<Response<GetBlockResponseMessage>, Status> {
+        let _guard = self.admit(&request, RpcApiOps::GetBlock).await?;
+        let response = match request.get_ref().try_into() {
+            Ok(request) => self.call(RpcApiOps::GetBlock, self.service.core_service().get_block(request)).await.into(),
+            Err(err) => GetBlockResponseMessage::from(err),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn get_info(&self, request: Request<GetInfoRequestMessage>) -> Result<Response<GetInfoResponseMessage>, Status> {
+        let _guard = self.admit(&request, RpcApiOps::GetInfo).await?;
+        let response = match request.get_ref().try_into() {
+            Ok(request) => self.call(RpcApiOps::GetInfo, self.service.core_service().get_info(request)).await.into(),
+            Err(err) => GetInfoResponseMessage::from(err),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn get_metrics(&self, request: Request<GetMetricsRequestMessage>) -> Result<Response<GetMetricsResponseMessage>, Status> {
+        let _guard = self.admit(&request, RpcApiOps::GetMetrics).await?;
+        let response = match request.get_ref().try_into() {
+            Ok(request) => self.call(RpcApiOps::GetMetrics, self.service.core_service().get_metrics(request)).await.into(),
+            Err(err) => GetMetricsResponseMessage::from(err),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn get_coin_supply(
+        &self,
+        request: Request<GetCoinSupplyRequestMessage>,
+    ) -> Result<Response<GetCoinSupplyResponseMessage>, Status> {
+        let _guard = self.admit(&request, RpcApiOps::GetCoinSupply).await?;
+        let response = match request.get_ref().try_into() {
+            Ok(request) => self.call(RpcApiOps::GetCoinSupply, self.service.core_service().get_coin_supply(request)).await.into(),
+            Err(err) => GetCoinSupplyResponseMessage::from(err),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn get_current_network(
+        &self,
+        request: Request<GetCurrentNetworkRequestMessage>,
+    ) -> Result<Response<GetCurrentNetworkResponseMessage>, Status> {
+        let _guard = self.admit(&request, RpcApiOps::GetCurrentNetwork).await?;
+        let response = match request.get_ref().try_into() {
+            Ok(request) => {
+                self.call(RpcApiOps::GetCurrentNetwork, self.service.core_service().get_current_network(request)).await.into()
+            }
+            Err(err) => GetCurrentNetworkResponseMessage::from(err),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn estimate_network_hashes_per_second(
+        &self,
+        request: Request<EstimateNetworkHashesPerSecondRequestMessage>,
+    ) -> Result<Response<EstimateNetworkHashesPerSecondResponseMessage>, Status> {
+        let _guard = self.admit(&request, RpcApiOps::EstimateNetworkHashesPerSecond).await?;
+        let response = match request.get_ref().try_into() {
+            Ok(request) => self
+                .call(
+                    RpcApiOps::EstimateNetworkHashesPerSecond,
+                    self.service.core_service().estimate_network_hashes_per_second(request),
+                )
+                .await
+                .into(),
+            Err(err) => EstimateNetworkHashesPerSecondResponseMessage::from(err),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn get_selected_tip_hash(
+        &self,
+        request: Request<GetSelectedTipHashRequestMessage>,
+    ) -> Result<Response<GetSelectedTipHashResponseMessage>, Status> {
+        let _guard = self.admit(&request, RpcApiOps::GetSelectedTipHash).await?;
+        let response = match request.get_ref().try_into() {
+            Ok(request) => {
+                self.call(RpcApiOps::GetSelectedTipHash, self.service.core_service().get_selected_tip_hash(request)).await.into()
+            }
+            Err(err) => GetSelectedTipHashResponseMessage::from(err),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn ping(&self, request: Request<PingRequestMessage>) -> Result<Response<PingResponseMessage>, Status> {
+        let _guard = self.admit(&request, RpcApiOps::Ping).await?;
+        let response = match request.get_ref().try_into() {
+            Ok(request) => self.call(RpcApiOps::Ping, self.service.core_service().ping(request)).await.into(),
+            Err(err) => PingResponseMessage::from(err),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn shut_down(&self, request: Request<ShutDownRequestMessage>) -> Result<Response<ShutDownResponseMessage>, Status> {
+        let _guard = self.admit(&request, RpcApiOps::Shutdown).await?;
+        let response = match request.get_ref().try_into() {
+            Ok(request) => self.call(RpcApiOps::Shutdown, self.service.core_service().shutdown(request)).await.into(),
+            Err(err) => ShutDownResponseMessage::from(err),
+        };
+        Ok(Response::new(response))
+    }
+
+    type SubscribeBlockAddedStream =
+        Pin<Box<dyn Stream<Item = Result<BlockAddedNotificationMessage, Status>> + Send + Sync + 'static>>;
+
+    async fn subscribe_block_added(
+        &self,
+        request: Request<SubscribeBlockAddedRequestMessage>,
+    ) -> Result<Response<Self::SubscribeBlockAddedStream>, Status> {
+        let guard = self.admit(&request, RpcApiOps::NotifyBlockAdded).await?;
+        let shutdown = self.service.shutdown_listener();
+        if shutdown.is_triggered() {
+            return Err(Status::unavailable("Server is shutting down"));
+        }
+
+        let notifier = self.service.notifier();
+        let metadata = ListenerMetadata::new("gRPC unary subscription", ListenerTransport::Grpc, request.remote_addr());
+        let listener = notifier.register_new_listener_with_metadata(None, metadata);
+        let listener_id = listener.id;
+        notifier.start_notify(listener_id, NotificationType::BlockAdded).map_err(|err| Status::internal(err.to_string()))?;
+        debug!("Unary subscription to block added notifications with listener id {0}", listener_id);
+
+        // The stream ends on shutdown or as soon as the client cancels it, counting as a connection until then
+        let (sender, receiver) = mpsc::channel::<Result<BlockAddedNotificationMessage, Status>>(128);
+        tokio::spawn(async move {
+            let _guard = guard;
+            loop {
+                tokio::select! {
+                    _ = shutdown.clone() => { break; }
+                    _ = sender.closed() => { break; }
+                    notification = listener.recv() => {
+                        match notification {
+                            Ok(notification) => {
+                                if let Notification::BlockAdded(ref notification) = *notification {
+                                    if sender.send(Ok(notification.into())).await.is_err() {
+                                        break;
+                                    }
+                                }
+                            }
+                            Err(err) => {
+                                warn!("Unary subscription listener id {0}: receive error: {1:?}", listener_id, err);
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            debug!("Unary subscription with listener id {0} terminated", listener_id);
+            if let Err(err) = notifier.unregister_listener(listener_id) {
+                warn!("error unregistering listener {0}: {1:?}", listener_id, err);
+            }
+        });
+
+        Ok(Response::new(Box::pin(tokio_stream::wrappers::ReceiverStream::new(receiver))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        protowire::{kaspad_request, kaspad_response, submit_block_response_message::RejectReason},
+        server::{
+            rate_limit::RateLimitConfig,
+            service::{RpcServiceConfig, ShutdownPolicy},
+            testing::TestServer,
+        },
+    };
+    use consensus::simulator::{DagSimulator, SimulatorConfig};
+    use consensus_core::{api::ConsensusApi, block::Block, networktype::NetworkType};
+    use rpc_core::{GetCoinSupplyRequest, GetSelectedTipHashRequest, RpcBlock, SubmitBlockRequest};
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        time::Duration,
+    };
+    use tonic::Code;
+
+    fn config(max_connections: usize) -> RpcServiceConfig {
+        RpcServiceConfig { max_connections, rate_limit: RateLimitConfig::unlimited(), ..Default::default() }
+    }
+
+    /// Retries a unary `GetInfo` until it succeeds, the server releasing the connection slots asynchronously.
+    async fn wait_get_info_ok(server: &TestServer) {
+        let mut client = server.unary_client().await;
+        for _ in 0..50 {
+            if client.get_info(GetInfoRequestMessage {}).await.is_ok() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("GetInfo keeps being refused");
+    }
+
+    #[tokio::test]
+    async fn test_banned_address() {
+        let server = TestServer::start(Arc::new(DagSimulator::new(Default::default()).unwrap()), config(8)).await;
+        let service = server.handle.service();
+        let mut client = server.unary_client().await;
+        let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        service.ban(localhost).await;
+        let status = client.get_info(GetInfoRequestMessage {}).await.unwrap_err();
+        assert_eq!(status.code(), Code::PermissionDenied);
+        let status = client.subscribe_block_added(SubscribeBlockAddedRequestMessage {}).await.unwrap_err();
+        assert_eq!(status.code(), Code::PermissionDenied);
+
+        assert!(service.unban(localhost).await);
+        assert!(client.get_info(GetInfoRequestMessage {}).await.is_ok());
+
+        server.stop(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test]
+    async fn test_max_connections() {
+        let server = TestServer::start(Arc::new(DagSimulator::new(Default::default()).unwrap()), config(1)).await;
+        let mut client = server.unary_client().await;
+
+        // A message stream takes the only connection slot
+        let (sender, stream) = server.message_stream().await;
+        let status = client.get_info(GetInfoRequestMessage {}).await.unwrap_err();
+        assert_eq!(status.code(), Code::ResourceExhausted);
+        drop(sender);
+        drop(stream);
+        wait_get_info_ok(&server).await;
+
+        // So does a unary subscription, for as long as it lasts
+        let subscription = client.subscribe_block_added(SubscribeBlockAddedRequestMessage {}).await.unwrap().into_inner();
+        let status = client.get_info(GetInfoRequestMessage {}).await.unwrap_err();
+        assert_eq!(status.code(), Code::ResourceExhausted);
+        drop(subscription);
+        wait_get_info_ok(&server).await;
+
+        server.stop(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test]
+    async fn test_get_coin_supply() {
+        let server = TestServer::start(Arc::new(DagSimulator::new(Default::default()).unwrap()), config(8)).await;
+        let expected: GetCoinSupplyResponseMessage = server.core_service.get_coin_supply(GetCoinSupplyRequest {}).await.into();
+        assert!(expected.error.is_none());
+
+        let response = server.unary_client().await.get_coin_supply(GetCoinSupplyRequestMessage {}).await.unwrap().into_inner();
+        assert_eq!(response, expected);
+        match server.stream_call(kaspad_request::Payload::GetCoinSupplyRequest(GetCoinSupplyRequestMessage {})).await {
+            kaspad_response::Payload::GetCoinSupplyResponse(response) => assert_eq!(response, expected),
+            payload => panic!("unexpected response {payload:?}"),
+        }
+
+        server.stop(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test]
+    async fn test_get_current_network() {
+        let consensus = DagSimulator::new(SimulatorConfig { network_type: NetworkType::Testnet, ..Default::default() }).unwrap();
+        let server = TestServer::start(Arc::new(consensus), config(8)).await;
+        let expected = NetworkType::Testnet.to_string();
+
+        let response = server.unary_client().await.get_current_network(GetCurrentNetworkRequestMessage {}).await.unwrap().into_inner();
+        assert!(response.error.is_none());
+        assert_eq!(response.current_network, expected);
+        match server.stream_call(kaspad_request::Payload::GetCurrentNetworkRequest(GetCurrentNetworkRequestMessage {})).await {
+            kaspad_response::Payload::GetCurrentNetworkResponse(response) => assert_eq!(response.current_network, expected),
+            payload => panic!("unexpected response {payload:?}"),
+        }
+
+        server.stop(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test]
+    async fn test_ping_and_get_selected_tip_hash() {
+        let server = TestServer::start(Arc::new(DagSimulator::new(Default::default()).unwrap()), config(8)).await;
+        let mut client = server.unary_client().await;
+
+        assert!(client.ping(PingRequestMessage {}).await.unwrap().into_inner().error.is_none());
+        match server.stream_call(kaspad_request::Payload::PingRequest(PingRequestMessage {})).await {
+            kaspad_response::Payload::PingResponse(response) => assert!(response.error.is_none()),
+            payload => panic!("unexpected response {payload:?}"),
+        }
+
+        let expected: GetSelectedTipHashResponseMessage =
+            server.core_service.get_selected_tip_hash(GetSelectedTipHashRequest {}).await.into();
+        assert!(expected.error.is_none());
+        assert_eq!(client.get_selected_tip_hash(GetSelectedTipHashRequestMessage {}).await.unwrap().into_inner(), expected);
+        match server.stream_call(kaspad_request::Payload::GetSelectedTipHashRequest(GetSelectedTipHashRequestMessage {})).await {
+            kaspad_response::Payload::GetSelectedTipHashResponse(response) => assert_eq!(response, expected),
+            payload => panic!("unexpected response {payload:?}"),
+        }
+
+        server.stop(Duration::from_secs(1)).await;
+    }
+
+    async fn start_server(shutdown_policy: ShutdownPolicy) -> TestServer {
+        TestServer::start(Arc::new(DagSimulator::new(Default::default()).unwrap()), RpcServiceConfig { shutdown_policy, ..config(8) })
+            .await
+    }
+
+    /// Stops the server the way the node does once a shutdown is requested and checks it is no longer reachable.
+    async fn assert_shutdown_requested(server: TestServer) {
+        let mut client = server.unary_client().await;
+        tokio::time::timeout(Duration::from_secs(1), server.shutdown.listener.clone()).await.expect("a shutdown should be requested");
+        server.stop(Duration::from_secs(1)).await;
+        assert!(client.ping(PingRequestMessage {}).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown() {
+        // Unauthorized requests are rejected on both transports
+        let server = start_server(ShutdownPolicy::Disabled).await;
+        let status = server.unary_client().await.shut_down(ShutDownRequestMessage {}).await.unwrap_err();
+        assert_eq!(status.code(), Code::PermissionDenied);
+        match server.stream_call(kaspad_request::Payload::ShutDownRequest(ShutDownRequestMessage {})).await {
+            kaspad_response::Payload::ShutDownResponse(response) => assert!(response.error.is_some()),
+            payload => panic!("unexpected response {payload:?}"),
+        }
+        assert!(!server.shutdown.listener.is_triggered());
+        server.stop(Duration::from_secs(1)).await;
+
+        // An authorized request stops the server, whatever the transport
+        let server = start_server(ShutdownPolicy::LoopbackOnly).await;
+        let response = server.unary_client().await.shut_down(ShutDownRequestMessage {}).await.unwrap().into_inner();
+        assert!(response.error.is_none());
+        assert_shutdown_requested(server).await;
+
+        let server = start_server(ShutdownPolicy::LoopbackOnly).await;
+        match server.stream_call(kaspad_request::Payload::ShutDownRequest(ShutDownRequestMessage {})).await {
+            kaspad_response::Payload::ShutDownResponse(response) => assert!(response.error.is_none()),
+            payload => panic!("unexpected response {payload:?}"),
+        }
+        assert_shutdown_requested(server).await;
+    }
+
+    #[tokio::test]
+    async fn test_estimate_network_hashes_per_second() {
+        // A block per second, each of 2 expected hashes
+        let consensus = DagSimulator::new(SimulatorConfig { parallelism: 1, ..Default::default() }).unwrap();
+        (0..10).for_each(|_| drop(consensus.produce_round()));
+        let server = TestServer::start(Arc::new(consensus), config(8)).await;
+        let mut client = server.unary_client().await;
+        let request = |window_size| EstimateNetworkHashesPerSecondRequestMessage { window_size, start_hash: String::default() };
+
+        let response = client.estimate_network_hashes_per_second(request(5)).await.unwrap().into_inner();
+        assert!(response.error.is_none());
+        assert_eq!(response.network_hashes_per_second, 2);
+
+        // Too small a window is an error of the operation, not of the call
+        let response = client.estimate_network_hashes_per_second(request(1)).await.unwrap().into_inner();
+        assert!(response.error.unwrap().message.contains("window size 1 is too small"));
+        assert_eq!(response.network_hashes_per_second, 0);
+
+        server.stop(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test]
+    async fn test_submit_block() {
+        // Blocks mined by an identical simulator are valid on the served one
+        let simulator_config = SimulatorConfig { parallelism: 1, ..Default::default() };
+        let miner = DagSimulator::new(simulator_config.clone()).unwrap();
+        let server = TestServer::start(Arc::new(DagSimulator::new(simulator_config).unwrap()), config(8)).await;
+        let request = |round: Vec<Block>| {
+            let block = round.into_iter().next().unwrap();
+            let context = miner.get_block_dag_context(block.hash()).unwrap();
+            let block = RpcBlock::from_block(&block, &context, true, miner.network_type().into());
+            SubmitBlockRequestMessage::from(&SubmitBlockRequest { block, allow_non_daa_blocks: false })
+        };
+        let first = request(miner.produce_round());
+        let second = request(miner.produce_round());
+        let mut client = server.unary_client().await;
+
+        let response = client.submit_block(first.clone()).await.unwrap().into_inner();
+        assert!(response.error.is_none());
+        assert_eq!(response.reject_reason(), RejectReason::None);
+        match server.stream_call(kaspad_request::Payload::SubmitBlockRequest(second)).await {
+            kaspad_response::Payload::SubmitBlockResponse(response) => assert_eq!(response.reject_reason(), RejectReason::None),
+            payload => panic!("unexpected response {payload:?}"),
+        }
+
+        // A block already in the DAG is rejected
+        let response = client.submit_block(first).await.unwrap().into_inner();
+        assert_eq!(response.reject_reason(), RejectReason::BlockInvalid);
+
+        server.stop(Duration::from_secs(1)).await;
+    }
+}
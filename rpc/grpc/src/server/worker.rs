@@ -0,0 +1,223 @@
+use super::connection::GrpcSender;
+use crate::protowire::{
+    kaspad_request::Payload, EstimateNetworkHashesPerSecondResponseMessage, GetBlockResponseMessage, GetCoinSupplyResponseMessage,
+    GetCurrentNetworkResponseMessage, GetInfoResponseMessage, GetMetricsResponseMessage, GetSelectedTipHashResponseMessage,
+    KaspadRequest, KaspadResponse, NotifyBlockAddedRequestMessage, NotifyBlockAddedResponseMessage, PingResponseMessage,
+    ShutDownResponseMessage, SubmitBlockResponseMessage,
+};
+use async_std::channel::{bounded, Receiver, Sender};
+use kaspa_core::{debug, trace, warn};
+use rpc_core::{
+    api::{ops::RpcApiOps, rpc::RpcApi as RpcApiT},
+    notify::{listener::ListenerID, notifier::Notifier},
+    server::service::RpcApi,
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::{sync::OwnedSemaphorePermit, task::JoinHandle};
+
+/// A request waiting in the queue of a [`WorkerPool`]
+pub(crate) struct Job {
+    request: KaspadRequest,
+    listener_id: ListenerID,
+    sender: GrpcSender,
+
+    /// Slot of the connection in-flight limit, released once the response is sent
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Job {
+    pub(crate) fn new(request: KaspadRequest, listener_id: ListenerID, sender: GrpcSender, permit: OwnedSemaphorePermit) -> Self {
+        Self { request, listener_id, sender, _permit: permit }
+    }
+}
+
+/// A pool of workers processing the requests of all connections
+///
+/// Requests are queued in a bounded channel shared by all the workers, so a connection
+/// pushing requests faster than they get processed is eventually put on hold.
+/// Each worker sends its response back as soon as it is ready, tagged with the id
+/// of the request, so responses of a connection may be sent out of order.
+///
+/// Subscription commands are not processed by the pool, see [`handle_subscription`].
+pub(crate) struct WorkerPool {
+    worker_count: usize,
+    send_queue: Sender<Job>,
+    recv_queue: Receiver<Job>,
+    workers: Mutex<Vec<JoinHandle<()>>>,
+    core_service: Arc<RpcApi>,
+    notifier: Arc<Notifier>,
+}
+
+impl WorkerPool {
+    pub(crate) fn new(worker_count: usize, queue_capacity: usize, core_service: Arc<RpcApi>, notifier: Arc<Notifier>) -> Self {
+        let (send_queue, recv_queue) = bounded(queue_capacity.max(1));
+        Self { worker_count: worker_count.max(1), send_queue, recv_queue, workers: Mutex::new(vec![]), core_service, notifier }
+    }
+
+    pub(crate) fn start(&self) {
+        let mut workers = self.workers.lock().unwrap();
+        for worker_id in workers.len()..self.worker_count {
+            workers.push(self.worker_task(worker_id));
+        }
+    }
+
+    /// Queues a job, waiting for some room in the queue if it is full.
+    ///
+    /// Fails if the pool is stopped.
+    pub(crate) async fn enqueue(&self, job: Job) -> Result<(), Job> {
+        self.send_queue.send(job).await.map_err(|err| err.into_inner())
+    }
+
+    /// Stops accepting jobs and waits for the workers to process the queued ones.
+    pub(crate) async fn stop(&self) {
+        self.send_queue.close();
+        let workers: Vec<JoinHandle<()>> = self.workers.lock().unwrap().drain(..).collect();
+        for worker in workers {
+            let _ = worker.await;
+        }
+    }
+
+    /// Stops accepting jobs and lets the workers process the queued ones within `deadline`.
+    ///
+    /// Past the deadline, the workers are aborted and the remaining jobs are dropped without
+    /// any response. Returns true if all jobs were processed.
+    pub(crate) async fn stop_with_deadline(&self, deadline: Duration) -> bool {
+        self.send_queue.close();
+        let mut workers: Vec<JoinHandle<()>> = self.workers.lock().unwrap().drain(..).collect();
+        let drain = async {
+            for worker in workers.iter_mut() {
+                let _ = worker.await;
+            }
+        };
+        if tokio::time::timeout(deadline, drain).await.is_ok() {
+            return true;
+        }
+        workers.iter().for_each(|worker| worker.abort());
+        while self.recv_queue.try_recv().is_ok() {}
+        false
+    }
+
+    fn worker_task(&self, worker_id: usize) -> JoinHandle<()> {
+        let recv_queue = self.recv_queue.clone();
+        let core_service = self.core_service.clone();
+        let notifier = self.notifier.clone();
+
+        tokio::spawn(async move {
+            debug!("worker {0}: start", worker_id);
+            while let Ok(job) = recv_queue.recv().await {
+                trace!("worker {0}: processing request {1}", worker_id, job.request.id);
+                let started = Instant::now();
+                let op = job.request.payload.as_ref().map(RpcApiOps::from);
+                let mut response = handle_request(&core_service, &notifier, job.listener_id, &job.request).await;
+                response.id = job.request.id;
+
+                if let Some(op) = op {
+                    core_service.metrics().record_call(op, started.elapsed(), response.error().is_some());
+                }
+
+                if let Err(err) = job.sender.send(Ok(response)).await {
+                    warn!("worker {0}: response send error: {1:?}", worker_id, err);
+                }
+            }
+            debug!("worker {0}: stop", worker_id);
+        })
+    }
+}
+
+/// Executes the request right away if it is a subscription command, returning its response.
+///
+/// Subscription commands change the state of a connection, so the request handler of the connection
+/// executes them itself, in stream order, instead of queueing them in the [`WorkerPool`] where a later
+/// command could be processed first by another worker.
+pub(crate) fn handle_subscription(
+    core_service: &RpcApi,
+    notifier: &Arc<Notifier>,
+    listener_id: ListenerID,
+    request: &KaspadRequest,
+) -> Option<KaspadResponse> {
+    let mut response = match request.payload {
+        Some(Payload::NotifyBlockAddedRequest(ref request)) => {
+            let started = Instant::now();
+            let response = notify_block_added(notifier, listener_id, request);
+            core_service.metrics().record_call(RpcApiOps::NotifyBlockAdded, started.elapsed(), response.error().is_some());
+            response
+        }
+        _ => return None,
+    };
+    response.id = request.id;
+    Some(response)
+}
+
+fn notify_block_added(notifier: &Arc<Notifier>, listener_id: ListenerID, request: &NotifyBlockAddedRequestMessage) -> KaspadResponse {
+    NotifyBlockAddedResponseMessage::from({
+        let request = rpc_core::NotifyBlockAddedRequest::try_from(request).unwrap();
+        notifier.clone().execute_notify_command(listener_id, rpc_core::NotificationType::BlockAdded, request.command)
+    })
+    .into()
+}
+
+async fn handle_request(
+    core_service: &RpcApi,
+    notifier: &Arc<Notifier>,
+    listener_id: ListenerID,
+    request: &KaspadRequest,
+) -> KaspadResponse {
+    match request.payload {
+        Some(Payload::PingRequest(ref request)) => match request.try_into() {
+            Ok(request) => core_service.ping(request).await.into(),
+            Err(err) => PingResponseMessage::from(err).into(),
+        },
+
+        Some(Payload::GetCurrentNetworkRequest(ref request)) => match request.try_into() {
+            Ok(request) => core_service.get_current_network(request).await.into(),
+            Err(err) => GetCurrentNetworkResponseMessage::from(err).into(),
+        },
+
+        Some(Payload::SubmitBlockRequest(ref request)) => match request.try_into() {
+            Ok(request) => core_service.submit_block(request).await.into(),
+            Err(err) => SubmitBlockResponseMessage::from(err).into(),
+        },
+
+        Some(Payload::GetSelectedTipHashRequest(ref request)) => match request.try_into() {
+            Ok(request) => core_service.get_selected_tip_hash(request).await.into(),
+            Err(err) => GetSelectedTipHashResponseMessage::from(err).into(),
+        },
+
+        Some(Payload::GetBlockRequest(ref request)) => match request.try_into() {
+            Ok(request) => core_service.get_block(request).await.into(),
+            Err(err) => GetBlockResponseMessage::from(err).into(),
+        },
+
+        Some(Payload::GetInfoRequest(ref request)) => match request.try_into() {
+            Ok(request) => core_service.get_info(request).await.into(),
+            Err(err) => GetInfoResponseMessage::from(err).into(),
+        },
+
+        Some(Payload::EstimateNetworkHashesPerSecondRequest(ref request)) => match request.try_into() {
+            Ok(request) => core_service.estimate_network_hashes_per_second(request).await.into(),
+            Err(err) => EstimateNetworkHashesPerSecondResponseMessage::from(err).into(),
+        },
+
+        Some(Payload::GetCoinSupplyRequest(ref request)) => match request.try_into() {
+            Ok(request) => core_service.get_coin_supply(request).await.into(),
+            Err(err) => GetCoinSupplyResponseMessage::from(err).into(),
+        },
+
+        Some(Payload::GetMetricsRequest(ref request)) => match request.try_into() {
+            Ok(request) => core_service.get_metrics(request).await.into(),
+            Err(err) => GetMetricsResponseMessage::from(err).into(),
+        },
+
+        Some(Payload::ShutDownRequest(ref request)) => match request.try_into() {
+            Ok(request) => core_service.shutdown(request).await.into(),
+            Err(err) => ShutDownResponseMessage::from(err).into(),
+        },
+
+        Some(Payload::NotifyBlockAddedRequest(ref request)) => notify_block_added(notifier, listener_id, request),
+
+        None => GetBlockResponseMessage::from(rpc_core::RpcError::General("Request without payload".to_string())).into(),
+    }
+}
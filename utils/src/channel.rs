@@ -27,6 +27,15 @@ impl<T> Channel<T> {
     pub fn is_closed(&self) -> bool {
         self.receiver.is_closed()
     }
+
+    /// Number of messages currently queued in the channel
+    pub fn len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.receiver.is_empty()
+    }
 }
 
 /// Default for a [`Channel<T>`] is unbounded